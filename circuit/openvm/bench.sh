@@ -16,10 +16,14 @@ measure_peak_memory() {
 
 mkdir -p report
 
+INSTANTIATION=${INSTANTIATION:-poseidon2}
+FIELD=${FIELD:-koala-bear}
+HASH=${HASH:-keccak}
+
 for R in 1 2 3; do for T in 4 8 16 24; do
     export RAYON_NUM_THREADS=$T
     OUTPUT="report/r${R}_t${T}"
-    RUN="cargo run --quiet --profile bench --example hash-sig-agg -- --log-signatures 13 --log-blowup $R"
+    RUN="cargo run --quiet --profile bench --example hash-sig-agg -- --log-signatures 13 --log-blowup $R --instantiation $INSTANTIATION --field $FIELD --hash $HASH"
     $RUN > $OUTPUT
     measure_peak_memory $RUN >> $OUTPUT
 done done
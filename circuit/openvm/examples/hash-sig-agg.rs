@@ -1,8 +1,8 @@
-use clap::Parser;
-use core::{fmt::Write, iter::zip};
-use hash_sig_agg_circuit_openvm::{
-    poseidon2::{chip::generate_air_proof_inputs, E, F},
-    util::engine::Engine,
+use clap::{error::ErrorKind, CommandFactory, Parser};
+use core::{fmt::Write, iter::zip, num::NonZeroUsize};
+use hash_sig_agg_circuit_openvm::util::{
+    engine::{Engine, Keccak, Poseidon2},
+    key::{fingerprint, keygen, load_pk, save_pk, KeyParameters},
 };
 use hash_sig_testdata::mock_vi;
 use metrics::Key;
@@ -13,7 +13,10 @@ use metrics_util::{
     CompositeKey, MetricKind,
 };
 use openvm_stark_backend::{engine::StarkEngine, prover::types::ProofInput};
-use std::time::{Duration, Instant};
+use std::{
+    path::PathBuf,
+    time::{Duration, Instant},
+};
 use tracing_forest::{util::LevelFilter, ForestLayer};
 use tracing_subscriber::{prelude::*, EnvFilter, Registry};
 
@@ -26,69 +29,253 @@ struct Args {
     log_signatures: usize,
     #[arg(long, short = 'p', default_value_t = 0)]
     proof_of_work_bits: usize,
+    #[arg(long, short = 'i', value_enum, default_value_t = Instantiation::Poseidon2)]
+    instantiation: Instantiation,
+    /// Field of the circuit, SHA3 instantiations support only KoalaBear.
+    #[arg(long, short = 'f', value_enum, default_value_t = Field::KoalaBear)]
+    field: Field,
+    #[arg(long, value_enum, default_value_t = Hash::Keccak)]
+    hash: Hash,
+    /// Layout of Merkle tree AIRs, only for Poseidon2 instantiation.
+    #[arg(long, value_enum, default_value_t = MerkleLayout::Auto)]
+    merkle_layout: MerkleLayout,
+    /// Number of chain compressions in each row, only for Poseidon2
+    /// instantiation, planned with the least padded trace area if not given.
+    #[arg(long)]
+    chain_compressions_per_row: Option<NonZeroUsize>,
+    /// Path to load proving key from, or to save generated one to if not exists.
+    /// Loading fails if the key is generated with other parameters.
+    #[arg(long)]
+    pk: Option<PathBuf>,
 }
 
-fn main() {
-    let args: Args = Parser::parse();
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+enum Instantiation {
+    Poseidon2,
+    Keccak256,
+    Sha3_256,
+}
 
-    let engine = Engine::<F, E>::new(args.log_blowup, args.proof_of_work_bits);
-    let vi = mock_vi(1 << args.log_signatures);
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+enum Field {
+    BabyBear,
+    KoalaBear,
+}
 
-    let pk = {
-        let (airs, _) = generate_air_proof_inputs(args.log_blowup, vi.clone());
-        let mut keygen_builder = engine.keygen_builder();
-        engine.set_up_keygen_builder(&mut keygen_builder, &airs);
-        keygen_builder.generate_pk()
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+enum Hash {
+    Keccak,
+    Poseidon2,
+}
+
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+enum MerkleLayout {
+    /// The one with the least padded trace area.
+    Auto,
+    Fused,
+    Split,
+}
+
+macro_rules! run {
+    (@layout $field:ident, $num_sigs:expr, $args:expr) => {{
+        use hash_sig_agg_circuit_openvm::poseidon2::$field::chip::{
+            plan::plan, Layout, MerkleLayout as Merkle,
+        };
+        let planned = plan($num_sigs, $num_sigs).layout;
+        Layout {
+            merkle: match $args.merkle_layout {
+                MerkleLayout::Auto => planned.merkle,
+                MerkleLayout::Fused => Merkle::Fused,
+                MerkleLayout::Split => Merkle::Split,
+            },
+            chain_compressions_per_row: $args
+                .chain_compressions_per_row
+                .unwrap_or(planned.chain_compressions_per_row),
+            ..planned
+        }
+    }};
+    (@airs poseidon2::$field:ident, $num_sigs:expr, $args:expr) => {
+        airs($num_sigs, run!(@layout $field, $num_sigs, $args))
+    };
+    (@airs sha3::$field:ident, $num_sigs:expr, $args:expr) => {
+        airs()
     };
+    (@describe poseidon2::$field:ident, $num_sigs:expr, $args:expr) => {
+        format!(
+            "poseidon2::{} num_sigs: {}, {:?}",
+            stringify!($field),
+            $num_sigs,
+            run!(@layout $field, $num_sigs, $args),
+        )
+    };
+    (@describe sha3::$field:ident, $num_sigs:expr, $args:expr) => {
+        format!("sha3::{} num_sigs: {}", stringify!($field), $num_sigs)
+    };
+    (@estimate poseidon2::$field:ident, $extra_capacity_bits:expr, $num_sigs:expr, $args:expr) => {{
+        use hash_sig_agg_circuit_openvm::poseidon2::$field::chip::{
+            estimate_peak_memory, DEFAULT_CHUNK_SIZE,
+        };
+        let peak_memory = estimate_peak_memory(
+            $extra_capacity_bits,
+            $num_sigs,
+            $num_sigs,
+            DEFAULT_CHUNK_SIZE,
+            run!(@layout $field, $num_sigs, $args),
+        );
+        eprintln!("estimated witgen peak memory: {}", human_size(peak_memory));
+    }};
+    (@estimate sha3::$field:ident, $extra_capacity_bits:expr, $num_sigs:expr, $args:expr) => {};
+    (@generate poseidon2::$field:ident, $extra_capacity_bits:expr, $vi:expr, $args:expr) => {{
+        use hash_sig_agg_circuit_openvm::poseidon2::$field::chip::{
+            generate_air_proof_inputs_in_chunks, DEFAULT_CHUNK_SIZE,
+        };
+        let vi = $vi;
+        let participation = vec![true; vi.pairs.len()];
+        let layout = run!(@layout $field, vi.pairs.len(), $args);
+        generate_air_proof_inputs_in_chunks(
+            $extra_capacity_bits,
+            vi,
+            &participation,
+            DEFAULT_CHUNK_SIZE,
+            layout,
+        )
+    }};
+    (@generate sha3::$field:ident, $extra_capacity_bits:expr, $vi:expr, $args:expr) => {{
+        use hash_sig_agg_circuit_openvm::sha3::$field::chip::generate_air_proof_inputs;
+        generate_air_proof_inputs($extra_capacity_bits, $vi)
+    }};
+    ($instantiation:ident::$field:ident, $hash:ident, $args:ident) => {{
+        use hash_sig_agg_circuit_openvm::$instantiation::$field::{chip::airs, E, F};
+        let args = $args;
+
+        let engine = Engine::<F, E, $hash>::with_hash(args.log_blowup, args.proof_of_work_bits);
+        let vi = mock_vi(1 << args.log_signatures);
 
-    // Warm up
-    {
-        let mut elapsed = Duration::default();
-        while elapsed.as_secs() < 3 {
-            let start = Instant::now();
-            let (_, inputs) = generate_air_proof_inputs(args.log_blowup, vi.clone());
-            engine.prove(&pk, ProofInput::new(zip(0.., inputs.clone()).collect()));
-            elapsed += start.elapsed();
+        let params = KeyParameters::new(
+            &engine,
+            run!(@describe $instantiation::$field, vi.pairs.len(), args),
+        );
+        let pk = match &args.pk {
+            Some(path) if path.exists() => match load_pk(path, &params) {
+                Ok(pk) => pk,
+                Err(err) => {
+                    eprintln!("error: failed to load proving key: {err}");
+                    std::process::exit(1);
+                }
+            },
+            _ => {
+                let pk = keygen(&engine, &run!(@airs $instantiation::$field, vi.pairs.len(), args));
+                if let Some(path) = &args.pk {
+                    if let Err(err) = save_pk(path, &params, &pk) {
+                        eprintln!("error: failed to save proving key: {err}");
+                        std::process::exit(1);
+                    }
+                }
+                pk
+            }
+        };
+        eprintln!("vk fingerprint: {}", hex(fingerprint(&pk.get_vk())));
+        run!(@estimate $instantiation::$field, args.log_blowup, vi.pairs.len(), args);
+
+        // Warm up
+        {
+            let mut elapsed = Duration::default();
+            while elapsed.as_secs() < 3 {
+                let start = Instant::now();
+                let (_, inputs) =
+                    run!(@generate $instantiation::$field, args.log_blowup, vi.clone(), args);
+                engine.prove(&pk, ProofInput::new(zip(0.., inputs.clone()).collect()));
+                elapsed += start.elapsed();
+            }
         }
-    }
 
-    let env_filter = EnvFilter::builder()
-        .with_default_directive(LevelFilter::WARN.into())
-        .from_env_lossy();
-
-    Registry::default()
-        .with(env_filter)
-        .with(ForestLayer::default())
-        .init();
-
-    let recorder = DebuggingRecorder::new();
-    let snapshotter = recorder.snapshotter();
-    let recorder = TracingContextLayer::all().layer(recorder);
-    metrics::set_global_recorder(recorder).unwrap();
-
-    let start = Instant::now();
-    let (_, inputs) = generate_air_proof_inputs(args.log_blowup, vi);
-    let witgen_time = start.elapsed();
-    let proof = engine.prove(&pk, ProofInput::new(zip(0.., inputs).collect()));
-    let proving_time = start.elapsed();
-    let proving_time_parts = proving_time_parts(proving_time, witgen_time, snapshotter.snapshot());
-
-    let start = Instant::now();
-    engine.verify(&pk.get_vk(), &proof).unwrap();
-    let verifying_time = start.elapsed();
-
-    let throughput = f64::from(1 << args.log_signatures) / proving_time.as_secs_f64();
-    let proving_time = human_time(proving_time);
-    let proof_size = human_size(bincode::serialize(&proof).unwrap().len());
-    let verifying_time = human_time(verifying_time);
-
-    println!(
-        r"proving time: {proving_time}
+        let env_filter = EnvFilter::builder()
+            .with_default_directive(LevelFilter::WARN.into())
+            .from_env_lossy();
+
+        Registry::default()
+            .with(env_filter)
+            .with(ForestLayer::default())
+            .init();
+
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        let recorder = TracingContextLayer::all().layer(recorder);
+        metrics::set_global_recorder(recorder).unwrap();
+
+        let start = Instant::now();
+        let (_, inputs) = run!(@generate $instantiation::$field, args.log_blowup, vi, args);
+        let witgen_time = start.elapsed();
+        let proof = engine.prove(&pk, ProofInput::new(zip(0.., inputs).collect()));
+        let proving_time = start.elapsed();
+        let proving_time_parts =
+            proving_time_parts(proving_time, witgen_time, snapshotter.snapshot());
+
+        let start = Instant::now();
+        engine.verify(&pk.get_vk(), &proof).unwrap();
+        let verifying_time = start.elapsed();
+
+        let log_max_height = proof
+            .per_air
+            .iter()
+            .map(|air_proof| air_proof.degree.ilog2() as usize)
+            .max()
+            .unwrap();
+        if let Err(err) = engine.security_report(log_max_height) {
+            eprintln!("error: {err}");
+            std::process::exit(1);
+        }
+
+        let throughput = f64::from(1 << args.log_signatures) / proving_time.as_secs_f64();
+        let proving_time = human_time(proving_time);
+        let proof_size = human_size(bincode::serialize(&proof).unwrap().len());
+        let verifying_time = human_time(verifying_time);
+
+        println!(
+            r"proving time: {proving_time}
 {proving_time_parts}
 throughput: {throughput:.2} sig/s
 proof_size: {proof_size}
 verifying time: {verifying_time}",
-    );
+        );
+    }};
+}
+
+fn main() {
+    let args: Args = Parser::parse();
+
+    match (args.instantiation, args.field, args.hash) {
+        (Instantiation::Poseidon2, Field::BabyBear, Hash::Keccak) => {
+            run!(poseidon2::baby_bear, Keccak, args)
+        }
+        (Instantiation::Poseidon2, Field::BabyBear, Hash::Poseidon2) => {
+            run!(poseidon2::baby_bear, Poseidon2, args)
+        }
+        (Instantiation::Poseidon2, Field::KoalaBear, Hash::Keccak) => {
+            run!(poseidon2::koala_bear, Keccak, args)
+        }
+        (Instantiation::Poseidon2, Field::KoalaBear, Hash::Poseidon2) => {
+            run!(poseidon2::koala_bear, Poseidon2, args)
+        }
+        (Instantiation::Keccak256, Field::KoalaBear, Hash::Keccak) => {
+            run!(sha3::keccak256, Keccak, args)
+        }
+        (Instantiation::Keccak256, Field::KoalaBear, Hash::Poseidon2) => {
+            run!(sha3::keccak256, Poseidon2, args)
+        }
+        (Instantiation::Sha3_256, Field::KoalaBear, Hash::Keccak) => {
+            run!(sha3::sha3_256, Keccak, args)
+        }
+        (Instantiation::Sha3_256, Field::KoalaBear, Hash::Poseidon2) => {
+            run!(sha3::sha3_256, Poseidon2, args)
+        }
+        (Instantiation::Keccak256 | Instantiation::Sha3_256, Field::BabyBear, _) => Args::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "SHA3 instantiations support only --field koala-bear",
+            )
+            .exit(),
+    }
 }
 
 fn proving_time_parts(proving: Duration, witgen: Duration, snapshot: Snapshot) -> String {
@@ -151,3 +338,10 @@ pub fn human_size(size: usize) -> String {
         format!("{:.2} MB", size as f64 / 2f64.powi(20))
     }
 }
+
+fn hex(bytes: impl IntoIterator<Item = u8>) -> String {
+    bytes.into_iter().fold(String::new(), |mut s, byte| {
+        write!(&mut s, "{byte:02x}").unwrap();
+        s
+    })
+}
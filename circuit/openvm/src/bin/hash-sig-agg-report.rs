@@ -0,0 +1,103 @@
+//! Prints per-AIR cost and constraint report of the Poseidon2 circuit as JSON.
+//!
+//! The report doesn't depend on witness, so it's cheap to compare across
+//! changes of column layouts or constraints.
+
+use clap::Parser;
+use core::num::NonZeroUsize;
+use hash_sig_agg_circuit_openvm::util::{
+    engine::Engine,
+    key::keygen,
+    report::{air_reports, AirReport},
+};
+use serde::Serialize;
+
+#[derive(Clone, Debug, clap::Parser)]
+#[command(version, about)]
+struct Args {
+    #[arg(long, short = 'l', default_value_t = 13)]
+    log_signatures: usize,
+    #[arg(long, short = 'f', value_enum, default_value_t = Field::KoalaBear)]
+    field: Field,
+    /// Layout of Merkle tree AIRs, `auto` picks the one with the least padded
+    /// trace area.
+    #[arg(long, value_enum, default_value_t = MerkleLayout::Auto)]
+    merkle_layout: MerkleLayout,
+    /// Number of chain compressions in each row, planned with the least padded
+    /// trace area if not given.
+    #[arg(long)]
+    chain_compressions_per_row: Option<NonZeroUsize>,
+}
+
+#[derive(Clone, Copy, Debug, clap::ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
+enum Field {
+    BabyBear,
+    KoalaBear,
+}
+
+#[derive(Clone, Copy, Debug, clap::ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
+enum MerkleLayout {
+    Auto,
+    Fused,
+    Split,
+}
+
+#[derive(Debug, Serialize)]
+struct Report {
+    field: Field,
+    merkle_layout: MerkleLayout,
+    chain_compressions_per_row: usize,
+    chain_instances: usize,
+    num_sigs: usize,
+    airs: Vec<AirReport>,
+}
+
+macro_rules! run {
+    ($field:ident, $args:ident) => {{
+        use hash_sig_agg_circuit_openvm::poseidon2::$field::{
+            chip::{airs, plan::plan, trace_heights, Layout, MerkleLayout as Merkle},
+            E, F,
+        };
+        let args = $args;
+
+        let num_sigs = 1 << args.log_signatures;
+        let planned = plan(num_sigs, num_sigs).layout;
+        let (merkle_layout, merkle) = match args.merkle_layout {
+            MerkleLayout::Auto => match planned.merkle {
+                Merkle::Fused => (MerkleLayout::Fused, Merkle::Fused),
+                Merkle::Split => (MerkleLayout::Split, Merkle::Split),
+            },
+            MerkleLayout::Fused => (MerkleLayout::Fused, Merkle::Fused),
+            MerkleLayout::Split => (MerkleLayout::Split, Merkle::Split),
+        };
+        let chain_compressions_per_row = args
+            .chain_compressions_per_row
+            .unwrap_or(planned.chain_compressions_per_row);
+        let layout = Layout {
+            merkle,
+            chain_compressions_per_row,
+            ..planned
+        };
+        let pk = keygen(&Engine::<F, E>::fastest(), &airs(num_sigs, layout));
+        Report {
+            field: args.field,
+            merkle_layout,
+            chain_compressions_per_row: chain_compressions_per_row.get(),
+            chain_instances: layout.chain_instances.get(),
+            num_sigs,
+            airs: air_reports(&pk, &trace_heights(num_sigs, num_sigs, layout)),
+        }
+    }};
+}
+
+fn main() {
+    let args: Args = Parser::parse();
+
+    let report = match args.field {
+        Field::BabyBear => run!(baby_bear, args),
+        Field::KoalaBear => run!(koala_bear, args),
+    };
+    println!("{}", serde_json::to_string_pretty(&report).unwrap());
+}
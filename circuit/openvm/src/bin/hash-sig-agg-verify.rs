@@ -0,0 +1,150 @@
+//! Verifies an aggregate proof produced by `Aggregator`.
+//!
+//! The verifying key and signer list, or the registry commitment of
+//! `SignerRegistry::commitment` with `--threshold` for keys of registry layout,
+//! are read as saved by `util::key::save`, and the proof as encoded by
+//! `AggregateProof::encode`. FRI parameters are read from the header of the
+//! proof.
+//!
+//! It only depends on the verifier, so it can be built without trace
+//! generation by `--no-default-features --features cli`.
+//!
+//! Exits with `0` if the proof is valid, `1` if invalid, and `2` if inputs
+//! can't be read.
+
+use clap::Parser;
+use hash_sig_agg_circuit_openvm::util::key::load;
+use std::{fs, path::PathBuf, process::ExitCode};
+
+#[derive(Clone, Debug, clap::Parser)]
+#[command(version, about)]
+struct Args {
+    /// Path to verifying key.
+    #[arg(long)]
+    vk: PathBuf,
+    /// Path to encoded aggregate proof.
+    #[arg(long)]
+    proof: PathBuf,
+    /// Path to list of public keys of signers.
+    #[arg(long, required_unless_present = "registry")]
+    signers: Option<PathBuf>,
+    /// Path to registry commitment of signers, instead of their public keys.
+    #[arg(long, conflicts_with = "signers", requires = "threshold")]
+    registry: Option<PathBuf>,
+    /// Minimum weight of participating signers in the registry.
+    #[arg(long, requires = "registry")]
+    threshold: Option<u32>,
+    #[arg(long)]
+    epoch: u32,
+    /// Message in hex.
+    #[arg(long, value_parser = parse_msg)]
+    msg: Vec<u8>,
+    #[arg(long, short = 'f', value_enum, default_value_t = Field::KoalaBear)]
+    field: Field,
+}
+
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+enum Field {
+    BabyBear,
+    KoalaBear,
+}
+
+macro_rules! run {
+    ($field:ident, $args:ident) => {{
+        use hash_sig_agg_circuit_openvm::poseidon2::$field::{
+            aggregator::{RegistryCommitment, Verifier},
+            chip::SignerSetLayout,
+            hash_sig::{PublicKey, MSG_LEN},
+            proof::AggregateProof,
+        };
+        let args = $args;
+
+        let Ok(msg) = <[u8; MSG_LEN]>::try_from(args.msg.as_slice()) else {
+            return error(format!("message should be {MSG_LEN} bytes"));
+        };
+        let vk = match load(&args.vk) {
+            Ok(vk) => vk,
+            Err(err) => return error(format!("failed to load verifying key: {err}")),
+        };
+        let proof = match fs::read(&args.proof) {
+            Ok(bytes) => match AggregateProof::decode(&bytes) {
+                Ok(proof) => proof,
+                Err(err) => return invalid(err),
+            },
+            Err(err) => return error(format!("failed to read proof: {err}")),
+        };
+
+        let result = match (args.signers, args.registry, args.threshold) {
+            (Some(signers), None, None) => {
+                let public_keys: Vec<PublicKey> = match load(&signers) {
+                    Ok(public_keys) => public_keys,
+                    Err(err) => return error(format!("failed to load signers: {err}")),
+                };
+                Verifier::new(SignerSetLayout::Sponge, vk).verify(
+                    &proof,
+                    args.epoch,
+                    msg,
+                    &public_keys,
+                )
+            }
+            (None, Some(registry), Some(threshold)) => {
+                let registry_commitment: RegistryCommitment = match load(&registry) {
+                    Ok(registry_commitment) => registry_commitment,
+                    Err(err) => return error(format!("failed to load registry: {err}")),
+                };
+                Verifier::new(SignerSetLayout::Registry, vk).verify_with_registry(
+                    &proof,
+                    args.epoch,
+                    msg,
+                    &registry_commitment,
+                    threshold,
+                )
+            }
+            _ => unreachable!("signer set arguments are validated by clap"),
+        };
+        match result {
+            Ok(participation) => {
+                let num_participating = participation.iter().filter(|p| **p).count();
+                println!(
+                    "valid: {num_participating} of {} signers participating",
+                    participation.len()
+                );
+                ExitCode::SUCCESS
+            }
+            Err(err) => invalid(err),
+        }
+    }};
+}
+
+fn main() -> ExitCode {
+    let args: Args = Parser::parse();
+
+    match args.field {
+        Field::BabyBear => run!(baby_bear, args),
+        Field::KoalaBear => run!(koala_bear, args),
+    }
+}
+
+fn invalid(reason: impl std::fmt::Display) -> ExitCode {
+    println!("invalid: {reason}");
+    ExitCode::FAILURE
+}
+
+fn error(reason: impl std::fmt::Display) -> ExitCode {
+    eprintln!("error: {reason}");
+    ExitCode::from(2)
+}
+
+fn parse_msg(hex: &str) -> Result<Vec<u8>, String> {
+    let hex = hex.strip_prefix("0x").unwrap_or(hex);
+    if !hex.is_ascii() {
+        return Err("invalid hex digit".to_string());
+    }
+    if hex.len() % 2 != 0 {
+        return Err("odd number of hex digits".to_string());
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|idx| u8::from_str_radix(&hex[idx..idx + 2], 16).map_err(|err| err.to_string()))
+        .collect()
+}
@@ -1,3 +1,5 @@
 pub mod gadget;
 pub mod poseidon2;
+#[cfg(feature = "prover")]
+pub mod sha3;
 pub mod util;
@@ -0,0 +1,873 @@
+use super::{
+    chip::{
+        air_idx, airs,
+        participation::{
+            num_words, participation_from_bitmap, MAX_PARTICIPATION_WORDS, PARTICIPATION_WORD_BITS,
+        },
+        plan::plan,
+        public_values, AirKind, Layout, SignerSetLayout,
+    },
+    hash_sig::{PublicKey, MSG_LEN},
+    proof::{AggregateProof, Incompatibility},
+    E, F,
+};
+use crate::util::{
+    engine::Config,
+    key::{fingerprint, Fingerprint},
+    security::InsufficientSecurity,
+    validation::ValidationReport,
+};
+use core::{fmt, iter::zip};
+use openvm_stark_backend::{config::Com, keygen::types::MultiStarkVerifyingKey};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "prover")]
+use {
+    super::{
+        chip::{
+            generate_air_proof_inputs_in_chunks, generate_air_proof_inputs_with_registry,
+            participating_weight,
+            registry::{is_valid_weights, Registry},
+            trace_heights, validate_participating, DEFAULT_CHUNK_SIZE,
+        },
+        hash_sig::VerificationInput,
+        proof::Header,
+    },
+    crate::util::{engine::Engine, key::keygen},
+    openvm_stark_backend::{
+        engine::StarkEngine, keygen::types::MultiStarkProvingKey, prover::types::ProofInput,
+    },
+};
+
+/// Proving key of [`Aggregator`].
+#[cfg(feature = "prover")]
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ProvingKey(pub(crate) MultiStarkProvingKey<Config<F, E>>);
+
+#[cfg(feature = "prover")]
+impl ProvingKey {
+    pub fn vk(&self) -> VerifyingKey {
+        VerifyingKey(self.0.get_vk())
+    }
+}
+
+/// Verifying key of [`Aggregator`], which can be saved and loaded to construct
+/// a [`Verifier`].
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct VerifyingKey(pub(crate) MultiStarkVerifyingKey<Config<F, E>>);
+
+impl VerifyingKey {
+    /// Returns the fingerprint recorded in [`Header`](super::proof::Header)
+    /// of proofs of this key.
+    pub fn fingerprint(&self) -> Fingerprint {
+        fingerprint(&self.0)
+    }
+}
+
+/// Reason a proof is rejected by the backend verifier.
+#[derive(Debug)]
+pub struct VerificationError(openvm_stark_backend::verifier::VerificationError);
+
+impl fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
+
+impl std::error::Error for VerificationError {}
+
+#[cfg(feature = "prover")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AggregatorConfig {
+    pub log_blowup: usize,
+    pub proof_of_work_bits: usize,
+    /// How public keys are bound, which changes the AIRs and so the keys.
+    pub signer_set: SignerSetLayout,
+}
+
+#[cfg(feature = "prover")]
+impl Default for AggregatorConfig {
+    fn default() -> Self {
+        Self {
+            log_blowup: 1,
+            proof_of_work_bits: 0,
+            signer_set: SignerSetLayout::default(),
+        }
+    }
+}
+
+/// Public keys and weights of all signers committed by
+/// [`Aggregator::commit_registry`], which can be saved and reused by
+/// [`Aggregator::prove_with_registry`] until the signer set changes.
+#[cfg(feature = "prover")]
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SignerRegistry(pub(crate) Registry<Config<F, E>>);
+
+#[cfg(feature = "prover")]
+impl SignerRegistry {
+    pub fn public_keys(&self) -> &[PublicKey] {
+        self.0.public_keys()
+    }
+
+    pub fn weights(&self) -> &[u32] {
+        self.0.weights()
+    }
+
+    pub fn commitment(&self) -> RegistryCommitment {
+        RegistryCommitment {
+            num_sigs: self.0.public_keys().len(),
+            total_weight: self.0.total_weight(),
+            commitment: self.0.commitment().clone(),
+        }
+    }
+}
+
+/// Commitment of a [`SignerRegistry`] with its number of public keys and
+/// total weight, which is all a verifier needs to know about the signer set
+/// with [`SignerSetLayout::Registry`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RegistryCommitment {
+    pub(crate) num_sigs: usize,
+    pub(crate) total_weight: u32,
+    pub(crate) commitment: Com<Config<F, E>>,
+}
+
+impl RegistryCommitment {
+    pub const fn num_sigs(&self) -> usize {
+        self.num_sigs
+    }
+
+    pub const fn total_weight(&self) -> u32 {
+        self.total_weight
+    }
+}
+
+#[derive(Debug)]
+pub enum AggregationError {
+    /// Number of signatures exceeds the participation bitmap capacity.
+    TooManySignatures { max: usize, actual: usize },
+    /// Number of signatures or public keys doesn't match the aggregator's.
+    NumSignatures { expected: usize, actual: usize },
+    /// Participation doesn't match number of signatures.
+    NumParticipation { expected: usize, actual: usize },
+    /// No signature is participating.
+    NoParticipation,
+    /// Total weight of signers is too large to be committed in a registry.
+    InvalidWeights,
+    /// Participating signatures weigh less than the threshold.
+    InsufficientWeight { threshold: u32, weight: u32 },
+    /// Some participating signatures are invalid.
+    InvalidSignatures(ValidationReport),
+    /// Proof is produced by another circuit or configuration.
+    Incompatible(Incompatibility),
+    /// Proof is about another epoch, message, registry or a lower threshold.
+    StatementMismatch,
+    /// Signer set is given in another way than the [`SignerSetLayout`] of
+    /// the aggregator.
+    SignerSetLayout { expected: SignerSetLayout },
+    /// Proof doesn't have the AIRs in expected order.
+    InvalidProofShape,
+    /// Public values of AIR at `air_idx` don't match the statement.
+    PublicValuesMismatch { air_idx: usize },
+    /// Proofs of the configuration are estimated to be less secure than
+    /// `SECURITY_BITS`.
+    InsufficientSecurity(InsufficientSecurity),
+    /// Proof fails to verify against the verifying key.
+    Verification(VerificationError),
+}
+
+impl fmt::Display for AggregationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooManySignatures { max, actual } => {
+                write!(f, "expected at most {max} signatures, got {actual}")
+            }
+            Self::NumSignatures { expected, actual } => {
+                write!(f, "expected {expected} signatures, got {actual}")
+            }
+            Self::NumParticipation { expected, actual } => {
+                write!(f, "expected {expected} participation flags, got {actual}")
+            }
+            Self::NoParticipation => write!(f, "no signature is participating"),
+            Self::InvalidWeights => write!(f, "total weight is too large"),
+            Self::InsufficientWeight { threshold, weight } => {
+                write!(f, "expected weight at least {threshold}, got {weight}")
+            }
+            Self::InvalidSignatures(report) => write!(f, "{report}"),
+            Self::Incompatible(incompatibility) => {
+                write!(f, "incompatible {incompatibility}")
+            }
+            Self::StatementMismatch => write!(f, "statement mismatch"),
+            Self::SignerSetLayout { expected } => {
+                write!(f, "expected signer set of layout {expected:?}")
+            }
+            Self::InvalidProofShape => write!(f, "invalid proof shape"),
+            Self::PublicValuesMismatch { air_idx } => {
+                write!(f, "public values of AIR {air_idx} mismatch")
+            }
+            Self::InsufficientSecurity(err) => write!(f, "{err}"),
+            Self::Verification(err) => write!(f, "verification failed: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for AggregationError {}
+
+/// Aggregates signatures of a fixed number of signers into a single proof.
+///
+/// The proving key is generated once in [`Aggregator::new`] without witness,
+/// and the AIRs are always in the order of [`airs`] with layout of
+/// [`layout`].
+///
+/// Proofs are checked to reach `SECURITY_BITS` with the tallest trace of the
+/// layout before keygen, see [`Engine::security_report`].
+#[cfg(feature = "prover")]
+pub struct Aggregator {
+    engine: Engine<F, E>,
+    num_sigs: usize,
+    layout: Layout,
+    pk: ProvingKey,
+}
+
+#[cfg(feature = "prover")]
+impl Aggregator {
+    pub fn new(config: AggregatorConfig, num_sigs: usize) -> Result<Self, AggregationError> {
+        check_num_sigs(num_sigs)?;
+        let engine = Engine::new(config.log_blowup, config.proof_of_work_bits);
+        let layout = layout(num_sigs, config.signer_set);
+        let log_max_height = trace_heights(num_sigs, num_sigs, layout)
+            .into_iter()
+            .map(|height| height.height.ilog2() as usize)
+            .max()
+            .unwrap();
+        engine
+            .security_report(log_max_height)
+            .map_err(AggregationError::InsufficientSecurity)?;
+        let pk = ProvingKey(keygen(&engine, &airs(num_sigs, layout)));
+        Ok(Self {
+            engine,
+            num_sigs,
+            layout,
+            pk,
+        })
+    }
+
+    pub const fn num_sigs(&self) -> usize {
+        self.num_sigs
+    }
+
+    pub fn vk(&self) -> VerifyingKey {
+        self.pk.vk()
+    }
+
+    /// Proves all signatures in `vi` are valid.
+    pub fn prove(&self, vi: VerificationInput) -> Result<AggregateProof, AggregationError> {
+        let participation = vec![true; vi.pairs.len()];
+        self.prove_with_participation(vi, &participation)
+    }
+
+    /// Proves signatures in `vi` flagged in `participation` are valid.
+    ///
+    /// Participating signatures are verified natively first, and invalid ones
+    /// or ones of duplicate signers are reported as
+    /// [`AggregationError::InvalidSignatures`] without proving.
+    pub fn prove_with_participation(
+        &self,
+        vi: VerificationInput,
+        participation: &[bool],
+    ) -> Result<AggregateProof, AggregationError> {
+        self.check_signer_set(SignerSetLayout::Sponge)?;
+        self.check_participation(vi.pairs.len(), participation)?;
+        validate_participating(&vi, participation).map_err(AggregationError::InvalidSignatures)?;
+        Ok(self.prove_validated(vi, participation, None))
+    }
+
+    /// Commits `public_keys` and `weights` of all signers once, to be reused
+    /// by [`Aggregator::prove_with_registry`] until the signer set changes.
+    pub fn commit_registry(
+        &self,
+        public_keys: Vec<PublicKey>,
+        weights: Vec<u32>,
+    ) -> Result<SignerRegistry, AggregationError> {
+        self.check_signer_set(SignerSetLayout::Registry)?;
+        check_len(self.num_sigs, public_keys.len(), |expected, actual| {
+            AggregationError::NumSignatures { expected, actual }
+        })?;
+        check_len(self.num_sigs, weights.len(), |expected, actual| {
+            AggregationError::NumSignatures { expected, actual }
+        })?;
+        if !is_valid_weights(&weights) {
+            return Err(AggregationError::InvalidWeights);
+        }
+        Ok(SignerRegistry(Registry::commit(
+            self.engine.config().pcs(),
+            public_keys,
+            weights,
+        )))
+    }
+
+    /// Same as [`Aggregator::prove_with_participation`], but public keys and
+    /// weights are looked up from `registry`, which should have the same
+    /// public keys as `vi`, and proves participating signatures weigh at
+    /// least `threshold`.
+    pub fn prove_with_registry(
+        &self,
+        vi: VerificationInput,
+        participation: &[bool],
+        registry: &SignerRegistry,
+        threshold: u32,
+    ) -> Result<AggregateProof, AggregationError> {
+        self.check_signer_set(SignerSetLayout::Registry)?;
+        self.check_participation(vi.pairs.len(), participation)?;
+        if registry.public_keys().len() != vi.pairs.len()
+            || zip(&vi.pairs, registry.public_keys()).any(|((pk, _), registered)| {
+                pk.parameter != registered.parameter || pk.merkle_root != registered.merkle_root
+            })
+        {
+            return Err(AggregationError::StatementMismatch);
+        }
+        let weight = participating_weight(participation, registry.weights());
+        if weight < threshold {
+            return Err(AggregationError::InsufficientWeight { threshold, weight });
+        }
+        validate_participating(&vi, participation).map_err(AggregationError::InvalidSignatures)?;
+        Ok(self.prove_validated(vi, participation, Some((&registry.0, threshold))))
+    }
+
+    fn check_signer_set(&self, expected: SignerSetLayout) -> Result<(), AggregationError> {
+        if self.layout.signer_set == expected {
+            Ok(())
+        } else {
+            Err(AggregationError::SignerSetLayout {
+                expected: self.layout.signer_set,
+            })
+        }
+    }
+
+    fn check_participation(
+        &self,
+        num_sigs: usize,
+        participation: &[bool],
+    ) -> Result<(), AggregationError> {
+        check_len(self.num_sigs, num_sigs, |expected, actual| {
+            AggregationError::NumSignatures { expected, actual }
+        })?;
+        check_len(self.num_sigs, participation.len(), |expected, actual| {
+            AggregationError::NumParticipation { expected, actual }
+        })?;
+        if !participation.contains(&true) {
+            return Err(AggregationError::NoParticipation);
+        }
+        Ok(())
+    }
+
+    fn prove_validated(
+        &self,
+        vi: VerificationInput,
+        participation: &[bool],
+        registry: Option<(&Registry<Config<F, E>>, u32)>,
+    ) -> AggregateProof {
+        let (epoch, msg) = (vi.epoch, vi.msg);
+        let (_, air_proof_inputs) = match registry {
+            Some((registry, threshold)) => generate_air_proof_inputs_with_registry(
+                self.engine.log_blowup(),
+                vi,
+                participation,
+                DEFAULT_CHUNK_SIZE,
+                self.layout,
+                registry,
+                threshold,
+            ),
+            None => generate_air_proof_inputs_in_chunks(
+                self.engine.log_blowup(),
+                vi,
+                participation,
+                DEFAULT_CHUNK_SIZE,
+                self.layout,
+            ),
+        };
+        // Every signer weighs `1` without registry.
+        let threshold = match registry {
+            Some((_, threshold)) => threshold,
+            None => participation.iter().filter(|p| **p).count() as u32,
+        };
+        let proof = self.engine.prove(
+            &self.pk.0,
+            ProofInput::new(zip(0.., air_proof_inputs).collect()),
+        );
+        AggregateProof {
+            header: Header::new(&self.engine, &self.vk()),
+            epoch,
+            msg,
+            threshold,
+            participation: participation.to_vec(),
+            proof,
+        }
+    }
+
+    /// Verifies `proof` aggregates signatures on `msg` at `epoch` of
+    /// `public_keys`, and returns which of them are participating.
+    pub fn verify(
+        &self,
+        vk: &VerifyingKey,
+        proof: &AggregateProof,
+        epoch: u32,
+        msg: [u8; MSG_LEN],
+        public_keys: &[PublicKey],
+    ) -> Result<Vec<bool>, AggregationError> {
+        self.check_signer_set(SignerSetLayout::Sponge)?;
+        check_len(self.num_sigs, public_keys.len(), |expected, actual| {
+            AggregationError::NumSignatures { expected, actual }
+        })?;
+        verify(vk, proof, epoch, msg, SignerSet::PublicKeys(public_keys))
+    }
+
+    /// Verifies `proof` aggregates signatures on `msg` at `epoch` of public
+    /// keys in the registry of `registry_commitment` weighing at least
+    /// `threshold`, and returns which of them are participating.
+    pub fn verify_with_registry(
+        &self,
+        vk: &VerifyingKey,
+        proof: &AggregateProof,
+        epoch: u32,
+        msg: [u8; MSG_LEN],
+        registry_commitment: &RegistryCommitment,
+        threshold: u32,
+    ) -> Result<Vec<bool>, AggregationError> {
+        self.check_signer_set(SignerSetLayout::Registry)?;
+        check_len(
+            self.num_sigs,
+            registry_commitment.num_sigs,
+            |expected, actual| AggregationError::NumSignatures { expected, actual },
+        )?;
+        verify(
+            vk,
+            proof,
+            epoch,
+            msg,
+            SignerSet::Registry(registry_commitment, threshold),
+        )
+    }
+}
+
+/// Verifies aggregate proofs against a verifying key without proving key.
+///
+/// Proofs are verified with FRI parameters in their
+/// [`Header`](super::proof::Header), which are checked to reach
+/// `SECURITY_BITS` instead of being configured.
+pub struct Verifier {
+    signer_set: SignerSetLayout,
+    vk: VerifyingKey,
+}
+
+impl Verifier {
+    pub const fn new(signer_set: SignerSetLayout, vk: VerifyingKey) -> Self {
+        Self { signer_set, vk }
+    }
+
+    pub const fn vk(&self) -> &VerifyingKey {
+        &self.vk
+    }
+
+    /// Verifies `proof` aggregates signatures on `msg` at `epoch` of
+    /// `public_keys`, and returns which of them are participating.
+    pub fn verify(
+        &self,
+        proof: &AggregateProof,
+        epoch: u32,
+        msg: [u8; MSG_LEN],
+        public_keys: &[PublicKey],
+    ) -> Result<Vec<bool>, AggregationError> {
+        self.verify_signer_set(proof, epoch, msg, SignerSet::PublicKeys(public_keys))
+    }
+
+    /// Verifies `proof` aggregates signatures on `msg` at `epoch` of public
+    /// keys in the registry of `registry_commitment` weighing at least
+    /// `threshold`, and returns which of them are participating.
+    pub fn verify_with_registry(
+        &self,
+        proof: &AggregateProof,
+        epoch: u32,
+        msg: [u8; MSG_LEN],
+        registry_commitment: &RegistryCommitment,
+        threshold: u32,
+    ) -> Result<Vec<bool>, AggregationError> {
+        self.verify_signer_set(
+            proof,
+            epoch,
+            msg,
+            SignerSet::Registry(registry_commitment, threshold),
+        )
+    }
+
+    fn verify_signer_set(
+        &self,
+        proof: &AggregateProof,
+        epoch: u32,
+        msg: [u8; MSG_LEN],
+        signer_set: SignerSet<'_>,
+    ) -> Result<Vec<bool>, AggregationError> {
+        if signer_set.layout() != self.signer_set {
+            return Err(AggregationError::SignerSetLayout {
+                expected: self.signer_set,
+            });
+        }
+        verify(&self.vk, proof, epoch, msg, signer_set)
+    }
+}
+
+/// Signer set a proof is verified against, with the threshold its
+/// participating weight should reach for registry.
+#[derive(Clone, Copy)]
+enum SignerSet<'a> {
+    PublicKeys(&'a [PublicKey]),
+    Registry(&'a RegistryCommitment, u32),
+}
+
+impl SignerSet<'_> {
+    const fn num_sigs(&self) -> usize {
+        match self {
+            Self::PublicKeys(public_keys) => public_keys.len(),
+            Self::Registry(registry_commitment, _) => registry_commitment.num_sigs,
+        }
+    }
+
+    const fn layout(&self) -> SignerSetLayout {
+        match self {
+            Self::PublicKeys(_) => SignerSetLayout::Sponge,
+            Self::Registry(..) => SignerSetLayout::Registry,
+        }
+    }
+}
+
+fn verify(
+    vk: &VerifyingKey,
+    proof: &AggregateProof,
+    epoch: u32,
+    msg: [u8; MSG_LEN],
+    signer_set: SignerSet<'_>,
+) -> Result<Vec<bool>, AggregationError> {
+    let num_sigs = signer_set.num_sigs();
+    check_num_sigs(num_sigs)?;
+    proof
+        .check_compatibility(vk)
+        .map_err(AggregationError::Incompatible)?;
+    let config = proof.header.verifier_config();
+    if (proof.epoch, proof.msg) != (epoch, msg) {
+        return Err(AggregationError::StatementMismatch);
+    }
+    let AggregateProof {
+        threshold: claimed_threshold,
+        participation: claimed_participation,
+        proof,
+        ..
+    } = proof;
+    let layout = layout(num_sigs, signer_set.layout());
+    let num_airs = airs::<Config<F, E>>(num_sigs, layout).len();
+    let participation_air_idx = air_idx(layout, AirKind::Participation).unwrap();
+    if proof.per_air.len() != num_airs
+        || proof
+            .per_air
+            .iter()
+            .enumerate()
+            .any(|(air_idx, air_proof)| air_proof.air_id != air_idx)
+    {
+        return Err(AggregationError::InvalidProofShape);
+    }
+    let log_max_height = proof
+        .per_air
+        .iter()
+        .map(|air_proof| air_proof.degree.ilog2() as usize)
+        .max()
+        .unwrap();
+    config
+        .security_report(log_max_height)
+        .map_err(AggregationError::InsufficientSecurity)?;
+    let participation = participation_from_bitmap(
+        &proof.per_air[participation_air_idx].public_values,
+        num_sigs,
+    )
+    .ok_or(AggregationError::PublicValuesMismatch {
+        air_idx: participation_air_idx,
+    })?;
+    if !participation.contains(&true) {
+        return Err(AggregationError::NoParticipation);
+    }
+    if participation != *claimed_participation {
+        return Err(AggregationError::StatementMismatch);
+    }
+    let (public_keys, total_weight): (&[PublicKey], _) = match signer_set {
+        // Every signer weighs `1` without registry.
+        SignerSet::PublicKeys(public_keys) => (public_keys, num_sigs as u32),
+        SignerSet::Registry(registry_commitment, threshold) => {
+            // Registry is the only AIR with cached trace, so its commitment
+            // is the first main trace commitment.
+            if proof.commitments.main_trace.first() != Some(&registry_commitment.commitment)
+                || *claimed_threshold < threshold
+            {
+                return Err(AggregationError::StatementMismatch);
+            }
+            (&[], registry_commitment.total_weight)
+        }
+    };
+    let public_values = public_values(
+        epoch,
+        msg,
+        *claimed_threshold,
+        total_weight,
+        &participation,
+        public_keys,
+        layout,
+    );
+    if let Some(air_idx) = zip(&proof.per_air, public_values)
+        .position(|(air_proof, public_values)| air_proof.public_values != public_values)
+    {
+        return Err(AggregationError::PublicValuesMismatch { air_idx });
+    }
+    config
+        .verify(&vk.0, proof)
+        .map_err(|err| AggregationError::Verification(VerificationError(err)))?;
+    Ok(participation)
+}
+
+/// Returns layout of AIRs aggregating `num_sigs` signatures with signer set
+/// in `signer_set`.
+///
+/// The proving key can't depend on participation, so it's planned with all
+/// signatures participating.
+fn layout(num_sigs: usize, signer_set: SignerSetLayout) -> Layout {
+    Layout {
+        signer_set,
+        ..plan(num_sigs, num_sigs).layout
+    }
+}
+
+#[cfg(feature = "prover")]
+fn check_len(
+    expected: usize,
+    actual: usize,
+    err: impl FnOnce(usize, usize) -> AggregationError,
+) -> Result<(), AggregationError> {
+    if expected == actual {
+        Ok(())
+    } else {
+        Err(err(expected, actual))
+    }
+}
+
+/// Checks participation bitmap of `num_sigs` signatures fits in
+/// `MAX_PARTICIPATION_WORDS` words.
+fn check_num_sigs(num_sigs: usize) -> Result<(), AggregationError> {
+    if num_words(num_sigs) > MAX_PARTICIPATION_WORDS {
+        return Err(AggregationError::TooManySignatures {
+            max: MAX_PARTICIPATION_WORDS * PARTICIPATION_WORD_BITS,
+            actual: num_sigs,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod test {
+    use_instance!({
+        aggregator::{
+            AggregationError, Aggregator, AggregatorConfig, RegistryCommitment, SignerRegistry,
+            Verifier,
+        },
+        chip::{participating_weight, SignerSetLayout},
+        hash_sig::test::mock_vi,
+        proof::{AggregateProof, DecodeError, Incompatibility},
+        F,
+    });
+    use crate::util::validation::InvalidReason;
+    use p3_field::FieldAlgebra;
+
+    #[test]
+    fn aggregator() {
+        let num_sigs = 13;
+        let aggregator = Aggregator::new(AggregatorConfig::default(), num_sigs).unwrap();
+        let vk = aggregator.vk();
+        let vi = mock_vi(num_sigs);
+        let (epoch, msg) = (vi.epoch, vi.msg);
+        let mut public_keys = vi.pairs.iter().map(|(pk, _)| *pk).collect::<Vec<_>>();
+        let participation = (0..num_sigs).map(|idx| idx % 4 != 2).collect::<Vec<_>>();
+        let proof = aggregator
+            .prove_with_participation(vi, &participation)
+            .unwrap();
+
+        assert_eq!(
+            aggregator
+                .verify(&vk, &proof, epoch, msg, &public_keys)
+                .unwrap(),
+            participation
+        );
+        assert!(matches!(
+            aggregator.verify(&vk, &proof, epoch + 1, msg, &public_keys),
+            Err(AggregationError::StatementMismatch)
+        ));
+        let mut forged = proof.clone();
+        forged.epoch += 1;
+        assert!(matches!(
+            aggregator.verify(&vk, &forged, epoch + 1, msg, &public_keys),
+            Err(AggregationError::PublicValuesMismatch { air_idx: 1 })
+        ));
+
+        let decoded = AggregateProof::decode(&proof.encode()).unwrap();
+        assert_eq!(
+            aggregator
+                .verify(&vk, &decoded, epoch, msg, &public_keys)
+                .unwrap(),
+            participation
+        );
+        let mut bytes = proof.encode();
+        bytes[4] += 1;
+        assert!(matches!(
+            AggregateProof::decode(&bytes),
+            Err(DecodeError::Version(_))
+        ));
+        let other = Aggregator::new(AggregatorConfig::default(), num_sigs + 1).unwrap();
+        assert!(matches!(
+            other.verify(&other.vk(), &proof, epoch, msg, &public_keys),
+            Err(AggregationError::NumSignatures { .. })
+        ));
+        public_keys.push(public_keys[0]);
+        assert!(matches!(
+            other.verify(&other.vk(), &proof, epoch, msg, &public_keys),
+            Err(AggregationError::Incompatible(
+                Incompatibility::VerifyingKey
+            ))
+        ));
+        public_keys.pop();
+        public_keys.swap(0, 1);
+        assert!(matches!(
+            aggregator.verify(&vk, &proof, epoch, msg, &public_keys),
+            Err(AggregationError::PublicValuesMismatch { air_idx })
+                if air_idx == aggregator.pk.0.per_air.len() - 1
+        ));
+        assert!(matches!(
+            aggregator.verify(&vk, &proof, epoch, msg, &public_keys[1..]),
+            Err(AggregationError::NumSignatures { .. })
+        ));
+        assert!(matches!(
+            Aggregator::new(AggregatorConfig::default(), (1 << 16) + 1),
+            Err(AggregationError::TooManySignatures {
+                max: 65536,
+                actual: 65537
+            })
+        ));
+    }
+
+    #[test]
+    fn aggregator_with_registry() {
+        let num_sigs = 13;
+        let config = AggregatorConfig {
+            signer_set: SignerSetLayout::Registry,
+            ..AggregatorConfig::default()
+        };
+        let aggregator = Aggregator::new(config, num_sigs).unwrap();
+        let verifier = Verifier::new(config.signer_set, aggregator.vk());
+        let vi = mock_vi(num_sigs);
+        let (epoch, msg) = (vi.epoch, vi.msg);
+        let mut public_keys = vi.pairs.iter().map(|(pk, _)| *pk).collect::<Vec<_>>();
+        let participation = (0..num_sigs).map(|idx| idx % 4 != 2).collect::<Vec<_>>();
+        let weights = (0..num_sigs as u32).map(|idx| idx % 5).collect::<Vec<_>>();
+        let weight = participating_weight(&participation, &weights);
+        let registry = aggregator
+            .commit_registry(public_keys.clone(), weights.clone())
+            .unwrap();
+        let registry: SignerRegistry =
+            bincode::deserialize(&bincode::serialize(&registry).unwrap()).unwrap();
+        let registry_commitment: RegistryCommitment =
+            bincode::deserialize(&bincode::serialize(&registry.commitment()).unwrap()).unwrap();
+        assert!(matches!(
+            aggregator.prove_with_registry(vi.clone(), &participation, &registry, weight + 1),
+            Err(AggregationError::InsufficientWeight { .. })
+        ));
+        let threshold = weight - 3;
+        let proof = aggregator
+            .prove_with_registry(vi.clone(), &participation, &registry, threshold)
+            .unwrap();
+
+        for threshold in [0, threshold] {
+            assert_eq!(
+                verifier
+                    .verify_with_registry(&proof, epoch, msg, &registry_commitment, threshold)
+                    .unwrap(),
+                participation
+            );
+        }
+        assert!(matches!(
+            verifier.verify_with_registry(&proof, epoch, msg, &registry_commitment, threshold + 1),
+            Err(AggregationError::StatementMismatch)
+        ));
+        let mut forged = proof.clone();
+        forged.threshold = weight + 1;
+        assert!(matches!(
+            verifier.verify_with_registry(&forged, epoch, msg, &registry_commitment, threshold),
+            Err(AggregationError::PublicValuesMismatch { air_idx: 0 })
+        ));
+        let mut forged_commitment = registry_commitment.clone();
+        forged_commitment.total_weight += 1;
+        assert!(matches!(
+            verifier.verify_with_registry(&proof, epoch, msg, &forged_commitment, threshold),
+            Err(AggregationError::PublicValuesMismatch { air_idx: 0 })
+        ));
+        assert!(matches!(
+            aggregator.commit_registry(public_keys.clone(), vec![u32::MAX; num_sigs]),
+            Err(AggregationError::InvalidWeights)
+        ));
+        assert!(matches!(
+            verifier.verify(&proof, epoch, msg, &public_keys),
+            Err(AggregationError::SignerSetLayout {
+                expected: SignerSetLayout::Registry
+            })
+        ));
+        assert!(matches!(
+            aggregator.prove(vi.clone()),
+            Err(AggregationError::SignerSetLayout { .. })
+        ));
+
+        public_keys.swap(0, 1);
+        let other = aggregator.commit_registry(public_keys, weights).unwrap();
+        assert!(matches!(
+            aggregator.prove_with_registry(vi, &participation, &other, threshold),
+            Err(AggregationError::StatementMismatch)
+        ));
+        assert!(matches!(
+            verifier.verify_with_registry(&proof, epoch, msg, &other.commitment(), threshold),
+            Err(AggregationError::StatementMismatch)
+        ));
+    }
+
+    #[test]
+    fn aggregator_with_invalid_signatures() {
+        let num_sigs = 4;
+        let aggregator = Aggregator::new(AggregatorConfig::default(), num_sigs).unwrap();
+        let mut vi = mock_vi(num_sigs);
+        vi.pairs[1].1.merkle_siblings[0][0] += F::ONE;
+        vi.pairs[3].1.merkle_siblings[0][0] += F::ONE;
+        match aggregator.prove(vi.clone()) {
+            Err(AggregationError::InvalidSignatures(report)) => assert_eq!(
+                report.invalid,
+                [
+                    (1, InvalidReason::MerkleRoot),
+                    (3, InvalidReason::MerkleRoot)
+                ]
+            ),
+            _ => panic!("Invalid signatures should be reported"),
+        }
+        vi.pairs[3] = vi.pairs[0];
+        match aggregator.prove(vi) {
+            Err(AggregationError::InvalidSignatures(report)) => assert_eq!(
+                report.invalid,
+                [
+                    (1, InvalidReason::MerkleRoot),
+                    (3, InvalidReason::DuplicateSigner)
+                ]
+            ),
+            _ => panic!("Duplicate signers should be reported"),
+        }
+    }
+}
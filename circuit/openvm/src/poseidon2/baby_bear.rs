@@ -0,0 +1,28 @@
+/// Imports items by their path from the root of this instantiation, so the
+/// shared source included below doesn't depend on its own depth.
+macro_rules! use_instance {
+    ($($path:tt)*) => {
+        use $crate::poseidon2::baby_bear::$($path)*;
+    };
+}
+
+#[path = "aggregator.rs"]
+pub mod aggregator;
+#[path = "chip/mod.rs"]
+pub mod chip;
+#[path = "hash_sig.rs"]
+pub mod hash_sig;
+#[path = "proof.rs"]
+pub mod proof;
+
+pub use hash_sig_verifier::instantiation::poseidon2::baby_bear_horizon::BabyBearHorizon as Poseidon2Parameter;
+pub use p3_baby_bear::BabyBear as F;
+pub use p3_poseidon2_util::instantiation::horizon::baby_bear::constant::{
+    partial_round, HALF_FULL_ROUNDS, RC16, RC24, SBOX_DEGREE,
+};
+/// Challenge field, whose degree is high enough to reach `SECURITY_BITS`, see
+/// `util::security`.
+pub type E = p3_field::extension::BinomialExtensionField<F, 8>;
+pub type Poseidon2LinearLayers<const WIDTH: usize> =
+    p3_poseidon2_util::instantiation::horizon::Poseidon2LinearLayersHorizon<F, WIDTH>;
+pub const SBOX_REGISTERS: usize = 1;
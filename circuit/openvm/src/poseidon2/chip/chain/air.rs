@@ -1,18 +1,18 @@
-use crate::{
-    gadget::{not, select},
-    poseidon2::{
-        chip::{
-            chain::{
-                column::{ChainCols, NUM_CHAIN_COLS},
-                poseidon2::{PARTIAL_ROUNDS, WIDTH},
-            },
-            Bus,
+use_instance!({
+    chip::{
+        chain::{
+            column::{ChainCols, NUM_CHAIN_COLS},
+            poseidon2::{PARTIAL_ROUNDS, WIDTH},
         },
-        Poseidon2LinearLayers, F, HALF_FULL_ROUNDS, RC16, SBOX_DEGREE, SBOX_REGISTERS,
+        Bus,
     },
+    Poseidon2LinearLayers, F, HALF_FULL_ROUNDS, RC16, SBOX_DEGREE, SBOX_REGISTERS,
+});
+use crate::{
+    gadget::{not, select},
     util::zip,
 };
-use core::{borrow::Borrow, iter};
+use core::{borrow::Borrow, iter, num::NonZeroUsize};
 use itertools::Itertools;
 use openvm_stark_backend::{
     air_builders::sub::SubAirBuilder,
@@ -35,18 +35,33 @@ type Poseidon2AirChain = Poseidon2Air<
     PARTIAL_ROUNDS,
 >;
 
+/// AIR of chain compressions, each row consists of `compressions_per_row`
+/// consecutive [`ChainCols`], which trades width for height.
+///
+/// Constraints between consecutive compressions are the same whether they are
+/// in the same row or not, so a signature could start in the middle of a row.
+///
+/// Signatures could be split into consecutive ranges proven by separate
+/// instances, each starting from the signature index in its public values, and
+/// an instance could consist of padding rows only.
 #[derive(Debug)]
-pub struct ChainAir(Poseidon2AirChain);
+pub struct ChainAir {
+    poseidon2: Poseidon2AirChain,
+    compressions_per_row: usize,
+}
 
-impl Default for ChainAir {
-    fn default() -> Self {
-        Self(Poseidon2Air::new(RC16.into()))
+impl ChainAir {
+    pub fn new(compressions_per_row: NonZeroUsize) -> Self {
+        Self {
+            poseidon2: Poseidon2Air::new(RC16.into()),
+            compressions_per_row: compressions_per_row.get(),
+        }
     }
 }
 
 impl BaseAir<F> for ChainAir {
     fn width(&self) -> usize {
-        NUM_CHAIN_COLS
+        self.compressions_per_row * NUM_CHAIN_COLS
     }
 }
 
@@ -54,7 +69,7 @@ impl PartitionedBaseAir<F> for ChainAir {}
 
 impl BaseAirWithPublicValues<F> for ChainAir {
     fn num_public_values(&self) -> usize {
-        1
+        2
     }
 }
 
@@ -64,65 +79,91 @@ where
     AB::Expr: FieldAlgebra<F = F>,
 {
     fn eval(&self, builder: &mut AB) {
-        self.0
-            .eval(&mut SubAirBuilder::<_, Poseidon2AirChain, _>::new(
-                builder,
-                0
-                    ..num_cols::<
-                        WIDTH,
-                        SBOX_DEGREE,
-                        SBOX_REGISTERS,
-                        HALF_FULL_ROUNDS,
-                        PARTIAL_ROUNDS,
-                    >(),
-            ));
+        for offset in (0..self.compressions_per_row).map(|idx| idx * NUM_CHAIN_COLS) {
+            self.poseidon2
+                .eval(&mut SubAirBuilder::<_, Poseidon2AirChain, _>::new(
+                    builder,
+                    offset
+                        ..offset
+                            + num_cols::<
+                                WIDTH,
+                                SBOX_DEGREE,
+                                SBOX_REGISTERS,
+                                HALF_FULL_ROUNDS,
+                                PARTIAL_ROUNDS,
+                            >(),
+                ));
+        }
 
-        let encoded_tweak_chain_first = builder.public_values()[0].into();
+        let epoch: AB::Expr = builder.public_values()[0].into();
+        let sig_offset: AB::Expr = builder.public_values()[1].into();
 
         let main = builder.main();
         let local = main.row_slice(0);
         let next = main.row_slice(1);
-        let local: &ChainCols<AB::Var> = (*local).borrow();
-        let next: &ChainCols<AB::Var> = (*next).borrow();
+        let local = local
+            .chunks_exact(NUM_CHAIN_COLS)
+            .map(Borrow::borrow)
+            .collect::<Vec<&ChainCols<AB::Var>>>();
+        let next: &ChainCols<AB::Var> = next[..NUM_CHAIN_COLS].borrow();
 
         // When every rows
-        eval_every_row(builder, encoded_tweak_chain_first, local);
+        local.iter().for_each(|local| {
+            eval_every_row(builder, epoch.clone(), local);
+        });
 
         // When first row
         {
             let mut builder = builder.when_first_row();
+            let mut builder = builder.when(*local[0].is_active);
 
-            builder.assert_one(*local.is_active);
-            builder.assert_zero(local.sig_idx);
-            local.sig_step.eval_first_row(&mut builder);
-            eval_sig_first_row(&mut builder, local);
+            builder.assert_eq(local[0].sig_idx, sig_offset);
+            local[0].sig_step.eval_first_row(&mut builder);
+            eval_sig_first_row(&mut builder, local[0]);
         }
 
-        // When transition
-        {
-            let mut builder = builder.when_transition();
+        // When consecutive compressions in the same row
+        local.iter().tuple_windows().for_each(|(local, next)| {
+            eval_compression_transition(builder, local, next);
+        });
 
-            eval_transition(&mut builder, local, next);
-            eval_sig_transition(&mut builder, local, next);
-            eval_sig_last_row(&mut builder, local, next);
-            eval_chain_transition(&mut builder, local, next);
-            eval_chain_last_row(&mut builder, local, next);
-        }
+        // When transition
+        eval_compression_transition(
+            &mut builder.when_transition(),
+            local[self.compressions_per_row - 1],
+            next,
+        );
 
         // Interaction
-        receive_parameter(builder, local);
-        receive_chain(builder, local);
-        send_merkle_tree(builder, local);
+        local.iter().for_each(|local| {
+            receive_parameter(builder, local);
+            receive_epoch(builder, epoch.clone(), local);
+            receive_chain(builder, local);
+            send_merkle_tree(builder, local);
+        });
     }
 }
 
+/// Constrains compression `next` following `local`.
 #[inline]
-fn eval_every_row<AB>(
+fn eval_compression_transition<AB>(
     builder: &mut AB,
-    encoded_tweak_chain_first: AB::Expr,
-    cols: &ChainCols<AB::Var>,
+    local: &ChainCols<AB::Var>,
+    next: &ChainCols<AB::Var>,
 ) where
     AB: AirBuilder<F = F>,
+{
+    eval_transition(builder, local, next);
+    eval_sig_transition(builder, local, next);
+    eval_sig_last_row(builder, local, next);
+    eval_chain_transition(builder, local, next);
+    eval_chain_last_row(builder, local, next);
+}
+
+#[inline]
+fn eval_every_row<AB>(builder: &mut AB, epoch: AB::Expr, cols: &ChainCols<AB::Var>)
+where
+    AB: AirBuilder<F = F>,
 {
     cols.is_active.eval_every_row(builder);
     cols.sig_step.eval_every_row(builder);
@@ -140,7 +181,7 @@ fn eval_every_row<AB>(
     zip!(
         cols.encoded_tweak_chain(),
         [
-            encoded_tweak_chain_first,
+            epoch * F::from_canonical_u32(1 << 2),
             *cols.chain_idx * AB::Expr::from_wrapped_u32(1 << 16)
                 + cols.chain_step::<AB>()
                 + F::ONE
@@ -247,6 +288,20 @@ where
     );
 }
 
+/// Receives epoch of each signature from the merkle AIR where it's range
+/// checked.
+#[inline]
+fn receive_epoch<AB>(builder: &mut AB, epoch: AB::Expr, local: &ChainCols<AB::Var>)
+where
+    AB: InteractionBuilder<F = F>,
+{
+    builder.push_receive(
+        Bus::Epoch as usize,
+        [local.sig_idx.into(), epoch],
+        (*local.is_active).into() * local.is_last_sig_row::<AB>(),
+    );
+}
+
 #[inline]
 fn receive_chain<AB>(builder: &mut AB, local: &ChainCols<AB::Var>)
 where
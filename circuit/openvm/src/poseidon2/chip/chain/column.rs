@@ -1,16 +1,16 @@
+use_instance!({
+    chip::chain::{
+        poseidon2::{PARTIAL_ROUNDS, WIDTH},
+        MAX_CHAIN_STEP_DIFF_BITS,
+    },
+    hash_sig::{CHUNK_SIZE, HASH_FE_LEN, PARAM_FE_LEN, TARGET_SUM, TWEAK_FE_LEN},
+    HALF_FULL_ROUNDS, SBOX_DEGREE, SBOX_REGISTERS,
+});
 use crate::{
     gadget::{
         cycle_int::CycleInt, lower_rows_filter::LowerRowsFilterCols,
         strictly_increasing::StrictlyIncreasingCols,
     },
-    poseidon2::{
-        chip::chain::{
-            poseidon2::{PARTIAL_ROUNDS, WIDTH},
-            MAX_CHAIN_STEP_DIFF_BITS,
-        },
-        hash_sig::{CHUNK_SIZE, HASH_FE_LEN, PARAM_FE_LEN, TARGET_SUM, TWEAK_FE_LEN},
-        HALF_FULL_ROUNDS, SBOX_DEGREE, SBOX_REGISTERS,
-    },
     util::AlignBorrow,
 };
 use core::{
@@ -26,11 +26,13 @@ pub const NUM_CHAIN_COLS: usize = size_of::<ChainCols<u8>>();
 
 const NUM_PADDING: usize = WIDTH - (PARAM_FE_LEN + TWEAK_FE_LEN + HASH_FE_LEN);
 
+/// Columns of a chain compression, see [`ChainAir`](super::ChainAir) for how
+/// they are laid out in rows.
 #[repr(C)]
 pub struct ChainCols<T> {
     pub perm:
         Poseidon2Cols<T, WIDTH, SBOX_DEGREE, SBOX_REGISTERS, HALF_FULL_ROUNDS, PARTIAL_ROUNDS>,
-    /// Whether this sig is active or not.
+    /// Whether this compression is active or not.
     pub is_active: LowerRowsFilterCols<T>,
     /// Signature index.
     pub sig_idx: T,
@@ -1,60 +1,83 @@
-use crate::{
-    poseidon2::{
-        chip::chain::{
-            column::{ChainCols, NUM_CHAIN_COLS},
-            poseidon2::{PARTIAL_ROUNDS, WIDTH},
-        },
-        hash_sig::{VerificationTrace, CHUNK_SIZE, NUM_CHUNKS, TARGET_SUM},
-        Poseidon2LinearLayers, F, HALF_FULL_ROUNDS, RC16, SBOX_DEGREE, SBOX_REGISTERS,
-    },
-    util::{
-        field::{MaybeUninitField, MaybeUninitFieldSlice},
-        par_zip, zip,
+use_instance!({
+    chip::chain::{
+        column::{ChainCols, NUM_CHAIN_COLS},
+        poseidon2::{PARTIAL_ROUNDS, WIDTH},
+        sig_offsets, split_sigs, trace_height, NUM_COMPRESSIONS_PER_SIG,
     },
+    hash_sig::{VerificationTrace, CHUNK_SIZE, NUM_CHUNKS},
+    Poseidon2LinearLayers, F, HALF_FULL_ROUNDS, RC16, SBOX_DEGREE, SBOX_REGISTERS,
+});
+use crate::util::{
+    field::{MaybeUninitField, MaybeUninitFieldSlice},
+    par_zip,
+    trace::UninitTrace,
+    zip,
 };
 use core::mem::MaybeUninit;
 use itertools::Itertools;
 use p3_field::FieldAlgebra;
-use p3_matrix::dense::{RowMajorMatrix, RowMajorMatrixViewMut};
+use p3_matrix::dense::RowMajorMatrix;
 use p3_maybe_rayon::prelude::*;
 use p3_poseidon2_util::air::generate_trace_rows_for_perm;
 
 const MAX_X_I: u32 = (1 << CHUNK_SIZE) - 1;
 
-pub const fn trace_height(traces: &[VerificationTrace]) -> usize {
-    (traces.len() * TARGET_SUM as usize).next_power_of_two()
+pub fn uninit_trace(
+    extra_capacity_bits: usize,
+    num_sigs: usize,
+    compressions_per_row: usize,
+) -> UninitTrace<F> {
+    UninitTrace::new(
+        compressions_per_row * NUM_CHAIN_COLS,
+        trace_height(num_sigs, compressions_per_row),
+        extra_capacity_bits,
+    )
 }
 
-pub fn generate_trace_rows(
-    extra_capacity_bits: usize,
+/// Generates compressions of `traces` as signatures starting from
+/// `sig_offset`, into the trace of an instance starting from signature
+/// `instance_sig_offset`.
+///
+/// Compressions are laid out consecutively across rows, so a signature could
+/// start in the middle of a row.
+pub fn generate_trace_rows_chunk(
+    trace: &mut UninitTrace<F>,
+    instance_sig_offset: usize,
+    sig_offset: usize,
     traces: &[VerificationTrace],
-) -> RowMajorMatrix<F> {
-    let height = trace_height(traces);
-    let size = height * NUM_CHAIN_COLS;
-    let mut vec = Vec::with_capacity(size << extra_capacity_bits);
-    let trace = &mut vec.spare_capacity_mut()[..size];
-    let trace = RowMajorMatrixViewMut::new(trace, NUM_CHAIN_COLS);
-
-    let (prefix, rows, suffix) =
-        unsafe { trace.values.align_to_mut::<ChainCols<MaybeUninit<F>>>() };
-    assert!(prefix.is_empty(), "Alignment should match");
-    assert!(suffix.is_empty(), "Alignment should match");
-    assert_eq!(rows.len(), height);
+) {
+    let compressions = unsafe { compressions_mut(trace) };
+    let compressions = &mut compressions
+        [(sig_offset - instance_sig_offset) * NUM_COMPRESSIONS_PER_SIG..]
+        [..traces.len() * NUM_COMPRESSIONS_PER_SIG];
 
-    let (rows, padding_rows) = rows.split_at_mut(traces.len() * TARGET_SUM as usize);
+    par_zip!(
+        compressions.par_chunks_mut(NUM_COMPRESSIONS_PER_SIG),
+        traces
+    )
+    .enumerate()
+    .for_each(|(idx, (compressions, trace))| {
+        generate_trace_rows_sig(compressions, sig_offset + idx, trace);
+    });
+}
 
-    join(
-        || {
-            par_zip!(rows.par_chunks_mut(TARGET_SUM as _), traces)
-                .enumerate()
-                .for_each(|(sig_idx, (rows, trace))| generate_trace_rows_sig(rows, sig_idx, trace));
-        },
-        || generate_trace_rows_padding(padding_rows),
-    );
+/// Generates padding compressions after `num_sigs` signatures and returns the
+/// trace.
+pub fn finalize(mut trace: UninitTrace<F>, num_sigs: usize) -> RowMajorMatrix<F> {
+    let compressions = unsafe { compressions_mut(&mut trace) };
+    generate_trace_rows_padding(&mut compressions[num_sigs * NUM_COMPRESSIONS_PER_SIG..]);
 
-    unsafe { vec.set_len(size) };
+    unsafe { trace.assume_init() }
+}
 
-    RowMajorMatrix::new(vec, NUM_CHAIN_COLS)
+/// Returns all compressions of the trace in order.
+///
+/// # Safety
+///
+/// Width of `trace` should be multiple of [`NUM_CHAIN_COLS`].
+#[inline]
+unsafe fn compressions_mut(trace: &mut UninitTrace<F>) -> &mut [ChainCols<MaybeUninit<F>>] {
+    trace.row_parts_mut(trace.width() / NUM_CHAIN_COLS)
 }
 
 #[inline]
@@ -128,3 +151,59 @@ pub fn generate_trace_row_padding(row: &mut ChainCols<MaybeUninit<F>>) {
         PARTIAL_ROUNDS,
     >(&mut row.perm, Default::default(), &RC16);
 }
+
+/// Traces of instances of [`ChainAir`](super::ChainAir), each of consecutive
+/// signatures split by [`split_sigs`].
+pub struct ChainTraces {
+    num_sigs: Vec<usize>,
+    sig_offsets: Vec<usize>,
+    traces: Vec<UninitTrace<F>>,
+}
+
+impl ChainTraces {
+    pub fn uninit(
+        extra_capacity_bits: usize,
+        num_sigs: usize,
+        compressions_per_row: usize,
+        num_instances: usize,
+    ) -> Self {
+        let num_sigs = split_sigs(num_sigs, compressions_per_row, num_instances);
+        let sig_offsets = sig_offsets(&num_sigs);
+        let traces = num_sigs
+            .iter()
+            .map(|num_sigs| uninit_trace(extra_capacity_bits, *num_sigs, compressions_per_row))
+            .collect();
+        Self {
+            num_sigs,
+            sig_offsets,
+            traces,
+        }
+    }
+
+    /// Generates compressions of `traces` as signatures starting from
+    /// `sig_offset` into the instances they belong to.
+    pub fn generate_trace_rows_chunk(&mut self, sig_offset: usize, traces: &[VerificationTrace]) {
+        zip!(&mut self.traces, &self.num_sigs, &self.sig_offsets).for_each(
+            |(trace, num_sigs, instance_sig_offset)| {
+                let start = sig_offset.max(*instance_sig_offset);
+                let end = (sig_offset + traces.len()).min(instance_sig_offset + num_sigs);
+                if start < end {
+                    generate_trace_rows_chunk(
+                        trace,
+                        *instance_sig_offset,
+                        start,
+                        &traces[start - sig_offset..end - sig_offset],
+                    );
+                }
+            },
+        );
+    }
+
+    /// Generates padding compressions of each instance and returns the index
+    /// of its first signature and its trace.
+    pub fn finalize(self) -> Vec<(usize, RowMajorMatrix<F>)> {
+        zip!(self.traces, self.num_sigs, self.sig_offsets)
+            .map(|(trace, num_sigs, sig_offset)| (sig_offset, finalize(trace, num_sigs)))
+            .collect()
+    }
+}
@@ -1,80 +1,78 @@
-use crate::poseidon2::{
-    chip::chain::{air::ChainAir, column::NUM_CHAIN_COLS},
-    hash_sig::{VerificationTrace, NUM_CHUNKS},
-    F,
-};
-use core::any::type_name;
-use generation::{generate_trace_rows, trace_height};
-use openvm_stark_backend::{
-    config::{Domain, StarkGenericConfig},
-    prover::types::{AirProofInput, AirProofRawInput},
-    rap::AnyRap,
-    Chip, ChipUsageGetter,
-};
-use p3_commit::PolynomialSpace;
+use_instance!({hash_sig::{NUM_CHUNKS, TARGET_SUM}, F});
 use p3_field::FieldAlgebra;
-use std::sync::Arc;
 
 const MAX_CHAIN_STEP_DIFF_BITS: usize = (NUM_CHUNKS / 2).next_power_of_two().ilog2() as usize;
 
 mod air;
-mod column;
-mod generation;
+pub(super) mod column;
+#[cfg(feature = "prover")]
+pub(super) mod generation;
+
+pub use air::ChainAir;
 
 mod poseidon2 {
-    pub const WIDTH: usize = 16;
-    pub const PARTIAL_ROUNDS: usize = crate::poseidon2::partial_round::<WIDTH>();
-}
+    use_instance!(partial_round);
 
-#[derive(Clone, Debug)]
-pub struct ChainChip<'a> {
-    air: Arc<ChainAir>,
-    extra_capacity_bits: usize,
-    epoch: u32,
-    traces: &'a [VerificationTrace],
+    pub const WIDTH: usize = 16;
+    pub const PARTIAL_ROUNDS: usize = partial_round::<WIDTH>();
 }
 
-impl<'a> ChainChip<'a> {
-    pub fn new(extra_capacity_bits: usize, epoch: u32, traces: &'a [VerificationTrace]) -> Self {
-        Self {
-            air: Default::default(),
-            extra_capacity_bits,
-            epoch,
-            traces,
-        }
-    }
+/// Returns public values of [`ChainAir`], which are the raw `epoch` and index
+/// of the first signature of the instance.
+pub fn public_values(epoch: u32, sig_offset: usize) -> Vec<F> {
+    vec![
+        F::from_canonical_u32(epoch),
+        F::from_canonical_usize(sig_offset),
+    ]
 }
 
-impl ChipUsageGetter for ChainChip<'_> {
-    fn air_name(&self) -> String {
-        type_name::<ChainAir>().to_string()
-    }
+pub const NUM_COMPRESSIONS_PER_SIG: usize = TARGET_SUM as usize;
 
-    fn current_trace_height(&self) -> usize {
-        trace_height(self.traces)
-    }
+pub const fn num_rows(num_sigs: usize, compressions_per_row: usize) -> usize {
+    (num_sigs * NUM_COMPRESSIONS_PER_SIG).div_ceil(compressions_per_row)
+}
 
-    fn trace_width(&self) -> usize {
-        NUM_CHAIN_COLS
-    }
+pub const fn trace_height(num_sigs: usize, compressions_per_row: usize) -> usize {
+    num_rows(num_sigs, compressions_per_row).next_power_of_two()
 }
 
-impl<SC: StarkGenericConfig> Chip<SC> for ChainChip<'_>
-where
-    Domain<SC>: PolynomialSpace<Val = F>,
-{
-    fn air(&self) -> Arc<dyn AnyRap<SC>> {
-        self.air.clone()
-    }
+/// Returns number of signatures in each of `num_instances` instances of
+/// [`ChainAir`] of `num_sigs` signatures in order.
+///
+/// Every instance but the last takes as many signatures as fit in the largest
+/// power of two rows not exceeding the ones of remaining signatures, so it's
+/// padded by less than a signature, and the last one takes the rest, which
+/// could be none.
+pub fn split_sigs(
+    num_sigs: usize,
+    compressions_per_row: usize,
+    num_instances: usize,
+) -> Vec<usize> {
+    let mut remaining = num_sigs;
+    (0..num_instances)
+        .map(|idx| {
+            let num_rows = num_rows(remaining, compressions_per_row);
+            let num_sigs = if idx == num_instances - 1 || num_rows == 0 {
+                remaining
+            } else {
+                ((compressions_per_row << num_rows.ilog2()) / NUM_COMPRESSIONS_PER_SIG)
+                    .min(remaining)
+            };
+            remaining -= num_sigs;
+            num_sigs
+        })
+        .collect()
+}
 
-    fn generate_air_proof_input(self) -> AirProofInput<SC> {
-        AirProofInput {
-            cached_mains_pdata: Vec::new(),
-            raw: AirProofRawInput {
-                cached_mains: Vec::new(),
-                common_main: Some(generate_trace_rows(self.extra_capacity_bits, self.traces)),
-                public_values: vec![F::from_canonical_u32(self.epoch << 2)],
-            },
-        }
-    }
+/// Returns index of the first signature of each instance split by
+/// [`split_sigs`].
+pub fn sig_offsets(num_sigs: &[usize]) -> Vec<usize> {
+    num_sigs
+        .iter()
+        .scan(0, |offset, num_sigs| {
+            let sig_offset = *offset;
+            *offset += num_sigs;
+            Some(sig_offset)
+        })
+        .collect()
 }
@@ -1,19 +1,16 @@
-use crate::{
-    gadget::not,
-    poseidon2::{
-        chip::{
-            decomposition::{
-                column::{DecompositionCols, NUM_DECOMPOSITION_COLS},
-                F_MS_LIMB, F_MS_LIMB_LEADING_ONES, F_MS_LIMB_TRAILING_ZEROS, LIMB_BITS, NUM_LIMBS,
-                NUM_MSG_HASH_LIMBS,
-            },
-            Bus,
+use_instance!({
+    chip::{
+        decomposition::{
+            column::{DecompositionCols, NUM_DECOMPOSITION_COLS},
+            F_MS_LIMB, F_MS_LIMB_LEADING_ONES, F_MS_LIMB_TRAILING_ZEROS, LIMB_BITS, NUM_LIMBS,
+            NUM_MSG_HASH_LIMBS,
         },
-        hash_sig::{CHUNK_SIZE, MSG_HASH_FE_LEN, TARGET_SUM},
-        F,
+        range_check, Bus,
     },
-    util::zip,
-};
+    hash_sig::{CHUNK_SIZE, MSG_HASH_FE_LEN, TARGET_SUM},
+    F,
+});
+use crate::{gadget::not, util::zip};
 use core::{borrow::Borrow, iter};
 use itertools::Itertools;
 use openvm_stark_backend::{
@@ -132,10 +129,9 @@ where
         .map(|(idx, limb)| limb * F::from_canonical_u32(1 << (idx * LIMB_BITS)))
         .sum::<AB::Expr>();
     (0..MSG_HASH_FE_LEN).for_each(|idx| {
-        builder.when(cols.inds[idx]).assert_eq(
-            cols.values[MSG_HASH_FE_LEN - 1 - idx],
-            value_composed.clone(),
-        );
+        builder
+            .when(cols.inds[idx])
+            .assert_eq(cols.values[idx], value_composed.clone());
     });
 }
 
@@ -305,7 +301,7 @@ where
         .chain(&cols.acc_limbs)
         .chain(&cols.carries)
     {
-        builder.push_send(Bus::RangeCheck as usize, [*limb], cols.is_acc::<AB>());
+        range_check::send_range_check(builder, *limb, LIMB_BITS, cols.is_acc::<AB>());
     }
 }
 
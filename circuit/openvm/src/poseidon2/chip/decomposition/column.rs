@@ -1,9 +1,9 @@
+use_instance!({
+    chip::decomposition::{F_MS_LIMB_BITS, LIMB_BITS, NUM_LIMBS, NUM_MSG_HASH_LIMBS},
+    hash_sig::{CHUNK_SIZE, MSG_HASH_FE_LEN},
+});
 use crate::{
     gadget::{cycle_bits::CycleBits, is_equal::IsEqualCols, is_zero::IsZeroCols},
-    poseidon2::{
-        chip::decomposition::{F_MS_LIMB_BITS, LIMB_BITS, NUM_LIMBS, NUM_MSG_HASH_LIMBS},
-        hash_sig::{CHUNK_SIZE, MSG_HASH_FE_LEN},
-    },
     util::AlignBorrow,
 };
 use core::{
@@ -23,7 +23,9 @@ pub struct DecompositionCols<T> {
     pub sig_idx: T,
     /// One-hot vector indicating current accumulation step.
     pub inds: CycleBits<T, { MSG_HASH_FE_LEN + NUM_MSG_HASH_LIMBS }>,
-    /// Scalars in little-endian order.
+    /// Message hash in its native order, where `values[step]` is accumulated
+    /// at `step` as the most significant one first, same as
+    /// `msg_hash_to_chunks`.
     pub values: [T; MSG_HASH_FE_LEN],
     /// Least significant limbs of `value[step]`.
     pub value_ls_limbs: [T; NUM_LIMBS - 1],
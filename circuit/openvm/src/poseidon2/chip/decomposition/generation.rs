@@ -1,92 +1,155 @@
-use crate::{
-    poseidon2::{
-        chip::decomposition::{
+use_instance!({
+    chip::{
+        decomposition::{
             column::{DecompositionCols, NUM_DECOMPOSITION_COLS},
-            F_MS_LIMB, F_MS_LIMB_BITS, F_MS_LIMB_LEADING_ONES, F_MS_LIMB_TRAILING_ZEROS, LIMB_BITS,
-            LIMB_MASK, NUM_LIMBS, NUM_MSG_HASH_LIMBS,
+            trace_height, DecompositionAir, F_MS_LIMB, F_MS_LIMB_BITS, F_MS_LIMB_LEADING_ONES,
+            F_MS_LIMB_TRAILING_ZEROS, LIMB_BITS, LIMB_MASK, NUM_LIMBS, NUM_MSG_HASH_LIMBS,
+            NUM_ROWS_PER_SIG,
         },
-        hash_sig::{VerificationTrace, CHUNK_SIZE, MSG_HASH_FE_LEN},
-        F,
-    },
-    util::{
-        field::{MaybeUninitField, MaybeUninitFieldSlice},
-        par_zip,
+        range_check::RangeChecker,
     },
+    hash_sig::{VerificationTrace, CHUNK_SIZE, MSG_HASH_FE_LEN},
+    F,
+});
+use crate::util::{
+    field::{MaybeUninitField, MaybeUninitFieldSlice},
+    par_zip,
+    trace::UninitTrace,
 };
-use core::{array::from_fn, iter::repeat_with, mem::MaybeUninit};
+use core::{any::type_name, array::from_fn, mem::MaybeUninit};
 use itertools::Itertools;
+use openvm_stark_backend::{
+    config::{Domain, StarkGenericConfig},
+    prover::types::{AirProofInput, AirProofRawInput},
+    rap::AnyRap,
+    Chip, ChipUsageGetter,
+};
+use p3_commit::PolynomialSpace;
 use p3_field::{FieldAlgebra, PrimeField32};
-use p3_matrix::dense::{RowMajorMatrix, RowMajorMatrixViewMut};
+use p3_matrix::dense::RowMajorMatrix;
 use p3_maybe_rayon::prelude::*;
-use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
 
-const NUM_ROWS_PER_SIG: usize = MSG_HASH_FE_LEN + NUM_MSG_HASH_LIMBS;
+#[derive(Clone, Copy, Debug)]
+pub struct DecompositionChip<'a> {
+    extra_capacity_bits: usize,
+    traces: &'a [VerificationTrace],
+    range_checker: &'a RangeChecker,
+}
 
-pub const fn trace_height(traces: &[VerificationTrace]) -> usize {
-    (NUM_ROWS_PER_SIG * traces.len()).next_power_of_two()
+impl<'a> DecompositionChip<'a> {
+    pub const fn new(
+        extra_capacity_bits: usize,
+        traces: &'a [VerificationTrace],
+        range_checker: &'a RangeChecker,
+    ) -> Self {
+        Self {
+            extra_capacity_bits,
+            traces,
+            range_checker,
+        }
+    }
 }
 
-pub fn generate_trace_rows(
-    extra_capacity_bits: usize,
+impl ChipUsageGetter for DecompositionChip<'_> {
+    fn air_name(&self) -> String {
+        type_name::<DecompositionAir>().to_string()
+    }
+
+    fn current_trace_height(&self) -> usize {
+        trace_height(self.traces.len())
+    }
+
+    fn trace_width(&self) -> usize {
+        NUM_DECOMPOSITION_COLS
+    }
+}
+
+impl<SC: StarkGenericConfig> Chip<SC> for DecompositionChip<'_>
+where
+    Domain<SC>: PolynomialSpace<Val = F>,
+{
+    fn air(&self) -> Arc<dyn AnyRap<SC>> {
+        Arc::new(DecompositionAir)
+    }
+
+    fn generate_air_proof_input(self) -> AirProofInput<SC> {
+        AirProofInput {
+            cached_mains_pdata: Vec::new(),
+            raw: AirProofRawInput {
+                cached_mains: Vec::new(),
+                common_main: Some(generate_trace_rows(
+                    self.extra_capacity_bits,
+                    self.traces,
+                    self.range_checker,
+                )),
+                public_values: Vec::new(),
+            },
+        }
+    }
+}
+
+pub fn uninit_trace(extra_capacity_bits: usize, num_sigs: usize) -> UninitTrace<F> {
+    UninitTrace::new(
+        NUM_DECOMPOSITION_COLS,
+        trace_height(num_sigs),
+        extra_capacity_bits,
+    )
+}
+
+/// Generates rows of `traces` as signatures starting from `sig_offset`, and
+/// records their range checks into `range_checker`.
+pub fn generate_trace_rows_chunk(
+    trace: &mut UninitTrace<F>,
+    range_checker: &RangeChecker,
+    sig_offset: usize,
     traces: &[VerificationTrace],
-) -> (RowMajorMatrix<F>, Vec<u32>) {
-    let height = trace_height(traces);
-    let size = height * NUM_DECOMPOSITION_COLS;
-    let mut vec = Vec::with_capacity(size << extra_capacity_bits);
-    let trace = &mut vec.spare_capacity_mut()[..size];
-    let trace = RowMajorMatrixViewMut::new(trace, NUM_DECOMPOSITION_COLS);
-
-    let (prefix, rows, suffix) = unsafe {
-        trace
-            .values
-            .align_to_mut::<DecompositionCols<MaybeUninit<_>>>()
-    };
-    assert!(prefix.is_empty(), "Alignment should match");
-    assert!(suffix.is_empty(), "Alignment should match");
-    assert_eq!(rows.len(), height);
-
-    let (msg_hash_rows, padding_rows) = rows.split_at_mut(NUM_ROWS_PER_SIG * traces.len());
-    let mult = repeat_with(|| AtomicU32::new(0))
-        .take(1 << LIMB_BITS)
-        .collect::<Vec<_>>();
-
-    join(
-        || {
-            par_zip!(msg_hash_rows.par_chunks_mut(NUM_ROWS_PER_SIG), traces)
+) {
+    let rows = unsafe { trace.rows_mut::<DecompositionCols<MaybeUninit<F>>>() };
+    let rows = &mut rows[sig_offset * NUM_ROWS_PER_SIG..][..traces.len() * NUM_ROWS_PER_SIG];
+
+    par_zip!(rows.par_chunks_mut(NUM_ROWS_PER_SIG), traces)
+        .enumerate()
+        .for_each(|(idx, (rows, trace))| {
+            let sig_idx = sig_offset + idx;
+            let values = trace.msg_hash;
+            let mut acc_limbs = Default::default();
+            let (acc_rows, decomposition_rows) = rows.split_at_mut(MSG_HASH_FE_LEN);
+            acc_rows.iter_mut().enumerate().for_each(|(step, row)| {
+                generate_trace_row_acc(row, sig_idx, &mut acc_limbs, values, step, range_checker);
+            });
+            let sums = trace
+                .x
+                .chunks(LIMB_BITS / CHUNK_SIZE)
+                .scan(0u32, |sum, x| {
+                    *sum += u32::from(x.iter().copied().sum::<u16>());
+                    Some(*sum)
+                })
+                .collect_vec();
+            par_zip!(decomposition_rows, sums)
                 .enumerate()
-                .for_each(|(sig_idx, (rows, trace))| {
-                    let values = from_fn(|i| trace.msg_hash[MSG_HASH_FE_LEN - 1 - i]); // FIXME: Use little-endian when #9 is resolved.
-                    let mut acc_limbs = Default::default();
-                    let (acc_rows, decomposition_rows) = rows.split_at_mut(MSG_HASH_FE_LEN);
-                    acc_rows.iter_mut().enumerate().for_each(|(step, row)| {
-                        generate_trace_row_acc(row, sig_idx, &mut acc_limbs, values, step, &mult);
-                    });
-                    let sums = trace
-                        .x
-                        .chunks(LIMB_BITS / CHUNK_SIZE)
-                        .scan(0u32, |sum, x| {
-                            *sum += u32::from(x.iter().copied().sum::<u16>());
-                            Some(*sum)
-                        })
-                        .collect_vec();
-                    par_zip!(decomposition_rows, sums).enumerate().for_each(
-                        |(step, (row, sum))| {
-                            generate_trace_row_decomposition(row, sig_idx, &acc_limbs, sum, step);
-                        },
-                    );
+                .for_each(|(step, (row, sum))| {
+                    generate_trace_row_decomposition(row, sig_idx, &acc_limbs, sum, step);
                 });
-        },
-        || generate_trace_rows_padding(padding_rows),
-    );
+        });
+}
 
-    unsafe { vec.set_len(size) };
+/// Generates padding rows after `num_sigs` signatures and returns the trace.
+pub fn finalize(mut trace: UninitTrace<F>, num_sigs: usize) -> RowMajorMatrix<F> {
+    let rows = unsafe { trace.rows_mut::<DecompositionCols<MaybeUninit<F>>>() };
+    generate_trace_rows_padding(&mut rows[num_sigs * NUM_ROWS_PER_SIG..]);
 
-    (
-        RowMajorMatrix::new(vec, NUM_DECOMPOSITION_COLS),
-        mult.into_par_iter()
-            .map(|value| value.load(Ordering::Relaxed))
-            .collect(),
-    )
+    unsafe { trace.assume_init() }
+}
+
+pub fn generate_trace_rows(
+    extra_capacity_bits: usize,
+    traces: &[VerificationTrace],
+    range_checker: &RangeChecker,
+) -> RowMajorMatrix<F> {
+    let mut trace = uninit_trace(extra_capacity_bits, traces.len());
+    generate_trace_rows_chunk(&mut trace, range_checker, 0, traces);
+    finalize(trace, traces.len())
 }
 
 #[inline]
@@ -96,9 +159,9 @@ pub fn generate_trace_row_acc(
     acc_limbs: &mut [u32; NUM_MSG_HASH_LIMBS],
     values: [F; MSG_HASH_FE_LEN],
     step: usize,
-    mult: &[AtomicU32],
+    range_checker: &RangeChecker,
 ) {
-    let value = values[MSG_HASH_FE_LEN - 1 - step].as_canonical_u32();
+    let value = values[step].as_canonical_u32();
     let value_limbs: [_; NUM_LIMBS] = from_fn(|i| (value >> (i * LIMB_BITS)) & LIMB_MASK);
     let value_ms_limb = value_limbs[NUM_LIMBS - 1];
     let value_ms_limb_bits: [_; F_MS_LIMB_BITS] = from_fn(|i| (value_ms_limb >> i) & 1 == 1);
@@ -126,9 +189,7 @@ pub fn generate_trace_row_acc(
         .take(NUM_LIMBS - 1)
         .chain(*acc_limbs)
         .chain(carries)
-        .for_each(|value| {
-            mult[value as usize].fetch_add(1, Ordering::Relaxed);
-        });
+        .for_each(|value| range_checker.add_count(value, LIMB_BITS));
     let value_ls_limbs: [_; NUM_LIMBS - 1] = from_fn(|i| F::from_canonical_u32(value_limbs[i]));
     row.sig_idx.write_usize(sig_idx);
     row.inds.populate(Some(step));
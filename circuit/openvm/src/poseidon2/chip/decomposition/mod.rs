@@ -1,23 +1,13 @@
-use crate::poseidon2::{
-    chip::decomposition::{
-        air::DecompositionAir,
-        column::NUM_DECOMPOSITION_COLS,
-        generation::{generate_trace_rows, trace_height},
-    },
-    hash_sig::{VerificationTrace, MSG_HASH_FE_LEN},
-    F,
-};
-use core::any::type_name;
-use openvm_stark_backend::{
-    config::{Domain, StarkGenericConfig},
-    prover::types::{AirProofInput, AirProofRawInput},
-    rap::AnyRap,
-    Chip, ChipUsageGetter,
-};
-use p3_commit::PolynomialSpace;
+use_instance!({hash_sig::MSG_HASH_FE_LEN, F});
 use p3_field::PrimeField32;
-use std::sync::Arc;
 
+/// Bits of limbs range checked over [`Bus::RangeCheck`](super::Bus::RangeCheck)
+/// by this and other AIRs.
+///
+/// It's fixed instead of configurable, since the decomposition relies on the
+/// modulus being `(F_MS_LIMB << (2 * LIMB_BITS)) + 1` in exactly three
+/// limbs, with limbs made of whole `CHUNK_SIZE`-bit chunks, and `12` is
+/// the only width satisfying both for BabyBear and KoalaBear.
 pub const LIMB_BITS: usize = 12;
 pub const LIMB_MASK: u32 = (1 << LIMB_BITS) - 1;
 pub const NUM_LIMBS: usize =
@@ -37,72 +27,16 @@ const __: () =
     assert!((F_MS_LIMB >> F_MS_LIMB_TRAILING_ZEROS).trailing_ones() == F_MS_LIMB_LEADING_ONES);
 
 mod air;
-mod column;
-mod generation;
-
-#[derive(Clone, Copy, Debug)]
-pub struct DecompositionChip<'a> {
-    extra_capacity_bits: usize,
-    traces: &'a [VerificationTrace],
-}
-
-impl<'a> DecompositionChip<'a> {
-    pub const fn new(extra_capacity_bits: usize, traces: &'a [VerificationTrace]) -> Self {
-        Self {
-            extra_capacity_bits,
-            traces,
-        }
-    }
+pub(super) mod column;
+#[cfg(feature = "prover")]
+pub(super) mod generation;
 
-    pub fn generate_air_proof_input_and_range_check_mult<SC: StarkGenericConfig>(
-        self,
-    ) -> (AirProofInput<SC>, Vec<u32>)
-    where
-        Domain<SC>: PolynomialSpace<Val = F>,
-    {
-        let (trace, mult) = generate_trace_rows(self.extra_capacity_bits, self.traces);
-        let api = AirProofInput {
-            cached_mains_pdata: Vec::new(),
-            raw: AirProofRawInput {
-                cached_mains: Vec::new(),
-                common_main: Some(trace),
-                public_values: Vec::new(),
-            },
-        };
-        (api, mult)
-    }
-}
-
-impl ChipUsageGetter for DecompositionChip<'_> {
-    fn air_name(&self) -> String {
-        type_name::<DecompositionAir>().to_string()
-    }
-
-    fn current_trace_height(&self) -> usize {
-        trace_height(self.traces)
-    }
-
-    fn trace_width(&self) -> usize {
-        NUM_DECOMPOSITION_COLS
-    }
-}
+pub use air::DecompositionAir;
+#[cfg(feature = "prover")]
+pub use generation::DecompositionChip;
 
-impl<SC: StarkGenericConfig> Chip<SC> for DecompositionChip<'_>
-where
-    Domain<SC>: PolynomialSpace<Val = F>,
-{
-    fn air(&self) -> Arc<dyn AnyRap<SC>> {
-        Arc::new(DecompositionAir)
-    }
+pub const NUM_ROWS_PER_SIG: usize = MSG_HASH_FE_LEN + NUM_MSG_HASH_LIMBS;
 
-    fn generate_air_proof_input(self) -> AirProofInput<SC> {
-        AirProofInput {
-            cached_mains_pdata: Vec::new(),
-            raw: AirProofRawInput {
-                cached_mains: Vec::new(),
-                common_main: Some(generate_trace_rows(self.extra_capacity_bits, self.traces).0),
-                public_values: Vec::new(),
-            },
-        }
-    }
+pub const fn trace_height(num_sigs: usize) -> usize {
+    (NUM_ROWS_PER_SIG * num_sigs).next_power_of_two()
 }
@@ -0,0 +1,468 @@
+use_instance!({
+    chip::{
+        airs,
+        chain::{self, generation::ChainTraces},
+        decomposition, estimate_peak_memory,
+        main::MainChip,
+        merkle_leaf, merkle_path, merkle_tree,
+        msg_encoding::MsgEncodingChip,
+        msg_hash, participating_weight,
+        participation::ParticipationChip,
+        range_check::{RangeCheckChip, RangeChecker},
+        registry::{Registry, RegistryChip},
+        signer_set::SignerSetChip,
+        unique_signer::{signer_key, UniqueSignerChip},
+        validate_participating, Layout, MerkleLayout, SignerSetLayout, RANGE_CHECK_BITS,
+    },
+    hash_sig::{encode_msg, PublicKey, VerificationInput, VerificationTrace, MSG_FE_LEN},
+    F,
+});
+use crate::util::{trace::UninitTrace, validation::ValidationReport};
+use core::iter::zip;
+use openvm_stark_backend::{
+    config::{Domain, StarkGenericConfig},
+    prover::types::{AirProofInput, AirProofRawInput},
+    AirRef, Chip,
+};
+use p3_commit::PolynomialSpace;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_maybe_rayon::prelude::*;
+use tracing::{info, instrument};
+
+/// Number of participating signatures whose [`VerificationTrace`]s are alive at
+/// once in [`generate_air_proof_inputs`].
+pub const DEFAULT_CHUNK_SIZE: usize = 1 << 10;
+
+/// Generates AIRs and their proof inputs with the default [`Layout`],
+/// proving validity of signatures flagged in `participation` only.
+///
+/// The participation bitmap is exposed as public values of the participation
+/// AIR, see [`participation_bitmap`](super::participation::participation_bitmap)
+/// for the layout, and the commitment of all public keys is exposed as public
+/// values of the last AIR, see
+/// [`signer_set_commitment`](super::signer_set::signer_set_commitment).
+/// Signatures not participating don't need to be valid, but at least one
+/// signature should be participating.
+///
+/// Every signer weighs `1`, and the threshold in public values of the main
+/// AIR is the number of participating signatures, see
+/// [`main::public_values`](super::main::public_values).
+///
+/// Participating signatures are assumed to be valid and of distinct signers,
+/// otherwise the witness is unprovable, see [`try_generate_air_proof_inputs`]
+/// for checking them beforehand.
+pub fn generate_air_proof_inputs<SC: StarkGenericConfig>(
+    extra_capacity_bits: usize,
+    vi: VerificationInput,
+    participation: &[bool],
+) -> (Vec<AirRef<SC>>, Vec<AirProofInput<SC>>)
+where
+    Domain<SC>: PolynomialSpace<Val = F>,
+{
+    generate_air_proof_inputs_in_chunks(
+        extra_capacity_bits,
+        vi,
+        participation,
+        DEFAULT_CHUNK_SIZE,
+        Layout::default(),
+    )
+}
+
+/// Same as [`generate_air_proof_inputs`], but returns report of invalid or
+/// duplicate participating signatures instead of an unprovable witness, see
+/// [`validate_participating`].
+pub fn try_generate_air_proof_inputs<SC: StarkGenericConfig>(
+    extra_capacity_bits: usize,
+    vi: VerificationInput,
+    participation: &[bool],
+) -> Result<(Vec<AirRef<SC>>, Vec<AirProofInput<SC>>), ValidationReport>
+where
+    Domain<SC>: PolynomialSpace<Val = F>,
+{
+    validate_participating(&vi, participation)?;
+    Ok(generate_air_proof_inputs(
+        extra_capacity_bits,
+        vi,
+        participation,
+    ))
+}
+
+/// Same as [`generate_air_proof_inputs`], but generates traces of
+/// `chunk_size` participating signatures at a time and fills them into the
+/// chip traces directly, so memory of [`VerificationTrace`]s is bounded by
+/// `chunk_size` instead of number of signatures, and with AIRs in `layout`.
+///
+/// # Panics
+///
+/// Panics if `layout` is [`SignerSetLayout::Registry`], which requires
+/// [`generate_air_proof_inputs_with_registry`] instead.
+pub fn generate_air_proof_inputs_in_chunks<SC: StarkGenericConfig>(
+    extra_capacity_bits: usize,
+    vi: VerificationInput,
+    participation: &[bool],
+    chunk_size: usize,
+    layout: Layout,
+) -> (Vec<AirRef<SC>>, Vec<AirProofInput<SC>>)
+where
+    Domain<SC>: PolynomialSpace<Val = F>,
+{
+    generate_air_proof_inputs_impl(
+        extra_capacity_bits,
+        vi,
+        participation,
+        chunk_size,
+        layout,
+        None,
+    )
+}
+
+/// Same as [`generate_air_proof_inputs_in_chunks`], but public keys and
+/// weights of all signatures are looked up from the cached trace of
+/// `registry` instead of being committed as public values, proving
+/// participating signatures weigh at least `threshold`.
+///
+/// # Panics
+///
+/// Panics if `layout` is not [`SignerSetLayout::Registry`], if public keys
+/// of `vi` differ from the ones in `registry`, or if participating signatures
+/// weigh less than `threshold`.
+pub fn generate_air_proof_inputs_with_registry<SC: StarkGenericConfig>(
+    extra_capacity_bits: usize,
+    vi: VerificationInput,
+    participation: &[bool],
+    chunk_size: usize,
+    layout: Layout,
+    registry: &Registry<SC>,
+    threshold: u32,
+) -> (Vec<AirRef<SC>>, Vec<AirProofInput<SC>>)
+where
+    Domain<SC>: PolynomialSpace<Val = F>,
+{
+    generate_air_proof_inputs_impl(
+        extra_capacity_bits,
+        vi,
+        participation,
+        chunk_size,
+        layout,
+        Some((registry, threshold)),
+    )
+}
+
+#[instrument(name = "generate hash-sig aggregation traces", skip_all)]
+fn generate_air_proof_inputs_impl<SC: StarkGenericConfig>(
+    extra_capacity_bits: usize,
+    vi: VerificationInput,
+    participation: &[bool],
+    chunk_size: usize,
+    layout: Layout,
+    registry: Option<(&Registry<SC>, u32)>,
+) -> (Vec<AirRef<SC>>, Vec<AirProofInput<SC>>)
+where
+    Domain<SC>: PolynomialSpace<Val = F>,
+{
+    assert_eq!(vi.pairs.len(), participation.len());
+    assert!(
+        participation.contains(&true),
+        "At least one signature should be participating"
+    );
+    assert!(chunk_size > 0, "Chunk size should be positive");
+    let VerificationInput { epoch, msg, pairs } = vi;
+    let encoded_msg = encode_msg(msg);
+    let public_keys = pairs.iter().map(|(pk, _)| *pk).collect::<Vec<PublicKey>>();
+    let unit_weights;
+    let (weights, threshold) = match (layout.signer_set, registry) {
+        (SignerSetLayout::Sponge, None) => {
+            unit_weights = vec![1; public_keys.len()];
+            let threshold = participating_weight(participation, &unit_weights);
+            (unit_weights.as_slice(), threshold)
+        }
+        (SignerSetLayout::Registry, Some((registry, threshold))) => {
+            assert_eq!(public_keys.len(), registry.public_keys().len());
+            assert!(
+                zip(&public_keys, registry.public_keys()).all(|(lhs, rhs)| {
+                    lhs.parameter == rhs.parameter && lhs.merkle_root == rhs.merkle_root
+                }),
+                "Public keys should be the same as the ones in registry"
+            );
+            (registry.weights(), threshold)
+        }
+        _ => panic!("Registry should be given iff signer set layout is registry"),
+    };
+    assert!(
+        participating_weight(participation, weights) >= threshold,
+        "Participating signatures should weigh at least the threshold"
+    );
+    let participating_pairs = zip(pairs, participation)
+        .filter(|(_, is_participating)| **is_participating)
+        .map(|(pair, _)| pair)
+        .collect::<Vec<_>>();
+    let num_participating = participating_pairs.len();
+    let signer_keys = participating_pairs
+        .iter()
+        .map(|(pk, _)| signer_key(pk))
+        .collect::<Vec<_>>();
+    info!(
+        peak_memory = estimate_peak_memory(
+            extra_capacity_bits,
+            participation.len(),
+            num_participating,
+            chunk_size,
+            layout
+        ),
+        "estimated peak memory of witness generation in bytes"
+    );
+
+    let mut chain_traces = ChainTraces::uninit(
+        extra_capacity_bits,
+        num_participating,
+        layout.chain_compressions_per_row.get(),
+        layout.chain_instances.get(),
+    );
+    let mut merkle_traces =
+        MerkleTraces::uninit(extra_capacity_bits, num_participating, layout.merkle);
+    let mut decomposition_trace =
+        decomposition::generation::uninit_trace(extra_capacity_bits, num_participating);
+    let range_checker = RangeChecker::new(RANGE_CHECK_BITS);
+    let mut msg_hashes = Vec::with_capacity(num_participating);
+    participating_pairs
+        .chunks(chunk_size)
+        .enumerate()
+        .for_each(|(chunk_idx, pairs)| {
+            let sig_offset = chunk_idx * chunk_size;
+            let traces = pairs
+                .par_iter()
+                .map(|(pk, sig)| VerificationTrace::generate(epoch, encoded_msg, *pk, *sig))
+                .collect::<Vec<_>>();
+            join(
+                || {
+                    join(
+                        || {
+                            chain_traces.generate_trace_rows_chunk(sig_offset, &traces);
+                        },
+                        || {
+                            merkle_traces.generate_trace_rows_chunk(
+                                &range_checker,
+                                epoch,
+                                encoded_msg,
+                                sig_offset,
+                                &traces,
+                            );
+                        },
+                    )
+                },
+                || {
+                    decomposition::generation::generate_trace_rows_chunk(
+                        &mut decomposition_trace,
+                        &range_checker,
+                        sig_offset,
+                        &traces,
+                    );
+                },
+            );
+            msg_hashes.extend(traces.iter().map(|trace| trace.msg_hash));
+        });
+    drop(participating_pairs);
+
+    let main = MainChip::new(
+        extra_capacity_bits,
+        participation,
+        &public_keys,
+        weights,
+        threshold,
+        &msg_hashes,
+        &range_checker,
+    );
+    let (main_api, (chain_traces, (merkle_traces, decomposition_trace))) = join(
+        || main.generate_air_proof_input(),
+        || {
+            join(
+                || chain_traces.finalize(),
+                || {
+                    join(
+                        || merkle_traces.finalize(num_participating),
+                        || {
+                            decomposition::generation::finalize(
+                                decomposition_trace,
+                                num_participating,
+                            )
+                        },
+                    )
+                },
+            )
+        },
+    );
+    let unique_signer_api =
+        UniqueSignerChip::new(extra_capacity_bits, &signer_keys, &range_checker)
+            .generate_air_proof_input();
+    let msg_encoding_api =
+        MsgEncodingChip::new(extra_capacity_bits, msg, num_participating, &range_checker)
+            .generate_air_proof_input();
+    // The table is sized by recorded range checks, which `trace_heights` and
+    // `estimate_peak_memory` assume to be `RANGE_CHECK_BITS` wide.
+    assert_eq!(range_checker.bits(), RANGE_CHECK_BITS);
+    let range_check_chip = RangeCheckChip::new(extra_capacity_bits, range_checker);
+    let participation_chip = ParticipationChip::new(extra_capacity_bits, participation);
+    let mut air_proof_inputs = vec![main_api];
+    air_proof_inputs.extend(chain_traces.into_iter().map(|(sig_offset, trace)| {
+        air_proof_input(trace, chain::public_values(epoch, sig_offset))
+    }));
+    air_proof_inputs.extend(merkle_traces.air_proof_inputs(epoch));
+    air_proof_inputs.extend([
+        air_proof_input(decomposition_trace, Vec::new()),
+        range_check_chip.generate_air_proof_input(),
+        unique_signer_api,
+        msg_encoding_api,
+        participation_chip.generate_air_proof_input(),
+    ]);
+    air_proof_inputs.push(match registry {
+        Some((registry, _)) => {
+            RegistryChip::new(extra_capacity_bits, registry).generate_air_proof_input()
+        }
+        None => SignerSetChip::new(extra_capacity_bits, &public_keys).generate_air_proof_input(),
+    });
+    (airs(participation.len(), layout), air_proof_inputs)
+}
+
+/// Traces of AIRs in a [`MerkleLayout`].
+enum MerkleTraces<T> {
+    Fused(T),
+    Split {
+        msg_hash: T,
+        merkle_leaf: T,
+        merkle_path: T,
+    },
+}
+
+impl MerkleTraces<UninitTrace<F>> {
+    fn uninit(extra_capacity_bits: usize, num_sigs: usize, layout: MerkleLayout) -> Self {
+        match layout {
+            MerkleLayout::Fused => Self::Fused(merkle_tree::generation::uninit_trace(
+                extra_capacity_bits,
+                num_sigs,
+            )),
+            MerkleLayout::Split => Self::Split {
+                msg_hash: msg_hash::generation::uninit_trace(extra_capacity_bits, num_sigs),
+                merkle_leaf: merkle_leaf::generation::uninit_trace(extra_capacity_bits, num_sigs),
+                merkle_path: merkle_path::generation::uninit_trace(extra_capacity_bits, num_sigs),
+            },
+        }
+    }
+
+    fn generate_trace_rows_chunk(
+        &mut self,
+        range_checker: &RangeChecker,
+        epoch: u32,
+        encoded_msg: [F; MSG_FE_LEN],
+        sig_offset: usize,
+        traces: &[VerificationTrace],
+    ) {
+        match self {
+            Self::Fused(trace) => merkle_tree::generation::generate_trace_rows_chunk(
+                trace,
+                range_checker,
+                epoch,
+                encoded_msg,
+                sig_offset,
+                traces,
+            ),
+            Self::Split {
+                msg_hash,
+                merkle_leaf,
+                merkle_path,
+            } => {
+                join(
+                    || {
+                        msg_hash::generation::generate_trace_rows_chunk(
+                            msg_hash,
+                            epoch,
+                            encoded_msg,
+                            sig_offset,
+                            traces,
+                        );
+                    },
+                    || {
+                        let merkle_leaf_hashes = merkle_leaf::generation::generate_trace_rows_chunk(
+                            merkle_leaf,
+                            epoch,
+                            sig_offset,
+                            traces,
+                        );
+                        merkle_path::generation::generate_trace_rows_chunk(
+                            merkle_path,
+                            range_checker,
+                            epoch,
+                            sig_offset,
+                            traces,
+                            &merkle_leaf_hashes,
+                        );
+                    },
+                );
+            }
+        }
+    }
+
+    fn finalize(self, num_sigs: usize) -> MerkleTraces<RowMajorMatrix<F>> {
+        match self {
+            Self::Fused(trace) => {
+                MerkleTraces::Fused(merkle_tree::generation::finalize(trace, num_sigs))
+            }
+            Self::Split {
+                msg_hash,
+                merkle_leaf,
+                merkle_path,
+            } => {
+                let (msg_hash, (merkle_leaf, merkle_path)) = join(
+                    || msg_hash::generation::finalize(msg_hash, num_sigs),
+                    || {
+                        join(
+                            || merkle_leaf::generation::finalize(merkle_leaf, num_sigs),
+                            || merkle_path::generation::finalize(merkle_path, num_sigs),
+                        )
+                    },
+                );
+                MerkleTraces::Split {
+                    msg_hash,
+                    merkle_leaf,
+                    merkle_path,
+                }
+            }
+        }
+    }
+}
+
+impl MerkleTraces<RowMajorMatrix<F>> {
+    fn air_proof_inputs<SC: StarkGenericConfig>(self, epoch: u32) -> Vec<AirProofInput<SC>>
+    where
+        Domain<SC>: PolynomialSpace<Val = F>,
+    {
+        match self {
+            Self::Fused(trace) => vec![air_proof_input(trace, merkle_tree::public_values(epoch))],
+            Self::Split {
+                msg_hash,
+                merkle_leaf,
+                merkle_path,
+            } => vec![
+                air_proof_input(msg_hash, msg_hash::public_values(epoch)),
+                air_proof_input(merkle_leaf, merkle_leaf::public_values(epoch)),
+                air_proof_input(merkle_path, merkle_path::public_values(epoch)),
+            ],
+        }
+    }
+}
+
+fn air_proof_input<SC: StarkGenericConfig>(
+    trace: RowMajorMatrix<F>,
+    public_values: Vec<F>,
+) -> AirProofInput<SC>
+where
+    Domain<SC>: PolynomialSpace<Val = F>,
+{
+    AirProofInput {
+        cached_mains_pdata: Vec::new(),
+        raw: AirProofRawInput {
+            cached_mains: Vec::new(),
+            common_main: Some(trace),
+            public_values,
+        },
+    }
+}
@@ -1,18 +1,28 @@
-use crate::poseidon2::{
+use_instance!({
     chip::{
-        main::column::{MainCols, NUM_MAIN_COLS},
+        decomposition::LIMB_BITS,
+        main::{
+            column::{MainCols, NUM_MAIN_COLS},
+            excess_limb_bits,
+        },
+        range_check::send_range_check,
+        unique_signer::KEY_FE_LEN,
         Bus,
     },
     F,
-};
+});
 use core::{borrow::Borrow, iter};
 use openvm_stark_backend::{
     interaction::InteractionBuilder,
     rap::{BaseAirWithPublicValues, PartitionedBaseAir},
 };
 use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_field::FieldAlgebra;
 use p3_matrix::Matrix;
 
+/// One row for each signature, which sends its public key and message hash
+/// to other chips if participating, and accumulates weight of participating
+/// signatures to prove it's at least the threshold in public values.
 #[derive(Clone, Copy, Debug)]
 pub struct MainAir;
 
@@ -24,13 +34,20 @@ impl BaseAir<F> for MainAir {
 
 impl PartitionedBaseAir<F> for MainAir {}
 
-impl BaseAirWithPublicValues<F> for MainAir {}
+impl BaseAirWithPublicValues<F> for MainAir {
+    fn num_public_values(&self) -> usize {
+        2
+    }
+}
 
 impl<AB> Air<AB> for MainAir
 where
     AB: InteractionBuilder<F = F> + AirBuilderWithPublicValues,
 {
     fn eval(&self, builder: &mut AB) {
+        let [threshold, total_weight]: [AB::Expr; 2] =
+            [0, 1].map(|idx| builder.public_values()[idx].into());
+
         let main = builder.main();
 
         let local = main.row_slice(0);
@@ -39,23 +56,91 @@ where
         let next: &MainCols<AB::Var> = (*next).borrow();
 
         // When every rows
-        local.is_active.eval_every_row(builder);
+        builder.assert_bool(local.is_participating);
 
         // When first row
-        builder.when_first_row().assert_one(*local.is_active);
+        {
+            let mut builder = builder.when_first_row();
+
+            builder.assert_zero(local.sig_idx);
+            builder.assert_zero(local.participant_idx);
+            builder.assert_eq(local.weight_sum, local.is_participating * local.weight);
+            builder.assert_eq(local.total_weight, local.weight);
+        }
 
         // When transition
-        local
-            .is_active
-            .eval_transition(&mut builder.when_transition(), &next.is_active);
+        {
+            let mut builder = builder.when_transition();
+
+            builder.assert_eq(next.sig_idx, local.sig_idx + AB::Expr::ONE);
+            builder.assert_eq(
+                next.participant_idx,
+                local.participant_idx + local.is_participating,
+            );
+            builder.assert_eq(
+                next.weight_sum,
+                local.weight_sum + next.is_participating * next.weight,
+            );
+            builder.assert_eq(next.total_weight, local.total_weight + next.weight);
+        }
+
+        // When last row
+        {
+            let mut builder = builder.when_last_row();
+
+            builder.assert_eq(local.total_weight, total_weight);
+            builder.assert_eq(
+                local.weight_sum - threshold,
+                local
+                    .excess_limbs
+                    .into_iter()
+                    .enumerate()
+                    .map(|(idx, limb)| limb * AB::Expr::from_canonical_u32(1 << (idx * LIMB_BITS)))
+                    .sum::<AB::Expr>(),
+            );
+        }
 
         // Interaction
+        receive_participation(builder, local);
+        send_signer(builder, local);
         send_parameter(builder, local);
+        send_merkle_root(builder, local);
         send_msg_hash(builder, local);
         send_decomposition(builder, local);
+        send_unique_signer(builder, local);
+        send_range_checks(builder, local);
     }
 }
 
+/// Receives index of participating signature from participation chip, which
+/// only sends indices whose bits are set in the bitmap.
+#[inline]
+fn receive_participation<AB>(builder: &mut AB, cols: &MainCols<AB::Var>)
+where
+    AB: InteractionBuilder<F = F>,
+{
+    builder.push_receive(
+        Bus::Participation as usize,
+        [cols.sig_idx],
+        cols.is_participating,
+    );
+}
+
+#[inline]
+fn send_signer<AB>(builder: &mut AB, cols: &MainCols<AB::Var>)
+where
+    AB: InteractionBuilder<F = F>,
+{
+    builder.push_send(
+        Bus::SignerSet as usize,
+        iter::once(cols.sig_idx)
+            .chain(cols.parameter)
+            .chain(cols.merkle_root)
+            .chain([cols.weight]),
+        AB::Expr::ONE,
+    );
+}
+
 #[inline]
 fn send_parameter<AB>(builder: &mut AB, cols: &MainCols<AB::Var>)
 where
@@ -63,8 +148,22 @@ where
 {
     builder.push_send(
         Bus::Parameter as usize,
-        iter::once(cols.sig_idx).chain(cols.parameter),
-        *cols.is_active,
+        iter::once(cols.participant_idx).chain(cols.parameter),
+        cols.is_participating,
+    );
+}
+
+#[inline]
+fn send_merkle_root<AB>(builder: &mut AB, cols: &MainCols<AB::Var>)
+where
+    AB: InteractionBuilder<F = F>,
+{
+    builder.push_send(
+        Bus::MerkleRoot as usize,
+        iter::once(cols.participant_idx)
+            .chain(cols.parameter)
+            .chain(cols.merkle_root),
+        cols.is_participating,
     );
 }
 
@@ -74,12 +173,11 @@ where
     AB: InteractionBuilder<F = F>,
 {
     builder.push_send(
-        Bus::MerkleRootAndMsgHash as usize,
-        iter::once(cols.sig_idx)
+        Bus::MsgHash as usize,
+        iter::once(cols.participant_idx)
             .chain(cols.parameter)
-            .chain(cols.merkle_root)
             .chain(cols.msg_hash),
-        *cols.is_active,
+        cols.is_participating,
     );
 }
 
@@ -90,7 +188,32 @@ where
 {
     builder.push_send(
         Bus::Decomposition as usize,
-        iter::once(cols.sig_idx).chain(cols.msg_hash.into_iter().rev()),
-        *cols.is_active,
+        iter::once(cols.participant_idx).chain(cols.msg_hash),
+        cols.is_participating,
     );
 }
+
+#[inline]
+fn send_unique_signer<AB>(builder: &mut AB, cols: &MainCols<AB::Var>)
+where
+    AB: InteractionBuilder<F = F>,
+{
+    builder.push_send(
+        Bus::UniqueSigner as usize,
+        cols.merkle_root[..KEY_FE_LEN].iter().copied(),
+        cols.is_participating,
+    );
+}
+
+#[inline]
+fn send_range_checks<AB>(builder: &mut AB, cols: &MainCols<AB::Var>)
+where
+    AB: InteractionBuilder<F = F>,
+{
+    cols.excess_limbs
+        .into_iter()
+        .enumerate()
+        .for_each(|(idx, limb)| {
+            send_range_check(builder, limb, excess_limb_bits(idx), AB::Expr::ONE);
+        });
+}
@@ -1,19 +1,33 @@
-use crate::{
-    gadget::lower_rows_filter::LowerRowsFilterCols,
-    poseidon2::hash_sig::{HASH_FE_LEN, MSG_HASH_FE_LEN, PARAM_FE_LEN},
-    util::AlignBorrow,
-};
+use_instance!({
+    chip::main::NUM_EXCESS_LIMBS,
+    hash_sig::{HASH_FE_LEN, MSG_HASH_FE_LEN, PARAM_FE_LEN},
+});
+use crate::util::AlignBorrow;
 use core::borrow::{Borrow, BorrowMut};
 
 pub const NUM_MAIN_COLS: usize = size_of::<MainCols<u8>>();
 
 #[repr(C)]
 pub struct MainCols<T> {
-    pub is_active: LowerRowsFilterCols<T>,
+    /// Signature index in the whole batch.
     pub sig_idx: T,
+    /// Whether this signature is participating or not.
+    pub is_participating: T,
+    /// Index among participating signatures, used as signature index by other
+    /// chips.
+    pub participant_idx: T,
     pub parameter: [T; PARAM_FE_LEN],
     pub merkle_root: [T; HASH_FE_LEN],
     pub msg_hash: [T; MSG_HASH_FE_LEN],
+    /// Weight of signer, looked up together with public key from signer set.
+    pub weight: T,
+    /// Accumulated weight of participating signatures up to this row.
+    pub weight_sum: T,
+    /// Accumulated weight of all signatures up to this row.
+    pub total_weight: T,
+    /// Limbs of `weight_sum` minus threshold in little-endian in last row, or
+    /// zero otherwise, which are range checked so the threshold is met.
+    pub excess_limbs: [T; NUM_EXCESS_LIMBS],
 }
 
 impl<T> AlignBorrow<T> for MainCols<T> {
@@ -1,51 +1,186 @@
-use crate::{
-    poseidon2::{
-        chip::main::column::{MainCols, NUM_MAIN_COLS},
-        hash_sig::VerificationTrace,
-        F,
+use_instance!({
+    chip::{
+        decomposition::{LIMB_BITS, LIMB_MASK},
+        main::{
+            column::{MainCols, NUM_MAIN_COLS},
+            excess_limb_bits, public_values, trace_height, MainAir, NUM_EXCESS_LIMBS,
+        },
+        range_check::RangeChecker,
     },
-    util::field::{MaybeUninitField, MaybeUninitFieldSlice},
+    hash_sig::{PublicKey, MSG_HASH_FE_LEN},
+    F,
+});
+use crate::util::{
+    field::{MaybeUninitField, MaybeUninitFieldSlice},
+    par_zip,
+    trace::UninitTrace,
 };
-use core::mem::MaybeUninit;
-use p3_matrix::dense::{RowMajorMatrix, RowMajorMatrixViewMut};
+use core::{any::type_name, array::from_fn, iter, mem::MaybeUninit};
+use itertools::Itertools;
+use openvm_stark_backend::{
+    config::{Domain, StarkGenericConfig},
+    prover::types::{AirProofInput, AirProofRawInput},
+    rap::AnyRap,
+    Chip, ChipUsageGetter,
+};
+use p3_commit::PolynomialSpace;
+use p3_field::FieldAlgebra;
+use p3_matrix::dense::RowMajorMatrix;
 use p3_maybe_rayon::prelude::*;
+use std::sync::Arc;
+
+#[derive(Clone, Copy, Debug)]
+pub struct MainChip<'a> {
+    extra_capacity_bits: usize,
+    participation: &'a [bool],
+    public_keys: &'a [PublicKey],
+    weights: &'a [u32],
+    threshold: u32,
+    msg_hashes: &'a [[F; MSG_HASH_FE_LEN]],
+    range_checker: &'a RangeChecker,
+}
+
+impl<'a> MainChip<'a> {
+    /// Returns a `MainChip` with `public_keys` and `weights` of all
+    /// signatures and `msg_hashes` of participating signatures in order.
+    ///
+    /// Weight of participating signatures is assumed to be at least
+    /// `threshold`, otherwise the witness is unprovable.
+    pub const fn new(
+        extra_capacity_bits: usize,
+        participation: &'a [bool],
+        public_keys: &'a [PublicKey],
+        weights: &'a [u32],
+        threshold: u32,
+        msg_hashes: &'a [[F; MSG_HASH_FE_LEN]],
+        range_checker: &'a RangeChecker,
+    ) -> Self {
+        Self {
+            extra_capacity_bits,
+            participation,
+            public_keys,
+            weights,
+            threshold,
+            msg_hashes,
+            range_checker,
+        }
+    }
+}
+
+impl ChipUsageGetter for MainChip<'_> {
+    fn air_name(&self) -> String {
+        type_name::<MainAir>().to_string()
+    }
+
+    fn current_trace_height(&self) -> usize {
+        trace_height(self.participation)
+    }
 
-pub const fn trace_height(traces: &[VerificationTrace]) -> usize {
-    traces.len().next_power_of_two()
+    fn trace_width(&self) -> usize {
+        NUM_MAIN_COLS
+    }
 }
 
+impl<SC: StarkGenericConfig> Chip<SC> for MainChip<'_>
+where
+    Domain<SC>: PolynomialSpace<Val = F>,
+{
+    fn air(&self) -> Arc<dyn AnyRap<SC>> {
+        Arc::new(MainAir)
+    }
+
+    fn generate_air_proof_input(self) -> AirProofInput<SC> {
+        AirProofInput {
+            cached_mains_pdata: Vec::new(),
+            raw: AirProofRawInput {
+                cached_mains: Vec::new(),
+                common_main: Some(generate_trace_rows(
+                    self.extra_capacity_bits,
+                    self.participation,
+                    self.public_keys,
+                    self.weights,
+                    self.threshold,
+                    self.msg_hashes,
+                    self.range_checker,
+                )),
+                public_values: public_values(self.threshold, self.weights.iter().sum()),
+            },
+        }
+    }
+}
+
+/// Generates rows of all signatures, and records range checks of weight sum
+/// minus `threshold` into `range_checker`.
+///
+/// Weight of participating signatures is assumed to be at least `threshold`.
 pub fn generate_trace_rows(
     extra_capacity_bits: usize,
-    traces: &[VerificationTrace],
+    participation: &[bool],
+    public_keys: &[PublicKey],
+    weights: &[u32],
+    threshold: u32,
+    msg_hashes: &[[F; MSG_HASH_FE_LEN]],
+    range_checker: &RangeChecker,
 ) -> RowMajorMatrix<F> {
-    let height = trace_height(traces);
-    let size = height * NUM_MAIN_COLS;
-    let mut vec = Vec::with_capacity(size << extra_capacity_bits);
-    let trace = &mut vec.spare_capacity_mut()[..size];
-    let trace = RowMajorMatrixViewMut::new(trace, NUM_MAIN_COLS);
-
-    let (prefix, rows, suffix) = unsafe { trace.values.align_to_mut::<MainCols<MaybeUninit<_>>>() };
-    assert!(prefix.is_empty(), "Alignment should match");
-    assert!(suffix.is_empty(), "Alignment should match");
-    assert_eq!(rows.len(), height);
-
-    rows.par_iter_mut().enumerate().for_each(|(sig_idx, row)| {
-        if let Some(trace) = traces.get(sig_idx) {
-            row.is_active.populate(true);
-            row.sig_idx.write_usize(sig_idx);
-            row.parameter.fill_from_slice(&trace.pk.parameter);
-            row.merkle_root.fill_from_slice(&trace.pk.merkle_root);
-            row.msg_hash.fill_from_slice(&trace.msg_hash);
-        } else {
-            row.is_active.populate(false);
-            row.sig_idx.write_zero();
-            row.parameter.fill_zero();
-            row.merkle_root.fill_zero();
-            row.msg_hash.fill_zero();
-        }
+    let height = trace_height(participation);
+    let mut trace = UninitTrace::new(NUM_MAIN_COLS, height, extra_capacity_bits);
+    let rows = unsafe { trace.rows_mut::<MainCols<MaybeUninit<F>>>() };
+
+    let accumulators = participation
+        .iter()
+        .chain(iter::repeat_n(&false, height - participation.len()))
+        .enumerate()
+        .scan(
+            (0, 0, 0),
+            |(participant_idx, weight_sum, total_weight), (sig_idx, is_participating)| {
+                let idx = *participant_idx;
+                let weight = weights.get(sig_idx).copied().unwrap_or_default();
+                *participant_idx += usize::from(*is_participating);
+                *weight_sum += if *is_participating { weight } else { 0 };
+                *total_weight += weight;
+                Some((*is_participating, idx, weight, *weight_sum, *total_weight))
+            },
+        )
+        .collect_vec();
+    let (.., weight_sum, _) = *accumulators.last().unwrap();
+    let excess_limbs: [u32; NUM_EXCESS_LIMBS] =
+        from_fn(|idx| ((weight_sum - threshold) >> (idx * LIMB_BITS)) & LIMB_MASK);
+    excess_limbs.iter().enumerate().for_each(|(idx, limb)| {
+        range_checker.add_count(*limb, excess_limb_bits(idx));
+        range_checker.add_counts(0, excess_limb_bits(idx), height as u32 - 1);
     });
 
-    unsafe { vec.set_len(size) };
+    par_zip!(rows, accumulators).enumerate().for_each(
+        |(
+            sig_idx,
+            (row, (is_participating, participant_idx, weight, weight_sum, total_weight)),
+        )| {
+            row.sig_idx.write_usize(sig_idx);
+            row.is_participating.write_bool(is_participating);
+            row.participant_idx.write_usize(participant_idx);
+            row.weight.write_u32(weight);
+            row.weight_sum.write_u32(weight_sum);
+            row.total_weight.write_u32(total_weight);
+            if sig_idx == height - 1 {
+                row.excess_limbs
+                    .fill_from_iter(excess_limbs.map(F::from_canonical_u32));
+            } else {
+                row.excess_limbs.fill_zero();
+            }
+            if let Some(pk) = public_keys.get(sig_idx) {
+                row.parameter.fill_from_slice(&pk.parameter);
+                row.merkle_root.fill_from_slice(&pk.merkle_root);
+            } else {
+                row.parameter.fill_zero();
+                row.merkle_root.fill_zero();
+            }
+            if is_participating {
+                row.msg_hash.fill_from_slice(&msg_hashes[participant_idx]);
+            } else {
+                row.msg_hash.fill_zero();
+            }
+        },
+    );
 
-    RowMajorMatrix::new(vec, NUM_MAIN_COLS)
+    unsafe { trace.assume_init() }
 }
@@ -1,68 +1,95 @@
-use crate::poseidon2::{
-    chip::main::{air::MainAir, column::NUM_MAIN_COLS},
-    hash_sig::VerificationTrace,
-    F,
-};
-use core::any::type_name;
-use generation::{generate_trace_rows, trace_height};
-use openvm_stark_backend::{
-    config::{Domain, StarkGenericConfig},
-    prover::types::{AirProofInput, AirProofRawInput},
-    rap::AnyRap,
-    Chip, ChipUsageGetter,
-};
-use p3_commit::PolynomialSpace;
-use std::sync::Arc;
+use_instance!({chip::decomposition::LIMB_BITS, F});
+use p3_field::FieldAlgebra;
 
 mod air;
-mod column;
+pub(super) mod column;
+#[cfg(feature = "prover")]
 mod generation;
 
-#[derive(Clone, Copy, Debug)]
-pub struct MainChip<'a> {
-    extra_capacity_bits: usize,
-    traces: &'a [VerificationTrace],
-}
+pub use air::MainAir;
+#[cfg(feature = "prover")]
+pub use generation::MainChip;
 
-impl<'a> MainChip<'a> {
-    pub const fn new(extra_capacity_bits: usize, traces: &'a [VerificationTrace]) -> Self {
-        Self {
-            extra_capacity_bits,
-            traces,
-        }
-    }
-}
+/// Bits of total weight of a signer set.
+///
+/// Weight sum minus threshold is range checked in `0..1 << TOTAL_WEIGHT_BITS`,
+/// which excludes every negative difference as long as the field modulus is
+/// greater than `1 << (TOTAL_WEIGHT_BITS + 1)`.
+pub const TOTAL_WEIGHT_BITS: usize = 29;
 
-impl ChipUsageGetter for MainChip<'_> {
-    fn air_name(&self) -> String {
-        type_name::<MainAir>().to_string()
-    }
+/// Number of limbs of weight sum minus threshold.
+pub const NUM_EXCESS_LIMBS: usize = TOTAL_WEIGHT_BITS.div_ceil(LIMB_BITS);
 
-    fn current_trace_height(&self) -> usize {
-        trace_height(self.traces)
+/// Returns bits of limb at `idx` of weight sum minus threshold, which is
+/// `LIMB_BITS` except the most significant one.
+pub const fn excess_limb_bits(idx: usize) -> usize {
+    if idx + 1 < NUM_EXCESS_LIMBS {
+        LIMB_BITS
+    } else {
+        TOTAL_WEIGHT_BITS - (NUM_EXCESS_LIMBS - 1) * LIMB_BITS
     }
+}
 
-    fn trace_width(&self) -> usize {
-        NUM_MAIN_COLS
-    }
+/// Returns public values of [`MainAir`], which proves participating
+/// signatures weigh at least `threshold` of `total_weight` of all signers.
+pub fn public_values(threshold: u32, total_weight: u32) -> Vec<F> {
+    vec![
+        F::from_canonical_u32(threshold),
+        F::from_canonical_u32(total_weight),
+    ]
 }
 
-impl<SC: StarkGenericConfig> Chip<SC> for MainChip<'_>
-where
-    Domain<SC>: PolynomialSpace<Val = F>,
-{
-    fn air(&self) -> Arc<dyn AnyRap<SC>> {
-        Arc::new(MainAir)
-    }
+pub const fn trace_height(participation: &[bool]) -> usize {
+    participation.len().next_power_of_two()
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod test {
+    use_instance!({
+        chip::{
+            generate_air_proof_inputs_with_registry, participating_weight, registry::Registry,
+            Layout, SignerSetLayout, DEFAULT_CHUNK_SIZE,
+        },
+        hash_sig::test::mock_vi,
+        E, F,
+    });
+    use crate::util::engine::{Config, Engine};
+    use openvm_stark_backend::utils::disable_debug_builder;
+    use openvm_stark_sdk::engine::StarkEngine;
+    use p3_field::FieldAlgebra;
+
+    #[test]
+    fn threshold() {
+        let engine = Engine::<F, E>::fastest();
+        let vi = mock_vi(6);
+        let participation = [true, false, true, true, false, true];
+        let public_keys = vi.pairs.iter().map(|(pk, _)| *pk).collect::<Vec<_>>();
+        let weights = vec![3, 100, 5, 0, 7, 11];
+        let threshold = participating_weight(&participation, &weights);
+        let registry =
+            Registry::<Config<F, E>>::commit(engine.config().pcs(), public_keys, weights);
+        let layout = Layout {
+            signer_set: SignerSetLayout::Registry,
+            ..Layout::default()
+        };
+        let (airs, air_proof_inputs) = generate_air_proof_inputs_with_registry(
+            engine.log_blowup(),
+            vi,
+            &participation,
+            DEFAULT_CHUNK_SIZE,
+            layout,
+            &registry,
+            threshold,
+        );
 
-    fn generate_air_proof_input(self) -> AirProofInput<SC> {
-        AirProofInput {
-            cached_mains_pdata: Vec::new(),
-            raw: AirProofRawInput {
-                cached_mains: Vec::new(),
-                common_main: Some(generate_trace_rows(self.extra_capacity_bits, self.traces)),
-                public_values: Vec::new(),
-            },
+        disable_debug_builder();
+        for (idx, perturb) in [(0, F::ONE), (1, F::ONE), (1, F::NEG_ONE)] {
+            let mut air_proof_inputs = air_proof_inputs.clone();
+            air_proof_inputs[0].raw.public_values[idx] += perturb;
+            assert!(engine
+                .run_test_impl(airs.clone(), air_proof_inputs)
+                .is_err());
         }
+        engine.run_test_impl(airs, air_proof_inputs).unwrap();
     }
 }
@@ -0,0 +1,314 @@
+use_instance!({
+    chip::{
+        merkle_leaf::{
+            column::{MerkleLeafCols, NUM_MERKLE_LEAF_COLS},
+            poseidon2::{PARTIAL_ROUNDS, WIDTH},
+        },
+        Bus,
+    },
+    hash_sig::{HASH_FE_LEN, PARAM_FE_LEN, SPONGE_CAPACITY_VALUES, SPONGE_RATE, TWEAK_FE_LEN},
+    Poseidon2LinearLayers, F, HALF_FULL_ROUNDS, RC24, SBOX_DEGREE, SBOX_REGISTERS,
+});
+use crate::{
+    gadget::{not, select},
+    util::zip,
+};
+use core::{borrow::Borrow, iter};
+use itertools::Itertools;
+use openvm_stark_backend::{
+    air_builders::sub::SubAirBuilder,
+    interaction::InteractionBuilder,
+    rap::{BaseAirWithPublicValues, PartitionedBaseAir},
+};
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_field::FieldAlgebra;
+use p3_matrix::Matrix;
+use p3_poseidon2_air::{num_cols, Poseidon2Air};
+
+/// Alias for `Poseidon2Air` specialized with merkle-leaf-related parameters.
+type Poseidon2AirMerkleLeaf = Poseidon2Air<
+    F,
+    Poseidon2LinearLayers<WIDTH>,
+    WIDTH,
+    SBOX_DEGREE,
+    SBOX_REGISTERS,
+    HALF_FULL_ROUNDS,
+    PARTIAL_ROUNDS,
+>;
+
+#[derive(Debug)]
+pub struct MerkleLeafAir(Poseidon2AirMerkleLeaf);
+
+impl Default for MerkleLeafAir {
+    fn default() -> Self {
+        Self(Poseidon2Air::new(RC24.into()))
+    }
+}
+
+impl BaseAir<F> for MerkleLeafAir {
+    fn width(&self) -> usize {
+        NUM_MERKLE_LEAF_COLS
+    }
+}
+
+impl PartitionedBaseAir<F> for MerkleLeafAir {}
+
+impl BaseAirWithPublicValues<F> for MerkleLeafAir {
+    fn num_public_values(&self) -> usize {
+        1
+    }
+}
+
+impl<AB> Air<AB> for MerkleLeafAir
+where
+    AB: InteractionBuilder<F = F> + AirBuilderWithPublicValues,
+    AB::Expr: FieldAlgebra<F = F>,
+{
+    fn eval(&self, builder: &mut AB) {
+        self.0
+            .eval(&mut SubAirBuilder::<_, Poseidon2AirMerkleLeaf, _>::new(
+                builder,
+                0
+                    ..num_cols::<
+                        WIDTH,
+                        SBOX_DEGREE,
+                        SBOX_REGISTERS,
+                        HALF_FULL_ROUNDS,
+                        PARTIAL_ROUNDS,
+                    >(),
+            ));
+
+        let epoch: AB::Expr = builder.public_values()[0].into();
+        // `encode_tweak_merkle_tree(0, epoch)`
+        let encoded_tweak_merkle_leaf = [AB::Expr::ONE, epoch];
+
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let next = main.row_slice(1);
+        let local: &MerkleLeafCols<AB::Var> = (*local).borrow();
+        let next: &MerkleLeafCols<AB::Var> = (*next).borrow();
+
+        // When every rows
+        eval_every_row(builder, local);
+
+        // When first row
+        {
+            let mut builder = builder.when_first_row();
+
+            builder.assert_one(*local.is_active);
+            builder.assert_zero(local.sig_idx);
+            eval_sig_first_row(&mut builder, encoded_tweak_merkle_leaf.clone(), local);
+        }
+
+        // When transition
+        {
+            let mut builder = builder.when_transition();
+
+            eval_transition(&mut builder, local, next);
+            eval_sig_transition(&mut builder, local, next);
+            eval_sig_last_row(&mut builder, encoded_tweak_merkle_leaf, local, next);
+        }
+
+        // Interaction
+        receive_merkle_leaf(builder, local, next);
+        send_merkle_leaf_hash(builder, local);
+    }
+}
+
+#[inline]
+fn eval_every_row<AB>(builder: &mut AB, cols: &MerkleLeafCols<AB::Var>)
+where
+    AB: AirBuilder<F = F>,
+{
+    cols.is_active.eval_every_row(builder);
+    cols.sponge_step.eval_every_row(builder);
+    cols.is_receive_merkle_tree.map(|v| builder.assert_bool(v));
+    {
+        let mut builder = builder.when(not((*cols.is_active).into()));
+
+        builder.assert_zero(*cols.sponge_step);
+        builder.assert_zero(
+            cols.is_receive_merkle_tree
+                .iter()
+                .copied()
+                .map_into()
+                .sum::<AB::Expr>(),
+        );
+    }
+    builder.when(*cols.is_active).assert_eq(
+        AB::Expr::TWO,
+        cols.leaf_chunk_start_ind[1..]
+            .iter()
+            .copied()
+            .map_into()
+            .sum::<AB::Expr>(),
+    );
+    cols.padding()
+        .map(|v| builder.when(cols.is_last_sig_row::<AB>()).assert_zero(v));
+}
+
+#[inline]
+fn eval_transition<AB>(
+    builder: &mut AB,
+    local: &MerkleLeafCols<AB::Var>,
+    next: &MerkleLeafCols<AB::Var>,
+) where
+    AB: AirBuilder<F = F>,
+{
+    local.is_active.eval_transition(builder, &next.is_active);
+    builder.assert_eq(
+        next.sig_idx,
+        select(
+            local.is_last_sig_row::<AB>(),
+            local.sig_idx.into(),
+            select(
+                (*next.is_active).into(),
+                AB::Expr::ZERO,
+                local.sig_idx + AB::Expr::ONE,
+            ),
+        ),
+    );
+}
+
+#[inline]
+fn eval_sig_first_row<AB>(
+    builder: &mut AB,
+    encoded_tweak_merkle_leaf: [AB::Expr; TWEAK_FE_LEN],
+    cols: &MerkleLeafCols<AB::Var>,
+) where
+    AB: AirBuilder<F = F>,
+{
+    cols.sponge_step.eval_first_row(builder);
+    builder.assert_zero(cols.leaf_chunk_idx);
+    (0..SPONGE_RATE)
+        .step_by(HASH_FE_LEN)
+        .for_each(|i| builder.assert_one(cols.leaf_chunk_start_ind[i]));
+    zip!(cols.merkle_parameter(), cols.parameter).for_each(|(a, b)| builder.assert_eq(a, b));
+    zip!(cols.encoded_tweak_merkle(), encoded_tweak_merkle_leaf)
+        .for_each(|(a, b)| builder.assert_eq(a, b));
+    zip!(
+        &cols.perm.inputs[PARAM_FE_LEN + TWEAK_FE_LEN..SPONGE_RATE],
+        &cols.sponge_block[PARAM_FE_LEN + TWEAK_FE_LEN..]
+    )
+    .for_each(|(a, b)| builder.assert_eq(*a, *b));
+    zip!(&cols.perm.inputs[SPONGE_RATE..], SPONGE_CAPACITY_VALUES)
+        .for_each(|(a, b)| builder.assert_eq(*a, b));
+}
+
+#[inline]
+fn eval_sig_transition<AB>(
+    builder: &mut AB,
+    local: &MerkleLeafCols<AB::Var>,
+    next: &MerkleLeafCols<AB::Var>,
+) where
+    AB: AirBuilder<F = F>,
+{
+    let mut builder = builder.when(local.is_sig_transition::<AB>());
+
+    local
+        .sponge_step
+        .eval_transition(&mut builder, &next.sponge_step);
+    (1..=HASH_FE_LEN).for_each(|i| {
+        (i - 1..HASH_FE_LEN).step_by(HASH_FE_LEN).for_each(|j| {
+            builder
+                .when(local.leaf_chunk_start_ind[i])
+                .assert_one(next.leaf_chunk_start_ind[j]);
+        });
+    });
+    zip!(next.perm.inputs, local.sponge_output())
+        .enumerate()
+        .for_each(|(idx, (input, output))| {
+            if let Some(block) = next.sponge_block.get(idx).copied() {
+                builder.assert_eq(input, output + block.into());
+            } else {
+                builder.assert_eq(input, output);
+            }
+        });
+    zip!(next.parameter, local.parameter).for_each(|(a, b)| builder.assert_eq(a, b));
+}
+
+#[inline]
+fn eval_sig_last_row<AB>(
+    builder: &mut AB,
+    encoded_tweak_merkle_leaf: [AB::Expr; TWEAK_FE_LEN],
+    local: &MerkleLeafCols<AB::Var>,
+    next: &MerkleLeafCols<AB::Var>,
+) where
+    AB: AirBuilder<F = F>,
+{
+    let mut builder = builder.when(local.is_last_sig_row::<AB>());
+
+    eval_sig_first_row(
+        &mut builder.when(*next.is_active),
+        encoded_tweak_merkle_leaf,
+        next,
+    );
+}
+
+#[inline]
+fn receive_merkle_leaf<AB>(
+    builder: &mut AB,
+    local: &MerkleLeafCols<AB::Var>,
+    next: &MerkleLeafCols<AB::Var>,
+) where
+    AB: InteractionBuilder<F = F>,
+{
+    builder.push_receive(
+        Bus::MerkleLeaf as usize,
+        [local.sig_idx.into(), local.leaf_chunk_idx.into()]
+            .into_iter()
+            .chain(local.sponge_block[..HASH_FE_LEN].iter().copied().map_into()),
+        local.is_receive_merkle_tree[0] * local.leaf_chunk_start_ind[0].into(),
+    );
+    builder.push_receive(
+        Bus::MerkleLeaf as usize,
+        [
+            local.sig_idx.into(),
+            local.leaf_chunk_idx.into() + local.leaf_chunk_start_ind[0].into(),
+        ]
+        .into_iter()
+        .chain((0..HASH_FE_LEN).map(|i| {
+            (1..)
+                .take(HASH_FE_LEN)
+                .map(|j| local.leaf_chunk_start_ind[j] * local.sponge_block[j + i])
+                .sum()
+        })),
+        local.is_receive_merkle_tree[1],
+    );
+    builder.push_receive(
+        Bus::MerkleLeaf as usize,
+        [
+            local.sig_idx.into(),
+            local.leaf_chunk_idx.into() + local.leaf_chunk_start_ind[0].into() + AB::Expr::ONE,
+        ]
+        .into_iter()
+        .chain((0..HASH_FE_LEN).map(|i| {
+            (1 + HASH_FE_LEN..)
+                .take(HASH_FE_LEN)
+                .map(|j| {
+                    local.leaf_chunk_start_ind[j]
+                        * (if j + i < SPONGE_RATE {
+                            local.sponge_block[j + i]
+                        } else {
+                            next.sponge_block[j + i - SPONGE_RATE]
+                        })
+                })
+                .sum()
+        })),
+        local.is_receive_merkle_tree[2] * not(local.is_last_sig_row::<AB>()),
+    );
+}
+
+#[inline]
+fn send_merkle_leaf_hash<AB>(builder: &mut AB, cols: &MerkleLeafCols<AB::Var>)
+where
+    AB: InteractionBuilder<F = F>,
+{
+    builder.push_send(
+        Bus::MerkleLeafHash as usize,
+        iter::once(cols.sig_idx)
+            .chain(cols.parameter)
+            .chain(cols.merkle_leaf_hash()),
+        cols.is_last_sig_row::<AB>(),
+    );
+}
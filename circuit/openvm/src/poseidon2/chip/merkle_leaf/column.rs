@@ -0,0 +1,121 @@
+use_instance!({
+    chip::merkle_leaf::poseidon2::{PARTIAL_ROUNDS, WIDTH},
+    hash_sig::{
+        HASH_FE_LEN, PARAM_FE_LEN, SPONGE_INPUT_SIZE, SPONGE_PERM, SPONGE_RATE, TWEAK_FE_LEN,
+    },
+    HALF_FULL_ROUNDS, SBOX_DEGREE, SBOX_REGISTERS,
+});
+use crate::{
+    gadget::{cycle_int::CycleInt, lower_rows_filter::LowerRowsFilterCols},
+    util::AlignBorrow,
+};
+use core::{
+    array::from_fn,
+    borrow::{Borrow, BorrowMut},
+    slice,
+};
+use p3_air::AirBuilder;
+use p3_poseidon2_util::air::{outputs, Poseidon2Cols};
+
+pub const NUM_MERKLE_LEAF_COLS: usize = size_of::<MerkleLeafCols<u8>>();
+
+const NUM_PADDING: usize = SPONGE_RATE - SPONGE_INPUT_SIZE % SPONGE_RATE;
+
+#[repr(C)]
+pub struct MerkleLeafCols<T> {
+    pub perm:
+        Poseidon2Cols<T, WIDTH, SBOX_DEGREE, SBOX_REGISTERS, HALF_FULL_ROUNDS, PARTIAL_ROUNDS>,
+    /// Whether this sig is active or not.
+    pub is_active: LowerRowsFilterCols<T>,
+    /// Signature index.
+    pub sig_idx: T,
+    /// Sponge step.
+    pub sponge_step: CycleInt<T, SPONGE_PERM>,
+    /// Block of the leaf absorbed in this sponge step.
+    pub sponge_block: [T; SPONGE_RATE],
+    /// Whether a leaf chunk starts at the index of `sponge_block` or not.
+    pub leaf_chunk_start_ind: [T; SPONGE_RATE],
+    /// Index of the first leaf chunk starting in `sponge_block`.
+    pub leaf_chunk_idx: T,
+    /// Whether to receive the leaf chunks overlapping `sponge_block` or not.
+    pub is_receive_merkle_tree: [T; 3],
+    /// Parameter of the signer, kept across sponge steps.
+    pub parameter: [T; PARAM_FE_LEN],
+}
+
+impl<T> MerkleLeafCols<T> {
+    #[inline]
+    pub const fn as_slice(&self) -> &[T] {
+        unsafe {
+            slice::from_raw_parts(core::ptr::from_ref(self).cast::<T>(), NUM_MERKLE_LEAF_COLS)
+        }
+    }
+
+    #[inline]
+    pub const fn as_slice_mut(&mut self) -> &mut [T] {
+        unsafe {
+            slice::from_raw_parts_mut(core::ptr::from_mut(self).cast::<T>(), NUM_MERKLE_LEAF_COLS)
+        }
+    }
+}
+
+impl<T: Copy> MerkleLeafCols<T> {
+    #[inline]
+    pub fn is_sig_transition<AB: AirBuilder>(&self) -> AB::Expr
+    where
+        T: Into<AB::Expr>,
+    {
+        (*self.is_active).into() - self.is_last_sig_row::<AB>()
+    }
+
+    #[inline]
+    pub fn is_last_sig_row<AB: AirBuilder>(&self) -> AB::Expr
+    where
+        T: Into<AB::Expr>,
+    {
+        self.sponge_step.is_last_step::<AB>()
+    }
+
+    #[inline]
+    pub fn merkle_parameter(&self) -> [T; PARAM_FE_LEN] {
+        from_fn(|i| self.perm.inputs[i])
+    }
+
+    #[inline]
+    pub fn encoded_tweak_merkle(&self) -> [T; TWEAK_FE_LEN] {
+        from_fn(|i| self.perm.inputs[PARAM_FE_LEN + i])
+    }
+
+    #[inline]
+    pub fn padding(&self) -> [T; NUM_PADDING] {
+        from_fn(|i| self.sponge_block[SPONGE_RATE - NUM_PADDING + i])
+    }
+
+    #[inline]
+    pub fn sponge_output(&self) -> [T; WIDTH] {
+        *outputs(&self.perm)
+    }
+
+    #[inline]
+    pub fn merkle_leaf_hash(&self) -> [T; HASH_FE_LEN] {
+        from_fn(|i| outputs(&self.perm)[i])
+    }
+}
+
+impl<T> AlignBorrow<T> for MerkleLeafCols<T> {
+    const SIZE: usize = NUM_MERKLE_LEAF_COLS;
+}
+
+impl<T> Borrow<MerkleLeafCols<T>> for [T] {
+    #[inline]
+    fn borrow(&self) -> &MerkleLeafCols<T> {
+        MerkleLeafCols::align_borrow(self)
+    }
+}
+
+impl<T> BorrowMut<MerkleLeafCols<T>> for [T] {
+    #[inline]
+    fn borrow_mut(&mut self) -> &mut MerkleLeafCols<T> {
+        MerkleLeafCols::align_borrow_mut(self)
+    }
+}
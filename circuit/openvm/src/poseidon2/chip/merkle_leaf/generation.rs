@@ -0,0 +1,141 @@
+use_instance!({
+    chip::merkle_leaf::{
+        column::{MerkleLeafCols, NUM_MERKLE_LEAF_COLS},
+        poseidon2::{PARTIAL_ROUNDS, WIDTH},
+        trace_height, NUM_ROWS_PER_SIG,
+    },
+    hash_sig::{
+        VerificationTrace, CHUNK_SIZE, HASH_FE_LEN, SPONGE_CAPACITY_VALUES,
+        SPONGE_RATE,
+    },
+    Poseidon2LinearLayers, F, HALF_FULL_ROUNDS, RC24, SBOX_DEGREE, SBOX_REGISTERS,
+});
+use crate::util::{
+    field::{MaybeUninitField, MaybeUninitFieldSlice},
+    par_zip,
+    trace::UninitTrace,
+    zip,
+};
+use core::{array::from_fn, iter, mem::MaybeUninit};
+use p3_field::FieldAlgebra;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_maybe_rayon::prelude::*;
+use p3_poseidon2_util::air::{generate_trace_rows_for_perm, outputs};
+
+pub fn uninit_trace(extra_capacity_bits: usize, num_sigs: usize) -> UninitTrace<F> {
+    UninitTrace::new(
+        NUM_MERKLE_LEAF_COLS,
+        trace_height(num_sigs),
+        extra_capacity_bits,
+    )
+}
+
+/// Generates rows of `traces` as signatures starting from `sig_offset`, and
+/// returns their Merkle leaf hashes.
+pub fn generate_trace_rows_chunk(
+    trace: &mut UninitTrace<F>,
+    epoch: u32,
+    sig_offset: usize,
+    traces: &[VerificationTrace],
+) -> Vec<[F; HASH_FE_LEN]> {
+    let rows = unsafe { trace.rows_mut::<MerkleLeafCols<MaybeUninit<F>>>() };
+    let rows = &mut rows[sig_offset * NUM_ROWS_PER_SIG..][..traces.len() * NUM_ROWS_PER_SIG];
+
+    par_zip!(rows.par_chunks_mut(NUM_ROWS_PER_SIG), traces)
+        .enumerate()
+        .map(|(idx, (rows, trace))| generate_trace_rows_sig(rows, epoch, sig_offset + idx, trace))
+        .collect()
+}
+
+/// Generates padding rows after `num_sigs` signatures and returns the trace.
+pub fn finalize(mut trace: UninitTrace<F>, num_sigs: usize) -> RowMajorMatrix<F> {
+    let rows = unsafe { trace.rows_mut::<MerkleLeafCols<MaybeUninit<F>>>() };
+    generate_trace_rows_padding(&mut rows[num_sigs * NUM_ROWS_PER_SIG..]);
+
+    unsafe { trace.assume_init() }
+}
+
+#[inline]
+fn generate_trace_rows_sig(
+    rows: &mut [MerkleLeafCols<MaybeUninit<F>>],
+    epoch: u32,
+    sig_idx: usize,
+    trace: &VerificationTrace,
+) -> [F; HASH_FE_LEN] {
+    let input = from_fn(|i| {
+        i.checked_sub(SPONGE_RATE)
+            .map(|i| SPONGE_CAPACITY_VALUES[i])
+            .unwrap_or_default()
+    });
+    let mut is_receive_merkle_tree = iter::once(false)
+        .chain(trace.x.iter().map(|x_i| *x_i != (1 << CHUNK_SIZE) - 1))
+        .chain([false]);
+    let output = zip!(rows, trace.merkle_tree_leaf(epoch).chunks(SPONGE_RATE))
+        .enumerate()
+        .fold(input, |mut input, (sponge_step, (row, sponge_block))| {
+            zip!(&mut input[..sponge_block.len()], sponge_block)
+                .for_each(|(input, block)| *input += *block);
+            row.is_active.populate(true);
+            row.sig_idx.write_usize(sig_idx);
+            row.sponge_step.populate(sponge_step);
+            row.sponge_block[..sponge_block.len()].fill_from_slice(sponge_block);
+            row.sponge_block[sponge_block.len()..].fill_zero();
+            row.leaf_chunk_start_ind.fill_from_iter(
+                (0..SPONGE_RATE)
+                    .map(|idx| F::from_bool((sponge_step * SPONGE_RATE + idx) % HASH_FE_LEN == 0)),
+            );
+            row.leaf_chunk_idx
+                .write_usize((sponge_step * SPONGE_RATE).div_ceil(HASH_FE_LEN));
+            if (sponge_step * SPONGE_RATE) % HASH_FE_LEN == 0 {
+                row.is_receive_merkle_tree
+                    .fill_from_iter(is_receive_merkle_tree.by_ref().take(3).map(F::from_bool));
+            } else {
+                row.is_receive_merkle_tree[0].write_zero();
+                row.is_receive_merkle_tree[1..]
+                    .fill_from_iter(is_receive_merkle_tree.by_ref().take(2).map(F::from_bool));
+            }
+            row.parameter.fill_from_slice(&trace.pk.parameter);
+            generate_trace_rows_for_perm::<
+                F,
+                Poseidon2LinearLayers<WIDTH>,
+                WIDTH,
+                SBOX_DEGREE,
+                SBOX_REGISTERS,
+                HALF_FULL_ROUNDS,
+                PARTIAL_ROUNDS,
+            >(&mut row.perm, input, &RC24);
+            unsafe { from_fn(|i| outputs(&row.perm)[i].assume_init()) }
+        });
+    from_fn(|i| output[i])
+}
+
+#[inline]
+fn generate_trace_rows_padding(rows: &mut [MerkleLeafCols<MaybeUninit<F>>]) {
+    if let Some((template, rows)) = rows.split_first_mut() {
+        generate_trace_row_padding(template);
+        let template = template.as_slice();
+        rows.par_iter_mut()
+            .for_each(|row| row.as_slice_mut().copy_from_slice(template));
+    }
+}
+
+#[inline]
+fn generate_trace_row_padding(row: &mut MerkleLeafCols<MaybeUninit<F>>) {
+    row.is_active.populate(false);
+    row.sig_idx.write_zero();
+    row.sponge_step.populate(0);
+    row.sponge_block.fill_zero();
+    row.leaf_chunk_start_ind.fill_zero();
+    row.leaf_chunk_idx.write_zero();
+    row.is_receive_merkle_tree.fill_zero();
+    row.parameter.fill_zero();
+    generate_trace_rows_for_perm::<
+        F,
+        Poseidon2LinearLayers<WIDTH>,
+        WIDTH,
+        SBOX_DEGREE,
+        SBOX_REGISTERS,
+        HALF_FULL_ROUNDS,
+        PARTIAL_ROUNDS,
+    >(&mut row.perm, Default::default(), &RC24);
+}
@@ -0,0 +1,27 @@
+use_instance!({hash_sig::SPONGE_PERM, F});
+use p3_field::FieldAlgebra;
+
+mod air;
+pub(super) mod column;
+#[cfg(feature = "prover")]
+pub(super) mod generation;
+
+pub use air::MerkleLeafAir;
+
+mod poseidon2 {
+    use_instance!(partial_round);
+
+    pub const WIDTH: usize = 24;
+    pub const PARTIAL_ROUNDS: usize = partial_round::<WIDTH>();
+}
+
+/// Returns public values of [`MerkleLeafAir`], which is the raw `epoch`.
+pub fn public_values(epoch: u32) -> Vec<F> {
+    vec![F::from_canonical_u32(epoch)]
+}
+
+pub const NUM_ROWS_PER_SIG: usize = SPONGE_PERM;
+
+pub const fn trace_height(num_sigs: usize) -> usize {
+    (num_sigs * NUM_ROWS_PER_SIG).next_power_of_two()
+}
@@ -0,0 +1,289 @@
+use_instance!({
+    chip::{
+        decomposition::LIMB_BITS,
+        merkle_path::{
+            column::{MerklePathCols, NUM_MERKLE_PATH_COLS},
+            epoch_limb_bits,
+            poseidon2::{PARTIAL_ROUNDS, WIDTH},
+        },
+        range_check::send_range_check,
+        Bus,
+    },
+    Poseidon2LinearLayers, F, HALF_FULL_ROUNDS, RC24, SBOX_DEGREE, SBOX_REGISTERS,
+});
+use crate::{
+    gadget::{not, select},
+    util::zip,
+};
+use core::{borrow::Borrow, iter};
+use openvm_stark_backend::{
+    air_builders::sub::SubAirBuilder,
+    interaction::InteractionBuilder,
+    rap::{BaseAirWithPublicValues, PartitionedBaseAir},
+};
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_field::FieldAlgebra;
+use p3_matrix::Matrix;
+use p3_poseidon2_air::{num_cols, Poseidon2Air};
+
+/// Alias for `Poseidon2Air` specialized with merkle-path-related parameters.
+type Poseidon2AirMerklePath = Poseidon2Air<
+    F,
+    Poseidon2LinearLayers<WIDTH>,
+    WIDTH,
+    SBOX_DEGREE,
+    SBOX_REGISTERS,
+    HALF_FULL_ROUNDS,
+    PARTIAL_ROUNDS,
+>;
+
+#[derive(Debug)]
+pub struct MerklePathAir(Poseidon2AirMerklePath);
+
+impl Default for MerklePathAir {
+    fn default() -> Self {
+        Self(Poseidon2Air::new(RC24.into()))
+    }
+}
+
+impl BaseAir<F> for MerklePathAir {
+    fn width(&self) -> usize {
+        NUM_MERKLE_PATH_COLS
+    }
+}
+
+impl PartitionedBaseAir<F> for MerklePathAir {}
+
+impl BaseAirWithPublicValues<F> for MerklePathAir {
+    fn num_public_values(&self) -> usize {
+        1
+    }
+}
+
+impl<AB> Air<AB> for MerklePathAir
+where
+    AB: InteractionBuilder<F = F> + AirBuilderWithPublicValues,
+    AB::Expr: FieldAlgebra<F = F>,
+{
+    fn eval(&self, builder: &mut AB) {
+        self.0
+            .eval(&mut SubAirBuilder::<_, Poseidon2AirMerklePath, _>::new(
+                builder,
+                0
+                    ..num_cols::<
+                        WIDTH,
+                        SBOX_DEGREE,
+                        SBOX_REGISTERS,
+                        HALF_FULL_ROUNDS,
+                        PARTIAL_ROUNDS,
+                    >(),
+            ));
+
+        let epoch: AB::Expr = builder.public_values()[0].into();
+
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let next = main.row_slice(1);
+        let local: &MerklePathCols<AB::Var> = (*local).borrow();
+        let next: &MerklePathCols<AB::Var> = (*next).borrow();
+
+        // When every rows
+        eval_every_row(builder, local);
+
+        // When first row
+        {
+            let mut builder = builder.when_first_row();
+
+            builder.assert_one(*local.is_active);
+            builder.assert_zero(local.sig_idx);
+            builder.assert_one(local.is_first_level);
+            eval_sig_first_row(&mut builder, epoch.clone(), local);
+        }
+
+        // When transition
+        {
+            let mut builder = builder.when_transition();
+
+            eval_transition(&mut builder, local, next);
+            eval_sig_transition(&mut builder, local, next);
+            eval_sig_last_row(&mut builder, epoch, local, next);
+        }
+
+        // Interaction
+        receive_merkle_leaf_hash(builder, local);
+        receive_merkle_root(builder, local);
+        send_epoch(builder, local);
+        send_range_checks(builder, local);
+    }
+}
+
+#[inline]
+fn eval_every_row<AB>(builder: &mut AB, cols: &MerklePathCols<AB::Var>)
+where
+    AB: AirBuilder<F = F>,
+{
+    cols.is_active.eval_every_row(builder);
+    cols.level.eval_every_row(builder);
+    builder
+        .when(not((*cols.is_active).into()))
+        .assert_zero(*cols.level);
+    {
+        let mut builder = builder.when(*cols.is_active);
+
+        builder.assert_bool(cols.is_right);
+        cols.padding().map(|v| builder.assert_zero(v));
+        builder.assert_eq(
+            cols.encoded_tweak_merkle()[0],
+            (*cols.level + F::ONE) * AB::Expr::from_canonical_u32(1 << 2) + F::ONE,
+        );
+    }
+    builder.when(cols.is_first_level).assert_eq(
+        cols.epoch_dec,
+        cols.epoch_limbs
+            .into_iter()
+            .enumerate()
+            .map(|(idx, limb)| limb * AB::Expr::from_canonical_u32(1 << (idx * LIMB_BITS)))
+            .sum::<AB::Expr>(),
+    );
+    {
+        let mut builder = builder.when(cols.is_last_sig_row::<AB>());
+
+        builder.assert_eq(cols.epoch_dec, cols.is_right);
+        builder.assert_zero(cols.encoded_tweak_merkle()[1]);
+    }
+}
+
+#[inline]
+fn eval_transition<AB>(
+    builder: &mut AB,
+    local: &MerklePathCols<AB::Var>,
+    next: &MerklePathCols<AB::Var>,
+) where
+    AB: AirBuilder<F = F>,
+{
+    local.is_active.eval_transition(builder, &next.is_active);
+    local
+        .level
+        .eval_transition(&mut builder.when(*local.is_active), &next.level);
+    builder.assert_eq(
+        next.is_first_level,
+        *next.is_active * local.is_last_sig_row::<AB>(),
+    );
+    builder.assert_eq(
+        next.sig_idx,
+        select(
+            local.is_last_sig_row::<AB>(),
+            local.sig_idx.into(),
+            select(
+                (*next.is_active).into(),
+                AB::Expr::ZERO,
+                local.sig_idx + AB::Expr::ONE,
+            ),
+        ),
+    );
+}
+
+#[inline]
+fn eval_sig_first_row<AB>(builder: &mut AB, epoch: AB::Expr, cols: &MerklePathCols<AB::Var>)
+where
+    AB: AirBuilder<F = F>,
+{
+    builder.assert_eq(cols.epoch_dec, epoch);
+}
+
+/// Constrains tweak of row at `level` to be
+/// `encode_tweak_merkle_tree(level + 1, epoch >> (level + 1))`, together with
+/// [`eval_every_row`].
+///
+/// The `epoch >> (level + 1)` is `epoch_dec` of next row when it's not the last
+/// level, otherwise it's `0` since `epoch < 1 << LOG_LIFETIME`.
+#[inline]
+fn eval_sig_transition<AB>(
+    builder: &mut AB,
+    local: &MerklePathCols<AB::Var>,
+    next: &MerklePathCols<AB::Var>,
+) where
+    AB: AirBuilder<F = F>,
+{
+    let mut builder = builder.when(local.is_sig_transition::<AB>());
+
+    builder.assert_eq(
+        next.epoch_dec.into().double() + local.is_right.into(),
+        local.epoch_dec,
+    );
+    builder.assert_eq(local.encoded_tweak_merkle()[1], next.epoch_dec);
+    zip!(next.parameter(), local.parameter()).for_each(|(a, b)| builder.assert_eq(a, b));
+    zip!(next.node::<AB>(), local.compress_output::<AB>())
+        .for_each(|(a, b)| builder.assert_eq(a, b));
+}
+
+#[inline]
+fn eval_sig_last_row<AB>(
+    builder: &mut AB,
+    epoch: AB::Expr,
+    local: &MerklePathCols<AB::Var>,
+    next: &MerklePathCols<AB::Var>,
+) where
+    AB: AirBuilder<F = F>,
+{
+    let mut builder = builder.when(local.is_last_sig_row::<AB>());
+
+    eval_sig_first_row(&mut builder.when(*next.is_active), epoch, next);
+}
+
+#[inline]
+fn receive_merkle_leaf_hash<AB>(builder: &mut AB, cols: &MerklePathCols<AB::Var>)
+where
+    AB: InteractionBuilder<F = F>,
+{
+    builder.push_receive(
+        Bus::MerkleLeafHash as usize,
+        iter::once(cols.sig_idx.into())
+            .chain(cols.parameter().map(Into::into))
+            .chain(cols.node::<AB>()),
+        cols.is_first_level,
+    );
+}
+
+#[inline]
+fn receive_merkle_root<AB>(builder: &mut AB, cols: &MerklePathCols<AB::Var>)
+where
+    AB: InteractionBuilder<F = F>,
+{
+    builder.push_receive(
+        Bus::MerkleRoot as usize,
+        iter::once(cols.sig_idx.into())
+            .chain(cols.parameter().map(Into::into))
+            .chain(cols.compress_output::<AB>()),
+        cols.is_last_sig_row::<AB>(),
+    );
+}
+
+/// Sends epoch of each signature to be consistent with the one of
+/// [`ChainAir`](super::super::chain::ChainAir).
+#[inline]
+fn send_epoch<AB>(builder: &mut AB, cols: &MerklePathCols<AB::Var>)
+where
+    AB: InteractionBuilder<F = F>,
+{
+    builder.push_send(
+        Bus::Epoch as usize,
+        [cols.sig_idx, cols.epoch_dec],
+        cols.is_first_level,
+    );
+}
+
+/// Sends epoch limbs of each signature to be range checked, which proves
+/// `epoch < 1 << LOG_LIFETIME` regardless of the path recursion.
+#[inline]
+fn send_range_checks<AB>(builder: &mut AB, cols: &MerklePathCols<AB::Var>)
+where
+    AB: InteractionBuilder<F = F>,
+{
+    cols.epoch_limbs
+        .into_iter()
+        .enumerate()
+        .for_each(|(idx, limb)| {
+            send_range_check(builder, limb, epoch_limb_bits(idx), cols.is_first_level);
+        });
+}
@@ -0,0 +1,139 @@
+use_instance!({
+    chip::merkle_path::{
+        poseidon2::{PARTIAL_ROUNDS, WIDTH},
+        NUM_EPOCH_LIMBS,
+    },
+    hash_sig::{HASH_FE_LEN, LOG_LIFETIME, PARAM_FE_LEN, TWEAK_FE_LEN},
+    HALF_FULL_ROUNDS, SBOX_DEGREE, SBOX_REGISTERS,
+});
+use crate::{
+    gadget::{cycle_int::CycleInt, lower_rows_filter::LowerRowsFilterCols, select},
+    util::AlignBorrow,
+};
+use core::{
+    array::from_fn,
+    borrow::{Borrow, BorrowMut},
+    slice,
+};
+use p3_air::AirBuilder;
+use p3_poseidon2_util::air::{outputs, Poseidon2Cols};
+
+pub const NUM_MERKLE_PATH_COLS: usize = size_of::<MerklePathCols<u8>>();
+
+const NUM_PADDING: usize = WIDTH - (PARAM_FE_LEN + TWEAK_FE_LEN + 2 * HASH_FE_LEN);
+
+#[repr(C)]
+pub struct MerklePathCols<T> {
+    pub perm:
+        Poseidon2Cols<T, WIDTH, SBOX_DEGREE, SBOX_REGISTERS, HALF_FULL_ROUNDS, PARTIAL_ROUNDS>,
+    /// Whether this sig is active or not.
+    pub is_active: LowerRowsFilterCols<T>,
+    /// Signature index.
+    pub sig_idx: T,
+    /// Level of the child nodes.
+    pub level: CycleInt<T, LOG_LIFETIME>,
+    /// Whether `level` is `0` in an active row or not.
+    pub is_first_level: T,
+    /// Index of the child node in its level, which is `epoch >> level`.
+    pub epoch_dec: T,
+    /// Limbs of `epoch_dec` in the first level, otherwise `0`.
+    pub epoch_limbs: [T; NUM_EPOCH_LIMBS],
+    /// Whether the child node is the right one or not.
+    pub is_right: T,
+}
+
+impl<T> MerklePathCols<T> {
+    #[inline]
+    pub const fn as_slice(&self) -> &[T] {
+        unsafe {
+            slice::from_raw_parts(core::ptr::from_ref(self).cast::<T>(), NUM_MERKLE_PATH_COLS)
+        }
+    }
+
+    #[inline]
+    pub const fn as_slice_mut(&mut self) -> &mut [T] {
+        unsafe {
+            slice::from_raw_parts_mut(core::ptr::from_mut(self).cast::<T>(), NUM_MERKLE_PATH_COLS)
+        }
+    }
+}
+
+impl<T: Copy> MerklePathCols<T> {
+    #[inline]
+    pub fn is_sig_transition<AB: AirBuilder>(&self) -> AB::Expr
+    where
+        T: Into<AB::Expr>,
+    {
+        (*self.is_active).into() - self.is_last_sig_row::<AB>()
+    }
+
+    #[inline]
+    pub fn is_last_sig_row<AB: AirBuilder>(&self) -> AB::Expr
+    where
+        T: Into<AB::Expr>,
+    {
+        self.level.is_last_step::<AB>()
+    }
+
+    #[inline]
+    pub fn parameter(&self) -> [T; PARAM_FE_LEN] {
+        from_fn(|i| self.perm.inputs[i])
+    }
+
+    #[inline]
+    pub fn encoded_tweak_merkle(&self) -> [T; TWEAK_FE_LEN] {
+        from_fn(|i| self.perm.inputs[PARAM_FE_LEN + i])
+    }
+
+    #[inline]
+    pub fn left(&self) -> [T; HASH_FE_LEN] {
+        from_fn(|i| self.perm.inputs[PARAM_FE_LEN + TWEAK_FE_LEN + i])
+    }
+
+    #[inline]
+    pub fn right(&self) -> [T; HASH_FE_LEN] {
+        from_fn(|i| self.perm.inputs[PARAM_FE_LEN + TWEAK_FE_LEN + HASH_FE_LEN + i])
+    }
+
+    #[inline]
+    pub fn padding(&self) -> [T; NUM_PADDING] {
+        from_fn(|i| self.perm.inputs[WIDTH - NUM_PADDING + i])
+    }
+
+    /// Returns the child node, which is the right one if `is_right`, otherwise
+    /// the left one.
+    #[inline]
+    pub fn node<AB: AirBuilder>(&self) -> [AB::Expr; HASH_FE_LEN]
+    where
+        T: Into<AB::Expr>,
+    {
+        let (left, right) = (self.left(), self.right());
+        from_fn(|i| select(self.is_right.into(), left[i].into(), right[i].into()))
+    }
+
+    #[inline]
+    pub fn compress_output<AB: AirBuilder>(&self) -> [AB::Expr; HASH_FE_LEN]
+    where
+        T: Into<AB::Expr>,
+    {
+        from_fn(|i| self.perm.inputs[i].into() + outputs(&self.perm)[i].into())
+    }
+}
+
+impl<T> AlignBorrow<T> for MerklePathCols<T> {
+    const SIZE: usize = NUM_MERKLE_PATH_COLS;
+}
+
+impl<T> Borrow<MerklePathCols<T>> for [T] {
+    #[inline]
+    fn borrow(&self) -> &MerklePathCols<T> {
+        MerklePathCols::align_borrow(self)
+    }
+}
+
+impl<T> BorrowMut<MerklePathCols<T>> for [T] {
+    #[inline]
+    fn borrow_mut(&mut self) -> &mut MerklePathCols<T> {
+        MerklePathCols::align_borrow_mut(self)
+    }
+}
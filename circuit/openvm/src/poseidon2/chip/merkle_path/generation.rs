@@ -0,0 +1,158 @@
+use_instance!({
+    chip::{
+        merkle_path::{
+            column::{MerklePathCols, NUM_MERKLE_PATH_COLS},
+            epoch_limb_bits, epoch_limbs,
+            poseidon2::{PARTIAL_ROUNDS, WIDTH},
+            trace_height, NUM_ROWS_PER_SIG,
+        },
+        range_check::RangeChecker,
+    },
+    hash_sig::{encode_tweak_merkle_tree, VerificationTrace, HASH_FE_LEN},
+    Poseidon2LinearLayers, F, HALF_FULL_ROUNDS, RC24, SBOX_DEGREE, SBOX_REGISTERS,
+});
+use crate::util::{
+    concat_array,
+    field::{MaybeUninitField, MaybeUninitFieldSlice},
+    par_zip,
+    trace::UninitTrace,
+    zip,
+};
+use core::{array::from_fn, mem::MaybeUninit};
+use p3_field::FieldAlgebra;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_maybe_rayon::prelude::*;
+use p3_poseidon2_util::air::{generate_trace_rows_for_perm, outputs};
+
+/// Records range checks of epoch limbs of `num_sigs` signatures, paired with
+/// the ones sent on the first level of each signature.
+pub fn add_epoch_range_checks(range_checker: &RangeChecker, epoch: u32, num_sigs: usize) {
+    epoch_limbs(epoch)
+        .into_iter()
+        .enumerate()
+        .for_each(|(idx, limb)| {
+            range_checker.add_counts(limb, epoch_limb_bits(idx), num_sigs as u32);
+        });
+}
+
+pub fn uninit_trace(extra_capacity_bits: usize, num_sigs: usize) -> UninitTrace<F> {
+    UninitTrace::new(
+        NUM_MERKLE_PATH_COLS,
+        trace_height(num_sigs),
+        extra_capacity_bits,
+    )
+}
+
+/// Generates rows of `traces` with their `merkle_leaf_hashes` as signatures
+/// starting from `sig_offset`, and records range checks of their epoch limbs
+/// into `range_checker`.
+pub fn generate_trace_rows_chunk(
+    trace: &mut UninitTrace<F>,
+    range_checker: &RangeChecker,
+    epoch: u32,
+    sig_offset: usize,
+    traces: &[VerificationTrace],
+    merkle_leaf_hashes: &[[F; HASH_FE_LEN]],
+) {
+    debug_assert_eq!(traces.len(), merkle_leaf_hashes.len());
+    let rows = unsafe { trace.rows_mut::<MerklePathCols<MaybeUninit<F>>>() };
+    let rows = &mut rows[sig_offset * NUM_ROWS_PER_SIG..][..traces.len() * NUM_ROWS_PER_SIG];
+
+    par_zip!(
+        rows.par_chunks_mut(NUM_ROWS_PER_SIG),
+        traces,
+        merkle_leaf_hashes
+    )
+    .enumerate()
+    .for_each(|(idx, (rows, trace, merkle_leaf_hash))| {
+        generate_trace_rows_sig(rows, epoch, sig_offset + idx, trace, *merkle_leaf_hash);
+    });
+    add_epoch_range_checks(range_checker, epoch, traces.len());
+}
+
+/// Generates padding rows after `num_sigs` signatures and returns the trace.
+pub fn finalize(mut trace: UninitTrace<F>, num_sigs: usize) -> RowMajorMatrix<F> {
+    let rows = unsafe { trace.rows_mut::<MerklePathCols<MaybeUninit<F>>>() };
+    generate_trace_rows_padding(&mut rows[num_sigs * NUM_ROWS_PER_SIG..]);
+
+    unsafe { trace.assume_init() }
+}
+
+#[inline]
+fn generate_trace_rows_sig(
+    rows: &mut [MerklePathCols<MaybeUninit<F>>],
+    epoch: u32,
+    sig_idx: usize,
+    trace: &VerificationTrace,
+    merkle_leaf_hash: [F; HASH_FE_LEN],
+) {
+    let mut epoch_dec = epoch;
+    zip!(rows, trace.sig.merkle_siblings).enumerate().fold(
+        merkle_leaf_hash,
+        |node, (level, (row, sibling))| {
+            let is_right = epoch_dec & 1 == 1;
+            row.is_active.populate(true);
+            row.sig_idx.write_usize(sig_idx);
+            row.level.populate(level);
+            row.is_first_level.write_bool(level == 0);
+            row.epoch_dec.write_u32(epoch_dec);
+            if level == 0 {
+                row.epoch_limbs
+                    .fill_from_iter(epoch_limbs(epoch).map(F::from_canonical_u32));
+            } else {
+                row.epoch_limbs.fill_zero();
+            }
+            row.is_right.write_bool(is_right);
+            let input = concat_array![
+                trace.pk.parameter,
+                encode_tweak_merkle_tree((level as u8 + 1).into(), epoch_dec >> 1),
+                if is_right {
+                    [sibling, node].into_iter().flatten()
+                } else {
+                    [node, sibling].into_iter().flatten()
+                }
+            ];
+            generate_trace_rows_for_perm::<
+                F,
+                Poseidon2LinearLayers<WIDTH>,
+                WIDTH,
+                SBOX_DEGREE,
+                SBOX_REGISTERS,
+                HALF_FULL_ROUNDS,
+                PARTIAL_ROUNDS,
+            >(&mut row.perm, input, &RC24);
+            epoch_dec >>= 1;
+            unsafe { from_fn(|i| input[i] + outputs(&row.perm)[i].assume_init()) }
+        },
+    );
+}
+
+#[inline]
+fn generate_trace_rows_padding(rows: &mut [MerklePathCols<MaybeUninit<F>>]) {
+    if let Some((template, rows)) = rows.split_first_mut() {
+        generate_trace_row_padding(template);
+        let template = template.as_slice();
+        rows.par_iter_mut()
+            .for_each(|row| row.as_slice_mut().copy_from_slice(template));
+    }
+}
+
+#[inline]
+fn generate_trace_row_padding(row: &mut MerklePathCols<MaybeUninit<F>>) {
+    row.is_active.populate(false);
+    row.sig_idx.write_zero();
+    row.level.populate(0);
+    row.is_first_level.write_zero();
+    row.epoch_dec.write_zero();
+    row.epoch_limbs.fill_zero();
+    row.is_right.write_zero();
+    generate_trace_rows_for_perm::<
+        F,
+        Poseidon2LinearLayers<WIDTH>,
+        WIDTH,
+        SBOX_DEGREE,
+        SBOX_REGISTERS,
+        HALF_FULL_ROUNDS,
+        PARTIAL_ROUNDS,
+    >(&mut row.perm, Default::default(), &RC24);
+}
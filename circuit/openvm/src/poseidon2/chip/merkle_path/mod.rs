@@ -0,0 +1,178 @@
+use_instance!({chip::decomposition::LIMB_BITS, hash_sig::LOG_LIFETIME, F});
+use core::array::from_fn;
+use p3_field::FieldAlgebra;
+
+mod air;
+pub(super) mod column;
+#[cfg(feature = "prover")]
+pub(super) mod generation;
+
+pub use air::MerklePathAir;
+
+mod poseidon2 {
+    use_instance!(partial_round);
+
+    pub const WIDTH: usize = 24;
+    pub const PARTIAL_ROUNDS: usize = partial_round::<WIDTH>();
+}
+
+/// Number of limbs of epoch, which are range checked to prove
+/// `epoch < 1 << LOG_LIFETIME`.
+pub const NUM_EPOCH_LIMBS: usize = LOG_LIFETIME.div_ceil(LIMB_BITS);
+
+/// Returns bits of epoch limb at `idx`, which is `LIMB_BITS` except the most
+/// significant one.
+pub const fn epoch_limb_bits(idx: usize) -> usize {
+    if idx + 1 < NUM_EPOCH_LIMBS {
+        LIMB_BITS
+    } else {
+        LOG_LIFETIME - (NUM_EPOCH_LIMBS - 1) * LIMB_BITS
+    }
+}
+
+/// Returns little-endian limbs of `epoch` with widths of [`epoch_limb_bits`].
+pub fn epoch_limbs(epoch: u32) -> [u32; NUM_EPOCH_LIMBS] {
+    debug_assert!(u64::from(epoch) < 1 << LOG_LIFETIME);
+    from_fn(|idx| (epoch >> (idx * LIMB_BITS)) & ((1 << epoch_limb_bits(idx)) - 1))
+}
+
+/// Returns public values of [`MerklePathAir`].
+pub fn public_values(epoch: u32) -> Vec<F> {
+    vec![F::from_canonical_u32(epoch)]
+}
+
+pub const NUM_ROWS_PER_SIG: usize = LOG_LIFETIME;
+
+pub const fn trace_height(num_sigs: usize) -> usize {
+    (num_sigs * NUM_ROWS_PER_SIG).next_power_of_two()
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod test {
+    use_instance!({
+        chip::{
+            air_idx,
+            decomposition::LIMB_BITS,
+            generate_air_proof_inputs,
+            merkle_path::{
+                column::MerklePathCols,
+                poseidon2::{PARTIAL_ROUNDS, WIDTH},
+            },
+            AirKind, Layout,
+        },
+        hash_sig::{
+            encode_msg, encode_tweak_merkle_tree,
+            test::{forged_merkle_root, mock_vi},
+            VerificationTrace, HASH_FE_LEN, LOG_LIFETIME, PARAM_FE_LEN, TWEAK_FE_LEN,
+        },
+        Poseidon2LinearLayers, E, F, HALF_FULL_ROUNDS, RC24, SBOX_DEGREE, SBOX_REGISTERS,
+    });
+    use crate::util::engine::Engine;
+    use core::{
+        array::from_fn,
+        borrow::BorrowMut,
+        mem::{offset_of, MaybeUninit},
+    };
+    use openvm_stark_backend::{utils::disable_debug_builder, verifier::VerificationError};
+    use openvm_stark_sdk::engine::StarkEngine;
+    use p3_field::FieldAlgebra;
+    use p3_poseidon2_util::air::{generate_trace_rows_for_perm, outputs};
+
+    const NUM_SIGS: usize = 4;
+
+    fn merkle_path_air_idx() -> usize {
+        air_idx(Layout::default(), AirKind::MerklePath).unwrap()
+    }
+
+    /// Same as the one of fused layout in `merkle_tree`, but regenerates rows
+    /// of [`MerklePathAir`](super::MerklePathAir) instead.
+    fn prove_with_forged_tweak(
+        forged_level: usize,
+        forged_tweak: [F; TWEAK_FE_LEN],
+    ) -> Result<(), VerificationError> {
+        let engine = Engine::<F, E>::fastest();
+        let mut vi = mock_vi(NUM_SIGS);
+        let (pk, sig) = vi.pairs[0];
+        let trace = VerificationTrace::generate(vi.epoch, encode_msg(vi.msg), pk, sig);
+        vi.pairs[0].0.merkle_root =
+            forged_merkle_root(vi.epoch, &trace, forged_level, forged_tweak);
+
+        let (airs, mut air_proof_inputs) =
+            generate_air_proof_inputs(engine.log_blowup(), vi, &[true; NUM_SIGS]);
+        let merkle_path_trace = air_proof_inputs[merkle_path_air_idx()]
+            .raw
+            .common_main
+            .as_mut()
+            .unwrap();
+        (forged_level..LOG_LIFETIME).fold(None, |node, level| {
+            let row = merkle_path_trace.row_mut(level);
+            let (_, row, _) = unsafe { row.align_to_mut::<MaybeUninit<F>>() };
+            let row: &mut MerklePathCols<MaybeUninit<F>> = row.borrow_mut();
+            let mut input = unsafe { row.perm.inputs.map(|v| v.assume_init()) };
+            if level == forged_level {
+                input[PARAM_FE_LEN..][..TWEAK_FE_LEN].copy_from_slice(&forged_tweak);
+            }
+            if let Some(node) = node {
+                let is_right = unsafe { row.is_right.assume_init() } == F::ONE;
+                input[PARAM_FE_LEN + TWEAK_FE_LEN + usize::from(is_right) * HASH_FE_LEN..]
+                    [..HASH_FE_LEN]
+                    .copy_from_slice(&node);
+            }
+            generate_trace_rows_for_perm::<
+                F,
+                Poseidon2LinearLayers<WIDTH>,
+                WIDTH,
+                SBOX_DEGREE,
+                SBOX_REGISTERS,
+                HALF_FULL_ROUNDS,
+                PARTIAL_ROUNDS,
+            >(&mut row.perm, input, &RC24);
+            Some(unsafe {
+                from_fn::<_, HASH_FE_LEN, _>(|i| input[i] + outputs(&row.perm)[i].assume_init())
+            })
+        });
+
+        disable_debug_builder();
+        engine.run_test_impl(airs, air_proof_inputs).map(drop)
+    }
+
+    #[test]
+    fn forged_tweak() {
+        let epoch = mock_vi(NUM_SIGS).epoch;
+        for level in [0, LOG_LIFETIME / 2, LOG_LIFETIME - 1] {
+            let tweak = encode_tweak_merkle_tree::<F>(level as u8 + 1, epoch >> (level + 1));
+            assert!(prove_with_forged_tweak(level, tweak).is_ok());
+
+            let mut separator = tweak;
+            separator[0] += F::ONE;
+            for forged_tweak in [
+                encode_tweak_merkle_tree::<F>(level as u8 + 2, epoch >> (level + 1)),
+                encode_tweak_merkle_tree::<F>(level as u8 + 1, (epoch >> (level + 1)) ^ 1),
+                separator,
+            ] {
+                assert_eq!(
+                    prove_with_forged_tweak(level, forged_tweak).err(),
+                    Some(VerificationError::OodEvaluationMismatch)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn epoch_limbs_out_of_range() {
+        let engine = Engine::<F, E>::fastest();
+        let (airs, mut air_proof_inputs) =
+            generate_air_proof_inputs(engine.log_blowup(), mock_vi(NUM_SIGS), &[true; NUM_SIGS]);
+        let merkle_path_trace = air_proof_inputs[merkle_path_air_idx()]
+            .raw
+            .common_main
+            .as_mut()
+            .unwrap();
+        let col = offset_of!(MerklePathCols<u8>, epoch_limbs);
+        merkle_path_trace.values[col] -= F::from_canonical_u32(1 << LIMB_BITS);
+        merkle_path_trace.values[col + 1] += F::ONE;
+
+        disable_debug_builder();
+        assert!(engine.run_test_impl(airs, air_proof_inputs).is_err());
+    }
+}
@@ -1,22 +1,22 @@
-use crate::{
-    gadget::{not, select},
-    poseidon2::{
-        chip::{
-            merkle_tree::{
-                column::{MerkleTreeCols, NUM_MERKLE_TREE_COLS},
-                poseidon2::{PARTIAL_ROUNDS, WIDTH},
-            },
-            Bus,
+use_instance!({
+    chip::{
+        decomposition::LIMB_BITS,
+        merkle_path::epoch_limb_bits,
+        merkle_tree::{
+            column::{MerkleTreeCols, NUM_MERKLE_TREE_COLS},
+            poseidon2::{PARTIAL_ROUNDS, WIDTH},
         },
-        hash_sig::{
-            HASH_FE_LEN, MSG_FE_LEN, PARAM_FE_LEN, SPONGE_CAPACITY_VALUES, SPONGE_RATE,
-            TWEAK_FE_LEN,
-        },
-        Poseidon2LinearLayers, F, HALF_FULL_ROUNDS, RC24, SBOX_DEGREE, SBOX_REGISTERS,
+        range_check::send_range_check,
+        Bus,
     },
+    hash_sig::{HASH_FE_LEN, PARAM_FE_LEN, SPONGE_CAPACITY_VALUES, SPONGE_RATE, TWEAK_FE_LEN},
+    Poseidon2LinearLayers, F, HALF_FULL_ROUNDS, RC24, SBOX_DEGREE, SBOX_REGISTERS,
+});
+use crate::{
+    gadget::{not, select},
     util::zip,
 };
-use core::{array::from_fn, borrow::Borrow, iter};
+use core::{borrow::Borrow, iter};
 use itertools::Itertools;
 use openvm_stark_backend::{
     air_builders::sub::SubAirBuilder,
@@ -58,7 +58,7 @@ impl PartitionedBaseAir<F> for MerkleTreeAir {}
 
 impl BaseAirWithPublicValues<F> for MerkleTreeAir {
     fn num_public_values(&self) -> usize {
-        1 + MSG_FE_LEN + 2 * TWEAK_FE_LEN
+        1
     }
 }
 
@@ -81,17 +81,16 @@ where
                     >(),
             ));
 
-        // TODO:
-        // 1. Make sure `encoded_tweak_merkle_tree` (`perm.inputs[PARAM_FE_LEN..][..TWEAK_FE_LEN]`) of path is correct.
-
         let main = builder.main();
 
-        let mut public_values = builder.public_values().iter().copied().map_into();
-        let epoch = public_values.next().unwrap();
-        let encoded_msg: [_; MSG_FE_LEN] = from_fn(|_| public_values.next().unwrap());
-        let encoded_tweak_msg: [_; TWEAK_FE_LEN] = from_fn(|_| public_values.next().unwrap());
-        let encoded_tweak_merkle_leaf: [_; TWEAK_FE_LEN] =
-            from_fn(|_| public_values.next().unwrap());
+        let epoch: AB::Expr = builder.public_values()[0].into();
+        // `encode_tweak_msg(epoch)`
+        let encoded_tweak_msg = [
+            epoch.clone() * F::from_canonical_u32(1 << 2) + F::TWO,
+            AB::Expr::ZERO,
+        ];
+        // `encode_tweak_merkle_tree(0, epoch)`
+        let encoded_tweak_merkle_leaf = [AB::Expr::ONE, epoch.clone()];
 
         let local = main.row_slice(0);
         let next = main.row_slice(1);
@@ -119,12 +118,12 @@ where
             eval_sig_transition(&mut builder, local, next);
             eval_merkle_leaf_transition(&mut builder, local, next);
             eval_merkle_leaf_last_row(&mut builder, epoch, local, next);
+            eval_merkle_path_tweak(&mut builder, local, next);
             eval_merkle_path_transition(&mut builder, local, next);
             eval_merkle_path_last_row(&mut builder, local, next);
             eval_msg(
                 &mut builder,
                 encoded_tweak_msg,
-                encoded_msg,
                 encoded_tweak_merkle_leaf,
                 local,
                 next,
@@ -133,8 +132,12 @@ where
         }
 
         // Interaction
-        receive_merkle_root_and_msg_hash(builder, local, next);
+        receive_merkle_root(builder, local);
+        receive_encoded_msg(builder, local);
+        receive_msg_hash(builder, local);
         receive_merkle_leaf(builder, local, next);
+        send_epoch(builder, local, next);
+        send_range_checks(builder, local, next);
     }
 }
 
@@ -275,6 +278,14 @@ fn eval_merkle_leaf_last_row<AB>(
     builder.assert_one(next.is_merkle_path);
     next.level.eval_first_row(&mut builder);
     builder.assert_eq(next.epoch_dec, epoch);
+    builder.assert_eq(
+        next.epoch_dec,
+        next.epoch_limbs
+            .into_iter()
+            .enumerate()
+            .map(|(idx, limb)| limb * AB::Expr::from_canonical_u32(1 << (idx * LIMB_BITS)))
+            .sum::<AB::Expr>(),
+    );
     zip!(next.merkle_parameter(), local.merkle_parameter_register())
         .for_each(|(a, b)| builder.assert_eq(a, b));
     zip!(
@@ -302,8 +313,13 @@ where
     cols.merkle_path_padding().map(|v| builder.assert_zero(v));
 }
 
+/// Constrains tweak of path row at `level` to be
+/// `encode_tweak_merkle_tree(level + 1, epoch >> (level + 1))`.
+///
+/// The `epoch >> (level + 1)` is `epoch_dec` of next row when it's not the last
+/// level, otherwise it's `0` since `epoch < 1 << LOG_LIFETIME`.
 #[inline]
-fn eval_merkle_path_transition<AB>(
+fn eval_merkle_path_tweak<AB>(
     builder: &mut AB,
     local: &MerkleTreeCols<AB::Var>,
     next: &MerkleTreeCols<AB::Var>,
@@ -324,8 +340,17 @@ fn eval_merkle_path_transition<AB>(
         ]
     )
     .for_each(|(a, b)| builder.assert_eq(a, b));
+}
 
-    let mut builder = builder.inner.when(local.is_merkle_path_transition);
+#[inline]
+fn eval_merkle_path_transition<AB>(
+    builder: &mut AB,
+    local: &MerkleTreeCols<AB::Var>,
+    next: &MerkleTreeCols<AB::Var>,
+) where
+    AB: AirBuilder<F = F>,
+{
+    let mut builder = builder.when(local.is_merkle_path_transition);
 
     builder.assert_one(next.is_merkle_path);
     local.level.eval_transition(&mut builder, &next.level);
@@ -367,7 +392,6 @@ fn eval_merkle_path_last_row<AB>(
 fn eval_msg<AB>(
     builder: &mut AB,
     encoded_tweak_msg: [AB::Expr; TWEAK_FE_LEN],
-    encoded_msg: [AB::Expr; MSG_FE_LEN],
     encoded_tweak_merkle_leaf: [AB::Expr; TWEAK_FE_LEN],
     local: &MerkleTreeCols<AB::Var>,
     next: &MerkleTreeCols<AB::Var>,
@@ -377,7 +401,6 @@ fn eval_msg<AB>(
     let mut builder = builder.when(local.is_msg);
 
     zip!(local.encoded_tweak_msg(), encoded_tweak_msg).for_each(|(a, b)| builder.assert_eq(a, b));
-    zip!(local.encoded_msg(), encoded_msg).for_each(|(a, b)| builder.assert_eq(a, b));
     local.msg_hash_padding().map(|v| builder.assert_zero(v));
     builder.assert_zero(next.is_msg.into() + next.is_merkle_path.into());
 
@@ -402,20 +425,38 @@ fn eval_padding_transition<AB>(
 }
 
 #[inline]
-fn receive_merkle_root_and_msg_hash<AB>(
-    builder: &mut AB,
-    local: &MerkleTreeCols<AB::Var>,
-    next: &MerkleTreeCols<AB::Var>,
-) where
+fn receive_merkle_root<AB>(builder: &mut AB, cols: &MerkleTreeCols<AB::Var>)
+where
+    AB: InteractionBuilder<F = F>,
+{
+    builder.push_receive(
+        Bus::MerkleRoot as usize,
+        iter::once(cols.sig_idx.into())
+            .chain(cols.merkle_parameter().map(Into::into))
+            .chain(cols.compress_output::<AB>()),
+        cols.is_last_merkle_path_row::<AB>(),
+    );
+}
+
+#[inline]
+fn receive_encoded_msg<AB>(builder: &mut AB, cols: &MerkleTreeCols<AB::Var>)
+where
+    AB: InteractionBuilder<F = F>,
+{
+    builder.push_receive(Bus::EncodedMsg as usize, cols.encoded_msg(), cols.is_msg);
+}
+
+#[inline]
+fn receive_msg_hash<AB>(builder: &mut AB, cols: &MerkleTreeCols<AB::Var>)
+where
     AB: InteractionBuilder<F = F>,
 {
     builder.push_receive(
-        Bus::MerkleRootAndMsgHash as usize,
-        iter::once(local.sig_idx.into())
-            .chain(local.merkle_parameter().map(Into::into))
-            .chain(local.compress_output::<AB>())
-            .chain(next.msg_hash::<AB>()),
-        local.is_last_merkle_path_row::<AB>(),
+        Bus::MsgHash as usize,
+        iter::once(cols.sig_idx.into())
+            .chain(cols.msg_hash_parameter().map(Into::into))
+            .chain(cols.msg_hash::<AB>()),
+        cols.is_msg,
     );
 }
 
@@ -472,3 +513,41 @@ fn receive_merkle_leaf<AB>(
         local.is_receive_merkle_tree[2] * not(local.is_last_sponge_step::<AB>()),
     );
 }
+
+/// Sends epoch of each signature from its last merkle leaf row to be
+/// consistent with the one of [`ChainAir`](super::super::chain::ChainAir).
+#[inline]
+fn send_epoch<AB>(builder: &mut AB, local: &MerkleTreeCols<AB::Var>, next: &MerkleTreeCols<AB::Var>)
+where
+    AB: InteractionBuilder<F = F>,
+{
+    builder.push_send(
+        Bus::Epoch as usize,
+        [local.sig_idx, next.epoch_dec],
+        local.is_last_merkle_leaf_row::<AB>(),
+    );
+}
+
+/// Sends epoch limbs in the first merkle path row of each signature to be
+/// range checked, which proves `epoch < 1 << LOG_LIFETIME` regardless of the
+/// path recursion.
+#[inline]
+fn send_range_checks<AB>(
+    builder: &mut AB,
+    local: &MerkleTreeCols<AB::Var>,
+    next: &MerkleTreeCols<AB::Var>,
+) where
+    AB: InteractionBuilder<F = F>,
+{
+    next.epoch_limbs
+        .into_iter()
+        .enumerate()
+        .for_each(|(idx, limb)| {
+            send_range_check(
+                builder,
+                limb,
+                epoch_limb_bits(idx),
+                local.is_last_merkle_leaf_row::<AB>(),
+            );
+        });
+}
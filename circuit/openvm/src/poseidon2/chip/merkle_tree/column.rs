@@ -1,13 +1,16 @@
+use_instance!({
+    chip::{
+        merkle_path::NUM_EPOCH_LIMBS,
+        merkle_tree::poseidon2::{PARTIAL_ROUNDS, WIDTH},
+    },
+    hash_sig::{
+        HASH_FE_LEN, LOG_LIFETIME, MSG_FE_LEN, MSG_HASH_FE_LEN, PARAM_FE_LEN, RHO_FE_LEN,
+        SPONGE_INPUT_SIZE, SPONGE_PERM, SPONGE_RATE, TWEAK_FE_LEN,
+    },
+    HALF_FULL_ROUNDS, SBOX_DEGREE, SBOX_REGISTERS,
+});
 use crate::{
     gadget::{cycle_int::CycleInt, not},
-    poseidon2::{
-        chip::merkle_tree::poseidon2::{PARTIAL_ROUNDS, WIDTH},
-        hash_sig::{
-            HASH_FE_LEN, LOG_LIFETIME, MSG_FE_LEN, MSG_HASH_FE_LEN, PARAM_FE_LEN, RHO_FE_LEN,
-            SPONGE_INPUT_SIZE, SPONGE_PERM, SPONGE_RATE, TWEAK_FE_LEN,
-        },
-        HALF_FULL_ROUNDS, SBOX_DEGREE, SBOX_REGISTERS,
-    },
     util::AlignBorrow,
 };
 use core::{
@@ -42,6 +45,7 @@ pub struct MerkleTreeCols<T> {
     pub leaf_chunk_idx: T,
     pub level: CycleInt<T, LOG_LIFETIME>,
     pub epoch_dec: T,
+    pub epoch_limbs: [T; NUM_EPOCH_LIMBS],
     pub is_right: T,
 }
 
@@ -1,74 +1,161 @@
-use crate::{
-    poseidon2::{
-        chip::merkle_tree::{
+use_instance!({
+    chip::{
+        merkle_path::{epoch_limbs, generation::add_epoch_range_checks},
+        merkle_tree::{
             column::{MerkleTreeCols, NUM_MERKLE_TREE_COLS},
             poseidon2::{PARTIAL_ROUNDS, WIDTH},
+            public_values, trace_height, MerkleTreeAir, NUM_ROWS_PER_SIG,
         },
-        hash_sig::{
-            encode_tweak_merkle_tree, VerificationTrace, CHUNK_SIZE, HASH_FE_LEN, LOG_LIFETIME,
-            MSG_FE_LEN, SPONGE_CAPACITY_VALUES, SPONGE_PERM, SPONGE_RATE,
-        },
-        Poseidon2LinearLayers, F, HALF_FULL_ROUNDS, RC24, SBOX_DEGREE, SBOX_REGISTERS,
+        range_check::RangeChecker,
     },
-    util::{
-        concat_array,
-        field::{MaybeUninitField, MaybeUninitFieldSlice},
-        par_zip, zip,
+    hash_sig::{
+        encode_tweak_merkle_tree, VerificationTrace, CHUNK_SIZE, HASH_FE_LEN, LOG_LIFETIME,
+        MSG_FE_LEN, SPONGE_CAPACITY_VALUES, SPONGE_PERM, SPONGE_RATE,
     },
+    Poseidon2LinearLayers, F, HALF_FULL_ROUNDS, RC24, SBOX_DEGREE, SBOX_REGISTERS,
+});
+use crate::util::{
+    concat_array,
+    field::{MaybeUninitField, MaybeUninitFieldSlice},
+    par_zip,
+    trace::UninitTrace,
+    zip,
+};
+use core::{any::type_name, array::from_fn, iter, mem::MaybeUninit};
+use openvm_stark_backend::{
+    config::{Domain, StarkGenericConfig},
+    prover::types::{AirProofInput, AirProofRawInput},
+    rap::AnyRap,
+    Chip, ChipUsageGetter,
 };
-use core::{array::from_fn, iter, mem::MaybeUninit};
+use p3_commit::PolynomialSpace;
 use p3_field::FieldAlgebra;
-use p3_matrix::dense::{RowMajorMatrix, RowMajorMatrixViewMut};
+use p3_matrix::dense::RowMajorMatrix;
 use p3_maybe_rayon::prelude::*;
 use p3_poseidon2_util::air::{generate_trace_rows_for_perm, outputs};
+use std::sync::Arc;
 
-const NUM_ROWS_PER_SIG: usize = 1 + SPONGE_PERM + LOG_LIFETIME;
+#[derive(Clone, Debug)]
+pub struct MerkleTreeChip<'a> {
+    air: Arc<MerkleTreeAir>,
+    extra_capacity_bits: usize,
+    epoch: u32,
+    encoded_msg: [F; MSG_FE_LEN],
+    traces: &'a [VerificationTrace],
+    range_checker: &'a RangeChecker,
+}
 
-pub const fn trace_height(traces: &[VerificationTrace]) -> usize {
-    (traces.len() * NUM_ROWS_PER_SIG).next_power_of_two()
+impl<'a> MerkleTreeChip<'a> {
+    pub fn new(
+        extra_capacity_bits: usize,
+        epoch: u32,
+        encoded_msg: [F; MSG_FE_LEN],
+        traces: &'a [VerificationTrace],
+        range_checker: &'a RangeChecker,
+    ) -> Self {
+        Self {
+            air: Default::default(),
+            extra_capacity_bits,
+            epoch,
+            encoded_msg,
+            traces,
+            range_checker,
+        }
+    }
 }
 
-pub fn generate_trace_rows(
-    extra_capacity_bits: usize,
+impl ChipUsageGetter for MerkleTreeChip<'_> {
+    fn air_name(&self) -> String {
+        type_name::<MerkleTreeAir>().to_string()
+    }
+
+    fn current_trace_height(&self) -> usize {
+        trace_height(self.traces.len())
+    }
+
+    fn trace_width(&self) -> usize {
+        NUM_MERKLE_TREE_COLS
+    }
+}
+
+impl<SC: StarkGenericConfig> Chip<SC> for MerkleTreeChip<'_>
+where
+    Domain<SC>: PolynomialSpace<Val = F>,
+{
+    fn air(&self) -> Arc<dyn AnyRap<SC>> {
+        self.air.clone()
+    }
+
+    fn generate_air_proof_input(self) -> AirProofInput<SC> {
+        AirProofInput {
+            cached_mains_pdata: Vec::new(),
+            raw: AirProofRawInput {
+                cached_mains: Vec::new(),
+                common_main: Some(generate_trace_rows(
+                    self.extra_capacity_bits,
+                    self.range_checker,
+                    self.epoch,
+                    self.encoded_msg,
+                    self.traces,
+                )),
+                public_values: public_values(self.epoch),
+            },
+        }
+    }
+}
+
+pub fn uninit_trace(extra_capacity_bits: usize, num_sigs: usize) -> UninitTrace<F> {
+    UninitTrace::new(
+        NUM_MERKLE_TREE_COLS,
+        trace_height(num_sigs),
+        extra_capacity_bits,
+    )
+}
+
+/// Generates rows of `traces` as signatures starting from `sig_offset`, and
+/// records range checks of their epoch limbs into `range_checker`.
+pub fn generate_trace_rows_chunk(
+    trace: &mut UninitTrace<F>,
+    range_checker: &RangeChecker,
     epoch: u32,
     encoded_msg: [F; MSG_FE_LEN],
+    sig_offset: usize,
     traces: &[VerificationTrace],
-) -> RowMajorMatrix<F> {
-    let height = trace_height(traces);
-    let size = height * NUM_MERKLE_TREE_COLS;
-    let mut vec = Vec::with_capacity(size << extra_capacity_bits);
-    let trace = &mut vec.spare_capacity_mut()[..size];
-    let trace = RowMajorMatrixViewMut::new(trace, NUM_MERKLE_TREE_COLS);
-
-    let (prefix, rows, suffix) = unsafe {
-        trace
-            .values
-            .align_to_mut::<MerkleTreeCols<MaybeUninit<F>>>()
-    };
-    assert!(prefix.is_empty(), "Alignment should match");
-    assert!(suffix.is_empty(), "Alignment should match");
-    assert_eq!(rows.len(), height);
+) {
+    let rows = unsafe { trace.rows_mut::<MerkleTreeCols<MaybeUninit<F>>>() };
+    let rows = &mut rows[sig_offset * NUM_ROWS_PER_SIG..][..traces.len() * NUM_ROWS_PER_SIG];
 
-    let (rows, padding_rows) = rows.split_at_mut(traces.len() * NUM_ROWS_PER_SIG);
+    par_zip!(rows.par_chunks_mut(NUM_ROWS_PER_SIG), traces)
+        .enumerate()
+        .for_each(|(idx, (rows, trace))| {
+            let sig_idx = sig_offset + idx;
+            let (leaf_rows, rows) = rows.split_at_mut(SPONGE_PERM);
+            let (msg_row, path_rows) = rows.split_last_mut().unwrap();
+            let leaf_hash = generate_trace_rows_leaf(leaf_rows, epoch, sig_idx, trace);
+            generate_trace_rows_path(path_rows, epoch, sig_idx, trace, leaf_hash);
+            generate_trace_row_msg(msg_row, epoch, encoded_msg, trace, sig_idx);
+        });
+    add_epoch_range_checks(range_checker, epoch, traces.len());
+}
 
-    join(
-        || {
-            par_zip!(rows.par_chunks_mut(NUM_ROWS_PER_SIG), traces)
-                .enumerate()
-                .for_each(|(sig_idx, (rows, trace))| {
-                    let (leaf_rows, rows) = rows.split_at_mut(SPONGE_PERM);
-                    let (msg_row, path_rows) = rows.split_last_mut().unwrap();
-                    let leaf_hash = generate_trace_rows_leaf(leaf_rows, epoch, sig_idx, trace);
-                    generate_trace_rows_path(path_rows, epoch, sig_idx, trace, leaf_hash);
-                    generate_trace_row_msg(msg_row, epoch, encoded_msg, trace, sig_idx);
-                });
-        },
-        || generate_trace_rows_padding(padding_rows),
-    );
+/// Generates padding rows after `num_sigs` signatures and returns the trace.
+pub fn finalize(mut trace: UninitTrace<F>, num_sigs: usize) -> RowMajorMatrix<F> {
+    let rows = unsafe { trace.rows_mut::<MerkleTreeCols<MaybeUninit<F>>>() };
+    generate_trace_rows_padding(&mut rows[num_sigs * NUM_ROWS_PER_SIG..]);
 
-    unsafe { vec.set_len(size) };
+    unsafe { trace.assume_init() }
+}
 
-    RowMajorMatrix::new(vec, NUM_MERKLE_TREE_COLS)
+pub fn generate_trace_rows(
+    extra_capacity_bits: usize,
+    range_checker: &RangeChecker,
+    epoch: u32,
+    encoded_msg: [F; MSG_FE_LEN],
+    traces: &[VerificationTrace],
+) -> RowMajorMatrix<F> {
+    let mut trace = uninit_trace(extra_capacity_bits, traces.len());
+    generate_trace_rows_chunk(&mut trace, range_checker, epoch, encoded_msg, 0, traces);
+    finalize(trace, traces.len())
 }
 
 #[inline]
@@ -92,6 +179,7 @@ fn generate_trace_row_msg(
     row.leaf_chunk_idx.write_zero();
     row.level.populate(0);
     row.epoch_dec.write_zero();
+    row.epoch_limbs.fill_zero();
     row.is_right.write_zero();
     let input = trace.msg_hash_preimage(epoch, encoded_msg);
     generate_trace_rows_for_perm::<
@@ -149,6 +237,7 @@ fn generate_trace_rows_leaf(
             );
             row.leaf_chunk_idx
                 .write_usize((sponge_step * SPONGE_RATE).div_ceil(HASH_FE_LEN));
+            row.epoch_limbs.fill_zero();
             zip!(row.merkle_parameter_register_mut(), trace.pk.parameter)
                 .for_each(|(cell, value)| cell.write_f(value));
             generate_trace_rows_for_perm::<
@@ -192,6 +281,12 @@ fn generate_trace_rows_path(
             row.leaf_chunk_idx.write_zero();
             row.level.populate(level);
             row.epoch_dec.write_u32(epoch_dec);
+            if level == 0 {
+                row.epoch_limbs
+                    .fill_from_iter(epoch_limbs(epoch).map(F::from_canonical_u32));
+            } else {
+                row.epoch_limbs.fill_zero();
+            }
             row.is_right.write_bool(is_right);
             let mut left_right = [node, sibling];
             if is_right {
@@ -246,6 +341,7 @@ pub fn generate_trace_row_padding(row: &mut MerkleTreeCols<MaybeUninit<F>>) {
     row.leaf_chunk_idx.write_zero();
     row.level.populate(0);
     row.epoch_dec.write_zero();
+    row.epoch_limbs.fill_zero();
     row.is_right.write_zero();
     generate_trace_rows_for_perm::<
         F,
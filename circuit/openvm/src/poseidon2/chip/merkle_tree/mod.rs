@@ -1,96 +1,168 @@
-use crate::poseidon2::{
-    chip::merkle_tree::{
-        air::MerkleTreeAir, column::NUM_MERKLE_TREE_COLS, generation::generate_trace_rows,
-    },
-    hash_sig::{encode_tweak_merkle_tree, encode_tweak_msg, VerificationTrace, MSG_FE_LEN},
-    F,
-};
-use core::{any::type_name, iter};
-use generation::trace_height;
-use openvm_stark_backend::{
-    config::{Domain, StarkGenericConfig},
-    prover::types::{AirProofInput, AirProofRawInput},
-    rap::AnyRap,
-    Chip, ChipUsageGetter,
-};
-use p3_commit::PolynomialSpace;
+use_instance!({hash_sig::{LOG_LIFETIME, SPONGE_PERM}, F});
 use p3_field::FieldAlgebra;
-use std::sync::Arc;
 
 mod air;
-mod column;
-mod generation;
+pub(super) mod column;
+#[cfg(feature = "prover")]
+pub(super) mod generation;
+
+pub use air::MerkleTreeAir;
+#[cfg(feature = "prover")]
+pub use generation::MerkleTreeChip;
 
 mod poseidon2 {
+    use_instance!(partial_round);
+
     pub const WIDTH: usize = 24;
-    pub const PARTIAL_ROUNDS: usize = crate::poseidon2::partial_round::<WIDTH>();
+    pub const PARTIAL_ROUNDS: usize = partial_round::<WIDTH>();
 }
 
-#[derive(Clone, Debug)]
-pub struct MerkleTreeChip<'a> {
-    air: Arc<MerkleTreeAir>,
-    extra_capacity_bits: usize,
-    epoch: u32,
-    encoded_msg: [F; MSG_FE_LEN],
-    traces: &'a [VerificationTrace],
+/// Returns public values of [`MerkleTreeAir`], which is the raw `epoch`.
+pub fn public_values(epoch: u32) -> Vec<F> {
+    vec![F::from_canonical_u32(epoch)]
 }
 
-impl<'a> MerkleTreeChip<'a> {
-    pub fn new(
-        extra_capacity_bits: usize,
-        epoch: u32,
-        encoded_msg: [F; MSG_FE_LEN],
-        traces: &'a [VerificationTrace],
-    ) -> Self {
-        Self {
-            air: Default::default(),
-            extra_capacity_bits,
-            epoch,
-            encoded_msg,
-            traces,
-        }
-    }
+pub const NUM_ROWS_PER_SIG: usize = 1 + SPONGE_PERM + LOG_LIFETIME;
+
+pub const fn trace_height(num_sigs: usize) -> usize {
+    (num_sigs * NUM_ROWS_PER_SIG).next_power_of_two()
 }
 
-impl ChipUsageGetter for MerkleTreeChip<'_> {
-    fn air_name(&self) -> String {
-        type_name::<MerkleTreeAir>().to_string()
+#[cfg(all(test, feature = "prover"))]
+mod test {
+    use_instance!({
+        chip::{
+            air_idx, generate_air_proof_inputs_in_chunks,
+            merkle_tree::{
+                column::MerkleTreeCols,
+                poseidon2::{PARTIAL_ROUNDS, WIDTH},
+            },
+            AirKind, Layout, MerkleLayout, DEFAULT_CHUNK_SIZE,
+        },
+        hash_sig::{
+            encode_msg, encode_tweak_merkle_tree,
+            test::{forged_merkle_root, mock_vi},
+            VerificationTrace, HASH_FE_LEN, LOG_LIFETIME, PARAM_FE_LEN, SPONGE_PERM, TWEAK_FE_LEN,
+        },
+        Poseidon2LinearLayers, E, F, HALF_FULL_ROUNDS, RC24, SBOX_DEGREE, SBOX_REGISTERS,
+    });
+    use crate::util::engine::Engine;
+    use core::{array::from_fn, borrow::BorrowMut, mem::MaybeUninit};
+    use openvm_stark_backend::{utils::disable_debug_builder, verifier::VerificationError};
+    use openvm_stark_sdk::engine::StarkEngine;
+    use p3_field::FieldAlgebra;
+    use p3_poseidon2_util::air::{generate_trace_rows_for_perm, outputs};
+
+    const NUM_SIGS: usize = 4;
+
+    /// Proves with tweak of the first signature's path at `forged_level`
+    /// replaced by `forged_tweak`.
+    ///
+    /// The public key's merkle root is replaced by the one computed with forged
+    /// tweak, and path rows from `forged_level` are regenerated accordingly, so
+    /// only the tweak constraint could catch the forgery.
+    fn prove_with_forged_tweak(
+        forged_level: usize,
+        forged_tweak: [F; TWEAK_FE_LEN],
+    ) -> Result<(), VerificationError> {
+        let engine = Engine::<F, E>::fastest();
+        let mut vi = mock_vi(NUM_SIGS);
+        let (pk, sig) = vi.pairs[0];
+        let trace = VerificationTrace::generate(vi.epoch, encode_msg(vi.msg), pk, sig);
+        vi.pairs[0].0.merkle_root =
+            forged_merkle_root(vi.epoch, &trace, forged_level, forged_tweak);
+
+        let layout = Layout {
+            merkle: MerkleLayout::Fused,
+            ..Layout::default()
+        };
+        let (airs, mut air_proof_inputs) = generate_air_proof_inputs_in_chunks(
+            engine.log_blowup(),
+            vi,
+            &[true; NUM_SIGS],
+            DEFAULT_CHUNK_SIZE,
+            layout,
+        );
+        let merkle_tree_trace = air_proof_inputs[air_idx(layout, AirKind::MerkleTree).unwrap()]
+            .raw
+            .common_main
+            .as_mut()
+            .unwrap();
+        (forged_level..LOG_LIFETIME).fold(None, |node, level| {
+            let row = merkle_tree_trace.row_mut(SPONGE_PERM + level);
+            let (_, row, _) = unsafe { row.align_to_mut::<MaybeUninit<F>>() };
+            let row: &mut MerkleTreeCols<MaybeUninit<F>> = row.borrow_mut();
+            let mut input = unsafe { row.perm.inputs.map(|v| v.assume_init()) };
+            if level == forged_level {
+                input[PARAM_FE_LEN..][..TWEAK_FE_LEN].copy_from_slice(&forged_tweak);
+            }
+            if let Some(node) = node {
+                let is_right = unsafe { row.is_right.assume_init() } == F::ONE;
+                input[PARAM_FE_LEN + TWEAK_FE_LEN + usize::from(is_right) * HASH_FE_LEN..]
+                    [..HASH_FE_LEN]
+                    .copy_from_slice(&node);
+            }
+            generate_trace_rows_for_perm::<
+                F,
+                Poseidon2LinearLayers<WIDTH>,
+                WIDTH,
+                SBOX_DEGREE,
+                SBOX_REGISTERS,
+                HALF_FULL_ROUNDS,
+                PARTIAL_ROUNDS,
+            >(&mut row.perm, input, &RC24);
+            Some(unsafe {
+                from_fn::<_, HASH_FE_LEN, _>(|i| input[i] + outputs(&row.perm)[i].assume_init())
+            })
+        });
+
+        disable_debug_builder();
+        engine.run_test_impl(airs, air_proof_inputs).map(drop)
     }
 
-    fn current_trace_height(&self) -> usize {
-        trace_height(self.traces)
+    #[test]
+    fn honest_tweak() {
+        let epoch = mock_vi(NUM_SIGS).epoch;
+        for level in [0, LOG_LIFETIME / 2, LOG_LIFETIME - 1] {
+            let tweak = encode_tweak_merkle_tree::<F>(level as u8 + 1, epoch >> (level + 1));
+            assert!(prove_with_forged_tweak(level, tweak).is_ok());
+        }
     }
 
-    fn trace_width(&self) -> usize {
-        NUM_MERKLE_TREE_COLS
+    #[test]
+    fn forged_tweak_level() {
+        let epoch = mock_vi(NUM_SIGS).epoch;
+        for level in [0, LOG_LIFETIME / 2, LOG_LIFETIME - 1] {
+            let tweak = encode_tweak_merkle_tree::<F>(level as u8 + 2, epoch >> (level + 1));
+            assert_eq!(
+                prove_with_forged_tweak(level, tweak).err(),
+                Some(VerificationError::OodEvaluationMismatch)
+            );
+        }
     }
-}
 
-impl<SC: StarkGenericConfig> Chip<SC> for MerkleTreeChip<'_>
-where
-    Domain<SC>: PolynomialSpace<Val = F>,
-{
-    fn air(&self) -> Arc<dyn AnyRap<SC>> {
-        self.air.clone()
+    #[test]
+    fn forged_tweak_index() {
+        let epoch = mock_vi(NUM_SIGS).epoch;
+        for level in [0, LOG_LIFETIME / 2, LOG_LIFETIME - 1] {
+            let tweak = encode_tweak_merkle_tree::<F>(level as u8 + 1, (epoch >> (level + 1)) ^ 1);
+            assert_eq!(
+                prove_with_forged_tweak(level, tweak).err(),
+                Some(VerificationError::OodEvaluationMismatch)
+            );
+        }
     }
 
-    fn generate_air_proof_input(self) -> AirProofInput<SC> {
-        AirProofInput {
-            cached_mains_pdata: Vec::new(),
-            raw: AirProofRawInput {
-                cached_mains: Vec::new(),
-                common_main: Some(generate_trace_rows(
-                    self.extra_capacity_bits,
-                    self.epoch,
-                    self.encoded_msg,
-                    self.traces,
-                )),
-                public_values: iter::once(F::from_canonical_u32(self.epoch))
-                    .chain(self.encoded_msg)
-                    .chain(encode_tweak_msg(self.epoch))
-                    .chain(encode_tweak_merkle_tree(0, self.epoch))
-                    .collect(),
-            },
+    #[test]
+    fn forged_tweak_separator() {
+        let epoch = mock_vi(NUM_SIGS).epoch;
+        for level in [0, LOG_LIFETIME / 2, LOG_LIFETIME - 1] {
+            let mut tweak = encode_tweak_merkle_tree::<F>(level as u8 + 1, epoch >> (level + 1));
+            tweak[0] += F::ONE;
+            assert_eq!(
+                prove_with_forged_tweak(level, tweak).err(),
+                Some(VerificationError::OodEvaluationMismatch)
+            );
         }
     }
 }
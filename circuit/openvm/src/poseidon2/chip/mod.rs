@@ -1,103 +1,688 @@
-use crate::poseidon2::{
-    chip::main::MainChip,
-    hash_sig::{encode_msg, VerificationInput, VerificationTrace},
+use super::{
+    hash_sig::{
+        PublicKey, VerificationInput, VerificationTrace, HASH_FE_LEN, MSG_FE_LEN, MSG_HASH_FE_LEN,
+        MSG_LEN,
+    },
     F,
 };
-use chain::ChainChip;
-use decomposition::DecompositionChip;
-use merkle_tree::MerkleTreeChip;
+use crate::util::{
+    trace::{trace_size, TraceHeight},
+    validation::{validate, InvalidReason, ValidationReport},
+};
+use chain::ChainAir;
+use core::{iter::zip, num::NonZeroUsize};
+use decomposition::{DecompositionAir, F_MS_LIMB_BITS, LIMB_BITS};
+use main::MainAir;
+use merkle_leaf::MerkleLeafAir;
+use merkle_path::MerklePathAir;
+use merkle_tree::MerkleTreeAir;
+use msg_encoding::MsgEncodingAir;
+use msg_hash::MsgHashAir;
 use openvm_stark_backend::{
     config::{Domain, StarkGenericConfig},
-    prover::types::AirProofInput,
-    AirRef, Chip,
+    AirRef,
 };
+use p3_air::BaseAir;
 use p3_commit::PolynomialSpace;
-use p3_maybe_rayon::prelude::*;
-use range_check::RangeCheckChip;
-use tracing::instrument;
+use participation::{num_words, participation_bitmap, ParticipationAir, PARTICIPATION_WORD_BITS};
+use range_check::{table_bits, RangeCheckAir};
+use registry::RegistryAir;
+use signer_set::{signer_set_commitment, SignerSetAir};
+use std::sync::{atomic::AtomicU32, Arc};
+use unique_signer::{duplicate_signers, UniqueSignerAir, KEY_FE_LEN};
+
+#[cfg(feature = "prover")]
+pub use generation::{
+    generate_air_proof_inputs, generate_air_proof_inputs_in_chunks,
+    generate_air_proof_inputs_with_registry, try_generate_air_proof_inputs, DEFAULT_CHUNK_SIZE,
+};
 
 pub mod chain;
 pub mod decomposition;
+#[cfg(feature = "prover")]
+mod generation;
 pub mod main;
+pub mod merkle_leaf;
+pub mod merkle_path;
 pub mod merkle_tree;
+pub mod msg_encoding;
+pub mod msg_hash;
+#[cfg(all(test, feature = "prover"))]
+mod mutation;
+pub mod participation;
+pub mod plan;
 pub mod range_check;
+pub mod registry;
+pub mod signer_set;
+pub mod unique_signer;
 
 #[repr(u8)]
 pub enum Bus {
     Parameter,
-    MerkleRootAndMsgHash,
+    MerkleRoot,
+    MsgHash,
     Chain,
     MerkleLeaf,
+    MerkleLeafHash,
     Decomposition,
     RangeCheck,
+    UniqueSigner,
+    Participation,
+    SignerSet,
+    Epoch,
+    EncodedMsg,
 }
 
-#[instrument(name = "generate hash-sig aggregation traces", skip_all)]
-pub fn generate_air_proof_inputs<SC: StarkGenericConfig>(
-    extra_capacity_bits: usize,
-    vi: VerificationInput,
-) -> (Vec<AirRef<SC>>, Vec<AirProofInput<SC>>)
+/// Capacity of [`RangeChecker`](range_check::RangeChecker), which covers the
+/// widest range check sent by any AIR over [`Bus::RangeCheck`].
+///
+/// The [`RangeCheckAir`] table is sized by the widest recorded range check
+/// instead, which is this one in every aggregation, since limbs of message
+/// hash decomposition are always range checked in `LIMB_BITS`.
+pub const RANGE_CHECK_BITS: usize = table_bits(&[LIMB_BITS, F_MS_LIMB_BITS]);
+
+/// Layout of AIRs proving message hashes and Merkle roots of signatures.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MerkleLayout {
+    /// Single [`MerkleTreeAir`] with `1 + SPONGE_PERM + LOG_LIFETIME` rows per
+    /// signature.
+    Fused,
+    /// [`MsgHashAir`], [`MerkleLeafAir`] and [`MerklePathAir`] with `1`,
+    /// `SPONGE_PERM` and `LOG_LIFETIME` rows per signature respectively, so
+    /// each trace is padded to power of two separately.
+    #[default]
+    Split,
+}
+
+/// Layout of the last AIR binding public keys of all signatures.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SignerSetLayout {
+    /// [`SignerSetAir`] absorbing public keys into a sponge, with the digest
+    /// exposed as public values.
+    #[default]
+    Sponge,
+    /// [`RegistryAir`] looking up public keys from a
+    /// [`Registry`](registry::Registry) committed once, with the commitment as
+    /// the first main trace commitment of proof and without public values.
+    Registry,
+}
+
+/// Layout of AIRs, which trades width, height and number of traces without
+/// changing what's proven, except how the signer set is bound.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Layout {
+    pub merkle: MerkleLayout,
+    /// Number of chain compressions in each row of [`ChainAir`].
+    pub chain_compressions_per_row: NonZeroUsize,
+    /// Number of [`ChainAir`] instances consecutive signatures are split
+    /// into, see [`chain::split_sigs`].
+    pub chain_instances: NonZeroUsize,
+    pub signer_set: SignerSetLayout,
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Self {
+            merkle: MerkleLayout::default(),
+            chain_compressions_per_row: NonZeroUsize::MIN,
+            chain_instances: NonZeroUsize::MIN,
+            signer_set: SignerSetLayout::default(),
+        }
+    }
+}
+
+/// Kind of each AIR of [`airs`], to look up AIR indices of a [`Layout`] by
+/// [`air_idx`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AirKind {
+    Main,
+    /// [`ChainAir`] instance at the index.
+    Chain(usize),
+    MerkleTree,
+    MsgHash,
+    MerkleLeaf,
+    MerklePath,
+    Decomposition,
+    RangeCheck,
+    UniqueSigner,
+    MsgEncoding,
+    Participation,
+    SignerSet,
+    Registry,
+}
+
+/// Returns kinds of AIRs with `layout` in the same order as [`airs`].
+pub fn air_kinds(layout: Layout) -> Vec<AirKind> {
+    let mut kinds = vec![AirKind::Main];
+    kinds.extend((0..layout.chain_instances.get()).map(AirKind::Chain));
+    match layout.merkle {
+        MerkleLayout::Fused => kinds.push(AirKind::MerkleTree),
+        MerkleLayout::Split => {
+            kinds.extend([AirKind::MsgHash, AirKind::MerkleLeaf, AirKind::MerklePath])
+        }
+    }
+    kinds.extend([
+        AirKind::Decomposition,
+        AirKind::RangeCheck,
+        AirKind::UniqueSigner,
+        AirKind::MsgEncoding,
+        AirKind::Participation,
+    ]);
+    match layout.signer_set {
+        SignerSetLayout::Sponge => kinds.push(AirKind::SignerSet),
+        SignerSetLayout::Registry => kinds.push(AirKind::Registry),
+    }
+    kinds
+}
+
+/// Returns index of AIR of `kind` in [`airs`] with `layout`, or `None` if
+/// `layout` doesn't have it.
+pub fn air_idx(layout: Layout, kind: AirKind) -> Option<usize> {
+    air_kinds(layout).into_iter().position(|k| k == kind)
+}
+
+/// Returns AIRs of aggregating `num_sigs` signatures with `layout`, in the
+/// same order as [`generate_air_proof_inputs_in_chunks`].
+///
+/// AIRs don't depend on witness, so this can be used for keygen without
+/// generating traces.
+pub fn airs<SC: StarkGenericConfig>(num_sigs: usize, layout: Layout) -> Vec<AirRef<SC>>
 where
     Domain<SC>: PolynomialSpace<Val = F>,
 {
-    let encoded_msg = encode_msg(vi.msg);
-    let traces = vi
-        .pairs
-        .into_par_iter()
-        .map(|(pk, sig)| VerificationTrace::generate(vi.epoch, encoded_msg, pk, sig))
-        .collect::<Vec<_>>();
-    let main = MainChip::new(extra_capacity_bits, &traces);
-    let chain = ChainChip::new(extra_capacity_bits, vi.epoch, &traces);
-    let merkle_tree = MerkleTreeChip::new(extra_capacity_bits, vi.epoch, encoded_msg, &traces);
-    let decomposition = DecompositionChip::new(extra_capacity_bits, &traces);
-    let ((main_api, chain_api), (merkle_tree_api, (decomposition_api, range_check_mult))) = join(
-        || {
-            join(
-                || main.generate_air_proof_input(),
-                || chain.clone().generate_air_proof_input(),
-            )
-        },
-        || {
-            join(
-                || merkle_tree.clone().generate_air_proof_input(),
-                || decomposition.generate_air_proof_input_and_range_check_mult(),
+    air_kinds(layout)
+        .into_iter()
+        .map(|kind| -> AirRef<SC> {
+            match kind {
+                AirKind::Main => Arc::new(MainAir),
+                AirKind::Chain(_) => Arc::new(ChainAir::new(layout.chain_compressions_per_row)),
+                AirKind::MerkleTree => Arc::new(MerkleTreeAir::default()),
+                AirKind::MsgHash => Arc::new(MsgHashAir::default()),
+                AirKind::MerkleLeaf => Arc::new(MerkleLeafAir::default()),
+                AirKind::MerklePath => Arc::new(MerklePathAir::default()),
+                AirKind::Decomposition => Arc::new(DecompositionAir),
+                AirKind::RangeCheck => Arc::new(RangeCheckAir),
+                AirKind::UniqueSigner => Arc::new(UniqueSignerAir),
+                AirKind::MsgEncoding => Arc::new(MsgEncodingAir),
+                AirKind::Participation => Arc::new(ParticipationAir::new(num_words(num_sigs))),
+                AirKind::SignerSet => Arc::new(SignerSetAir::default()),
+                AirKind::Registry => Arc::new(RegistryAir),
+            }
+        })
+        .collect()
+}
+
+/// Returns public values of AIRs in the same order as [`airs`], for aggregation
+/// of signatures on `msg` at `epoch` of `public_keys` flagged in
+/// `participation`, which weigh at least `threshold` of `total_weight`.
+///
+/// Only the raw `epoch` and `msg` are exposed, and their encodings are derived
+/// in-circuit, so the verifier doesn't need to recompute them.
+///
+/// `public_keys` is only used by [`SignerSetLayout::Sponge`], where every
+/// signer weighs `1`.
+pub fn public_values(
+    epoch: u32,
+    msg: [u8; MSG_LEN],
+    threshold: u32,
+    total_weight: u32,
+    participation: &[bool],
+    public_keys: &[PublicKey],
+    layout: Layout,
+) -> Vec<Vec<F>> {
+    let num_participating = participation.iter().filter(|p| **p).count();
+    let mut public_values = vec![main::public_values(threshold, total_weight)];
+    public_values.extend(
+        chain_sig_offsets(num_participating, layout)
+            .into_iter()
+            .map(|sig_offset| chain::public_values(epoch, sig_offset)),
+    );
+    match layout.merkle {
+        MerkleLayout::Fused => public_values.push(merkle_tree::public_values(epoch)),
+        MerkleLayout::Split => public_values.extend([
+            msg_hash::public_values(epoch),
+            merkle_leaf::public_values(epoch),
+            merkle_path::public_values(epoch),
+        ]),
+    }
+    public_values.extend([
+        Vec::new(),
+        Vec::new(),
+        Vec::new(),
+        msg_encoding::public_values(msg),
+        participation_bitmap(participation),
+    ]);
+    match layout.signer_set {
+        SignerSetLayout::Sponge => public_values.push(signer_set_commitment(public_keys).to_vec()),
+        SignerSetLayout::Registry => public_values.push(Vec::new()),
+    }
+    public_values
+}
+
+/// Verifies signatures in `vi` flagged in `participation` natively, and checks
+/// they are of distinct signers.
+pub fn validate_participating(
+    vi: &VerificationInput,
+    participation: &[bool],
+) -> Result<(), ValidationReport> {
+    let mut invalid = validate(vi, participation)
+        .err()
+        .map_or_else(Vec::new, |report| report.invalid);
+    let public_keys = vi.pairs.iter().map(|(pk, _)| *pk).collect::<Vec<_>>();
+    invalid.extend(
+        duplicate_signers(&public_keys, participation)
+            .into_iter()
+            .map(|idx| (idx, InvalidReason::DuplicateSigner)),
+    );
+    if invalid.is_empty() {
+        return Ok(());
+    }
+    invalid.sort_by_key(|(idx, _)| *idx);
+    Err(ValidationReport { invalid })
+}
+
+/// Returns index of the first signature of each [`ChainAir`] instance
+/// aggregating `num_participating` participating signatures with `layout`.
+fn chain_sig_offsets(num_participating: usize, layout: Layout) -> Vec<usize> {
+    chain::sig_offsets(&chain::split_sigs(
+        num_participating,
+        layout.chain_compressions_per_row.get(),
+        layout.chain_instances.get(),
+    ))
+}
+
+/// Returns total weight of signatures flagged in `participation`.
+pub fn participating_weight(participation: &[bool], weights: &[u32]) -> u32 {
+    zip(participation, weights)
+        .filter(|(is_participating, _)| **is_participating)
+        .map(|(_, weight)| weight)
+        .sum()
+}
+
+/// Returns heights of AIRs in the same order as [`airs`], aggregating
+/// `num_sigs` signatures of which `num_participating` are participating.
+pub fn trace_heights(
+    num_sigs: usize,
+    num_participating: usize,
+    layout: Layout,
+) -> Vec<TraceHeight> {
+    let main_height = TraceHeight::new(num_sigs, num_sigs.next_power_of_two());
+    let mut heights = vec![main_height];
+    heights.extend(
+        chain::split_sigs(
+            num_participating,
+            layout.chain_compressions_per_row.get(),
+            layout.chain_instances.get(),
+        )
+        .into_iter()
+        .map(|num_sigs| {
+            TraceHeight::new(
+                chain::num_rows(num_sigs, layout.chain_compressions_per_row.get()),
+                chain::trace_height(num_sigs, layout.chain_compressions_per_row.get()),
             )
-        },
+        }),
     );
-    let range_check_chip = RangeCheckChip::new(extra_capacity_bits, range_check_mult);
-    (
-        vec![
-            main.air(),
-            chain.air(),
-            merkle_tree.air(),
-            decomposition.air(),
-            range_check_chip.air(),
-        ],
-        vec![
-            main_api,
-            chain_api,
-            merkle_tree_api,
-            decomposition_api,
-            range_check_chip.generate_air_proof_input(),
-        ],
+    match layout.merkle {
+        MerkleLayout::Fused => heights.push(TraceHeight::new(
+            num_participating * merkle_tree::NUM_ROWS_PER_SIG,
+            merkle_tree::trace_height(num_participating),
+        )),
+        MerkleLayout::Split => heights.extend([
+            TraceHeight::new(
+                num_participating * msg_hash::NUM_ROWS_PER_SIG,
+                msg_hash::trace_height(num_participating),
+            ),
+            TraceHeight::new(
+                num_participating * merkle_leaf::NUM_ROWS_PER_SIG,
+                merkle_leaf::trace_height(num_participating),
+            ),
+            TraceHeight::new(
+                num_participating * merkle_path::NUM_ROWS_PER_SIG,
+                merkle_path::trace_height(num_participating),
+            ),
+        ]),
+    }
+    heights.extend([
+        TraceHeight::new(
+            num_participating * decomposition::NUM_ROWS_PER_SIG,
+            decomposition::trace_height(num_participating),
+        ),
+        TraceHeight::new(
+            range_check::trace_height(RANGE_CHECK_BITS),
+            range_check::trace_height(RANGE_CHECK_BITS),
+        ),
+        TraceHeight::new(
+            num_participating,
+            unique_signer::trace_height(num_participating),
+        ),
+        TraceHeight::new(MSG_FE_LEN, msg_encoding::trace_height()),
+        TraceHeight::new(
+            num_sigs.div_ceil(PARTICIPATION_WORD_BITS),
+            num_words(num_sigs),
+        ),
+        main_height,
+    ]);
+    heights
+}
+
+/// Returns widths of AIRs in the same order as [`airs`].
+pub fn widths(layout: Layout) -> Vec<usize> {
+    air_kinds(layout)
+        .into_iter()
+        .map(|kind| match kind {
+            AirKind::Main => BaseAir::<F>::width(&MainAir),
+            AirKind::Chain(_) => {
+                BaseAir::<F>::width(&ChainAir::new(layout.chain_compressions_per_row))
+            }
+            AirKind::MerkleTree => BaseAir::<F>::width(&MerkleTreeAir::default()),
+            AirKind::MsgHash => BaseAir::<F>::width(&MsgHashAir::default()),
+            AirKind::MerkleLeaf => BaseAir::<F>::width(&MerkleLeafAir::default()),
+            AirKind::MerklePath => BaseAir::<F>::width(&MerklePathAir::default()),
+            AirKind::Decomposition => BaseAir::<F>::width(&DecompositionAir),
+            AirKind::RangeCheck => BaseAir::<F>::width(&RangeCheckAir),
+            AirKind::UniqueSigner => BaseAir::<F>::width(&UniqueSignerAir),
+            AirKind::MsgEncoding => BaseAir::<F>::width(&MsgEncodingAir),
+            AirKind::Participation => BaseAir::<F>::width(&ParticipationAir::new(1)),
+            AirKind::SignerSet => BaseAir::<F>::width(&SignerSetAir::default()),
+            AirKind::Registry => BaseAir::<F>::width(&RegistryAir),
+        })
+        .collect()
+}
+
+/// Returns estimated peak memory in bytes of [`generate_air_proof_inputs_in_chunks`]
+/// aggregating `num_sigs` signatures of which `num_participating` are
+/// participating with AIRs in `layout`, excluding the [`VerificationInput`]
+/// itself.
+///
+/// It consists of all traces allocated with `extra_capacity_bits` headroom,
+/// the [`VerificationTrace`]s and Merkle leaf hashes of a chunk, the
+/// message hashes kept for the main trace, the signer keys, the weights and
+/// the multiplicities of [`RangeChecker`](range_check::RangeChecker).
+pub fn estimate_peak_memory(
+    extra_capacity_bits: usize,
+    num_sigs: usize,
+    num_participating: usize,
+    chunk_size: usize,
+    layout: Layout,
+) -> usize {
+    let merkle_leaf_hash_size = match layout.merkle {
+        MerkleLayout::Fused => 0,
+        MerkleLayout::Split => size_of::<[F; HASH_FE_LEN]>(),
+    };
+    zip(
+        widths(layout),
+        trace_heights(num_sigs, num_participating, layout),
     )
+    .map(|(width, height)| trace_size::<F>(width, height.height, extra_capacity_bits))
+    .sum::<usize>()
+        + chunk_size.min(num_participating)
+            * (size_of::<VerificationTrace>() + merkle_leaf_hash_size)
+        + num_participating * size_of::<[F; MSG_HASH_FE_LEN]>()
+        + num_participating * size_of::<[F; KEY_FE_LEN]>()
+        + num_sigs * size_of::<u32>()
+        + range_check::trace_height(RANGE_CHECK_BITS) * size_of::<AtomicU32>()
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "prover"))]
 mod test {
-    use crate::{
-        poseidon2::{chip::generate_air_proof_inputs, hash_sig::test::mock_vi, E, F},
-        util::engine::Engine,
+    use_instance!({
+        chip::{
+            airs, generate_air_proof_inputs, generate_air_proof_inputs_in_chunks,
+            generate_air_proof_inputs_with_registry, main, participating_weight, public_values,
+            registry::Registry, trace_heights, try_generate_air_proof_inputs, Layout,
+            MerkleLayout, SignerSetLayout, DEFAULT_CHUNK_SIZE,
+        },
+        hash_sig::test::mock_vi,
+        E, F,
+    });
+    use crate::util::{
+        engine::{Config, Engine, Poseidon2},
+        key::{fingerprint, keygen, load_pk, save_pk, KeyParameters, LoadError},
+        report::air_reports,
+        validation::{InvalidReason, ValidationReport},
+        zip,
     };
+    use core::{iter::zip, num::NonZeroUsize};
+    use openvm_stark_backend::{keygen::types::MultiStarkProvingKey, prover::types::ProofInput};
     use openvm_stark_sdk::engine::StarkEngine;
+    use p3_field::{FieldAlgebra, PrimeField32};
+    use p3_matrix::Matrix;
+    use std::{env, fs, process};
+
+    const LAYOUTS: [Layout; 4] = [
+        Layout {
+            merkle: MerkleLayout::Fused,
+            chain_compressions_per_row: NonZeroUsize::MIN,
+            chain_instances: NonZeroUsize::MIN,
+            signer_set: SignerSetLayout::Sponge,
+        },
+        Layout {
+            merkle: MerkleLayout::Split,
+            chain_compressions_per_row: NonZeroUsize::MIN,
+            chain_instances: NonZeroUsize::new(2).unwrap(),
+            signer_set: SignerSetLayout::Sponge,
+        },
+        Layout {
+            merkle: MerkleLayout::Split,
+            chain_compressions_per_row: NonZeroUsize::new(2).unwrap(),
+            chain_instances: NonZeroUsize::MIN,
+            signer_set: SignerSetLayout::Sponge,
+        },
+        Layout {
+            merkle: MerkleLayout::Fused,
+            chain_compressions_per_row: NonZeroUsize::new(3).unwrap(),
+            chain_instances: NonZeroUsize::new(3).unwrap(),
+            signer_set: SignerSetLayout::Sponge,
+        },
+    ];
 
     #[test]
     fn chip() {
         let engine = Engine::<F, E>::fastest();
-        for log_sigs in 4..8 {
+        for (log_sigs, layout) in
+            (4..8).flat_map(|log_sigs| LAYOUTS.map(|layout| (log_sigs, layout)))
+        {
             let vi = mock_vi(1 << log_sigs);
-            let (airs, air_proof_inputs) = generate_air_proof_inputs(engine.log_blowup(), vi);
+            let participation = vec![true; vi.pairs.len()];
+            let (airs, air_proof_inputs) = generate_air_proof_inputs_in_chunks(
+                engine.log_blowup(),
+                vi,
+                &participation,
+                DEFAULT_CHUNK_SIZE,
+                layout,
+            );
+            engine.run_test_impl(airs, air_proof_inputs).unwrap();
+        }
+    }
+
+    #[test]
+    fn chip_in_chunks() {
+        let engine = Engine::<F, E>::fastest();
+        let vi = mock_vi(20);
+        let participation = (0..20).map(|idx| idx % 3 != 1).collect::<Vec<_>>();
+        for layout in LAYOUTS {
+            let (_, expected) = generate_air_proof_inputs_in_chunks::<Config<F, E>>(
+                engine.log_blowup(),
+                vi.clone(),
+                &participation,
+                usize::MAX,
+                layout,
+            );
+            for chunk_size in [1, 3] {
+                let (airs, air_proof_inputs) = generate_air_proof_inputs_in_chunks(
+                    engine.log_blowup(),
+                    vi.clone(),
+                    &participation,
+                    chunk_size,
+                    layout,
+                );
+                zip(&air_proof_inputs, &expected).for_each(|(input, expected)| {
+                    assert_eq!(
+                        input.raw.common_main.as_ref().unwrap().values,
+                        expected.raw.common_main.as_ref().unwrap().values
+                    );
+                    assert_eq!(input.raw.public_values, expected.raw.public_values);
+                });
+                engine.run_test_impl(airs, air_proof_inputs).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn chip_with_registry() {
+        let engine = Engine::<F, E>::fastest();
+        let vi = mock_vi(20);
+        let participation = (0..20).map(|idx| idx % 3 != 1).collect::<Vec<_>>();
+        let public_keys = vi.pairs.iter().map(|(pk, _)| *pk).collect::<Vec<_>>();
+        let weights = (0..20).map(|idx| idx * 7 % 10).collect::<Vec<_>>();
+        let threshold = participating_weight(&participation, &weights);
+        let registry =
+            Registry::<Config<F, E>>::commit(engine.config().pcs(), public_keys, weights);
+        for (layout, threshold) in zip(LAYOUTS, [threshold, threshold - 1, 1, 0]) {
+            let layout = Layout {
+                signer_set: SignerSetLayout::Registry,
+                ..layout
+            };
+            let (airs, air_proof_inputs) = generate_air_proof_inputs_with_registry(
+                engine.log_blowup(),
+                vi.clone(),
+                &participation,
+                DEFAULT_CHUNK_SIZE,
+                layout,
+                &registry,
+                threshold,
+            );
+            assert_eq!(
+                air_proof_inputs[0].raw.public_values,
+                main::public_values(threshold, registry.total_weight())
+            );
             engine.run_test_impl(airs, air_proof_inputs).unwrap();
         }
     }
+
+    #[test]
+    fn chip_with_poseidon2_engine() {
+        let engine = Engine::<F, E, Poseidon2>::with_hash(1, 0);
+        let vi = mock_vi(20);
+        let participation = vec![true; vi.pairs.len()];
+        let (airs, air_proof_inputs) =
+            generate_air_proof_inputs(engine.log_blowup(), vi, &participation);
+        engine.run_test_impl(airs, air_proof_inputs).unwrap();
+    }
+
+    #[test]
+    fn chip_with_invalid_signatures() {
+        let engine = Engine::<F, E>::fastest();
+        for num_sigs in [1, 5, 20, 37] {
+            let mut vi = mock_vi(num_sigs);
+            let participation = (0..num_sigs).map(|idx| idx % 3 != 1).collect::<Vec<_>>();
+            vi.pairs
+                .iter_mut()
+                .zip(&participation)
+                .filter(|(_, is_participating)| !**is_participating)
+                .for_each(|((_, sig), _)| sig.merkle_siblings[0][0] += F::ONE);
+            let public_keys = vi.pairs.iter().map(|(pk, _)| *pk).collect::<Vec<_>>();
+            let num_participating = participation.iter().filter(|p| **p).count();
+            let public_values = public_values(
+                vi.epoch,
+                vi.msg,
+                num_participating as u32,
+                num_sigs as u32,
+                &participation,
+                &public_keys,
+                Layout::default(),
+            );
+            let (airs, air_proof_inputs) =
+                generate_air_proof_inputs(engine.log_blowup(), vi, &participation);
+            zip(&air_proof_inputs, public_values).for_each(|(input, public_values)| {
+                assert_eq!(input.raw.public_values, public_values);
+            });
+            engine.run_test_impl(airs, air_proof_inputs).unwrap();
+        }
+    }
+
+    #[test]
+    fn try_chip_with_invalid_signatures() {
+        let mut vi = mock_vi(5);
+        vi.pairs[1].1.merkle_siblings[0][0] += F::ONE;
+        vi.pairs[3] = vi.pairs[0];
+        let participation = vec![true; 5];
+        assert_eq!(
+            try_generate_air_proof_inputs::<Config<F, E>>(0, vi.clone(), &participation).err(),
+            Some(ValidationReport {
+                invalid: vec![
+                    (1, InvalidReason::MerkleRoot),
+                    (3, InvalidReason::DuplicateSigner)
+                ]
+            })
+        );
+        let participation = [true, false, true, false, true];
+        assert!(try_generate_air_proof_inputs::<Config<F, E>>(0, vi, &participation).is_ok());
+    }
+
+    #[test]
+    fn keygen_without_witness() {
+        let engine = Engine::<F, E>::fastest();
+        let vi = mock_vi(20);
+        let participation = vec![true; vi.pairs.len()];
+        let pk = keygen(&engine, &airs(vi.pairs.len(), Layout::default()));
+
+        let params = KeyParameters::new(&engine, format!("{:?}", Layout::default()));
+        let path = env::temp_dir().join(format!("pk-{}-{}", F::ORDER_U32, process::id()));
+        save_pk(&path, &params, &pk).unwrap();
+        let loaded: MultiStarkProvingKey<_> = load_pk(&path, &params).unwrap();
+        let other = KeyParameters::new(&Engine::<F, E>::new(2, 0), params.airs.clone());
+        assert!(matches!(
+            load_pk::<Config<F, E>>(&path, &other),
+            Err(LoadError::ParametersMismatch { .. })
+        ));
+        fs::remove_file(&path).unwrap();
+        assert_eq!(fingerprint(&pk.get_vk()), fingerprint(&loaded.get_vk()));
+
+        let (_, air_proof_inputs) =
+            generate_air_proof_inputs(engine.log_blowup(), vi, &participation);
+        let proof = engine.prove(
+            &loaded,
+            ProofInput::new(zip(0.., air_proof_inputs).collect()),
+        );
+        engine.verify(&pk.get_vk(), &proof).unwrap();
+    }
+
+    #[test]
+    fn report() {
+        let engine = Engine::<F, E>::fastest();
+        let vi = mock_vi(20);
+        let num_sigs = vi.pairs.len();
+        let participation = vec![true; num_sigs];
+        let public_keys = vi.pairs.iter().map(|(pk, _)| *pk).collect::<Vec<_>>();
+        for layout in LAYOUTS {
+            let public_values = public_values(
+                vi.epoch,
+                vi.msg,
+                num_sigs as u32,
+                num_sigs as u32,
+                &participation,
+                &public_keys,
+                layout,
+            );
+            let pk = keygen(&engine, &airs(num_sigs, layout));
+            let reports = air_reports(&pk, &trace_heights(num_sigs, num_sigs, layout));
+            let (_, air_proof_inputs) = generate_air_proof_inputs_in_chunks(
+                engine.log_blowup(),
+                vi.clone(),
+                &participation,
+                DEFAULT_CHUNK_SIZE,
+                layout,
+            );
+            zip!(reports, air_proof_inputs, public_values).for_each(
+                |(report, input, public_values)| {
+                    let trace = input.raw.common_main.unwrap();
+                    assert_eq!(report.width, trace.width());
+                    assert_eq!(report.height, trace.height());
+                    assert!(report.padding_rows < report.height);
+                    assert_eq!(report.num_public_values, public_values.len());
+                },
+            );
+        }
+    }
 }
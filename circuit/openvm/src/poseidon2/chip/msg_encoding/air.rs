@@ -0,0 +1,212 @@
+use_instance!({
+    chip::{
+        decomposition::{F_MS_LIMB, F_MS_LIMB_BITS, LIMB_BITS, NUM_LIMBS},
+        msg_encoding::{
+            column::{MsgEncodingCols, NUM_MSG_ENCODING_COLS},
+            BYTES_PER_LIMB_PAIR, NUM_MSG_ACC_LIMBS,
+        },
+        range_check::send_range_check,
+        Bus,
+    },
+    hash_sig::{MSG_FE_LEN, MSG_LEN},
+    F,
+});
+use crate::util::zip;
+use core::{array::from_fn, borrow::Borrow};
+use itertools::Itertools;
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    rap::{BaseAirWithPublicValues, PartitionedBaseAir},
+};
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_field::FieldAlgebra;
+use p3_matrix::Matrix;
+
+/// AIR deriving `encode_msg(msg)` from the raw message in public values,
+/// which sends the encoded message to every participating signature.
+///
+/// The encoded message is accumulated as `sum(encoded_msg[i] * p^i)` into
+/// limbs, whose value should be the little-endian message. Each element is
+/// decomposed into canonical limbs, so the encoding is unique.
+#[derive(Clone, Copy, Debug)]
+pub struct MsgEncodingAir;
+
+impl BaseAir<F> for MsgEncodingAir {
+    fn width(&self) -> usize {
+        NUM_MSG_ENCODING_COLS
+    }
+}
+
+impl PartitionedBaseAir<F> for MsgEncodingAir {}
+
+impl BaseAirWithPublicValues<F> for MsgEncodingAir {
+    fn num_public_values(&self) -> usize {
+        MSG_LEN
+    }
+}
+
+impl<AB> Air<AB> for MsgEncodingAir
+where
+    AB: InteractionBuilder<F = F> + AirBuilderWithPublicValues,
+{
+    fn eval(&self, builder: &mut AB) {
+        let msg: [AB::Expr; MSG_LEN] = from_fn(|i| builder.public_values()[i].into());
+
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let next = main.row_slice(1);
+        let local: &MsgEncodingCols<AB::Var> = (*local).borrow();
+        let next: &MsgEncodingCols<AB::Var> = (*next).borrow();
+
+        // When every row
+        eval_every_row(builder, local);
+        eval_last_step(builder, msg, local);
+
+        // When first row
+        {
+            let mut builder = builder.when_first_row();
+
+            builder.assert_one(local.inds[0]);
+            zip!(
+                local.acc_limbs,
+                local
+                    .limbs::<AB>()
+                    .into_iter()
+                    .chain([AB::Expr::ZERO; NUM_MSG_ACC_LIMBS - NUM_LIMBS])
+            )
+            .for_each(|(a, b)| builder.assert_eq(a, b));
+        }
+
+        // When transition
+        {
+            let mut builder = builder.when_transition();
+
+            eval_transition(&mut builder, local, next);
+        }
+
+        // Interaction
+        send_encoded_msg(builder, local);
+        send_range_checks(builder, local);
+    }
+}
+
+#[inline]
+fn eval_every_row<AB>(builder: &mut AB, cols: &MsgEncodingCols<AB::Var>)
+where
+    AB: AirBuilder<F = F>,
+{
+    cols.inds.eval_every_row(builder);
+    cols.is_ms_limb_max.eval(
+        builder,
+        cols.ms_limb,
+        AB::Expr::from_canonical_u32(F_MS_LIMB),
+    );
+    // When MSL == F_MS_LIMB, least significant limbs should be 0.
+    builder
+        .when(cols.is_ms_limb_max.output)
+        .assert_zero(cols.ls_limbs.into_iter().map_into().sum::<AB::Expr>());
+    (0..MSG_FE_LEN).for_each(|step| {
+        builder.when(cols.inds[step]).assert_eq(
+            cols.encoded_msg[MSG_FE_LEN - 1 - step],
+            cols.element::<AB>(),
+        );
+    });
+}
+
+/// Constrains accumulation of `next` element as `local * p + next`, where
+/// `p = (F_MS_LIMB << (2 * LIMB_BITS)) + 1`.
+#[inline]
+fn eval_transition<AB>(
+    builder: &mut AB,
+    local: &MsgEncodingCols<AB::Var>,
+    next: &MsgEncodingCols<AB::Var>,
+) where
+    AB: AirBuilder<F = F>,
+{
+    local.inds.eval_transition(builder, &next.inds);
+    // The accumulation can't restart after the last step.
+    builder
+        .when(local.is_last_step::<AB>())
+        .assert_zero(next.inds[0]);
+
+    let mut builder = builder.when(local.inds.is_transition::<AB>());
+
+    zip!(next.encoded_msg, local.encoded_msg).for_each(|(a, b)| builder.assert_eq(a, b));
+    let f_ms_limb = F::from_canonical_u32(F_MS_LIMB);
+    let base = F::from_canonical_u32(1 << LIMB_BITS);
+    let limbs = next.limbs::<AB>();
+    (0..NUM_MSG_ACC_LIMBS).for_each(|i| {
+        let mut lhs: AB::Expr = next.acc_limbs[i].into();
+        if let Some(carry) = next.carries.get(i) {
+            lhs += *carry * base;
+        }
+        let mut rhs: AB::Expr = local.acc_limbs[i].into();
+        if let Some(j) = i.checked_sub(NUM_LIMBS - 1) {
+            rhs += local.acc_limbs[j] * f_ms_limb;
+        }
+        if let Some(limb) = limbs.get(i) {
+            rhs += limb.clone();
+        }
+        if let Some(j) = i.checked_sub(1) {
+            rhs += AB::Expr::from(next.carries[j]);
+        }
+        builder.assert_eq(lhs, rhs);
+    });
+}
+
+/// Constrains the accumulation value to be the little-endian `msg`, compared
+/// every `BYTES_PER_LIMB_PAIR` bytes at once whose value is less than the
+/// field modulus.
+#[inline]
+fn eval_last_step<AB>(builder: &mut AB, msg: [AB::Expr; MSG_LEN], cols: &MsgEncodingCols<AB::Var>)
+where
+    AB: AirBuilder<F = F>,
+{
+    let mut builder = builder.when(cols.is_last_step::<AB>());
+
+    let mut acc_limbs = cols.acc_limbs.into_iter();
+    msg.chunks(BYTES_PER_LIMB_PAIR).for_each(|bytes| {
+        let (lo, hi) = acc_limbs.next_tuple().unwrap();
+        builder.assert_eq(
+            lo + hi * F::from_canonical_u32(1 << LIMB_BITS),
+            bytes
+                .iter()
+                .enumerate()
+                .map(|(idx, byte)| byte.clone() * F::from_canonical_u32(1 << (idx * 8)))
+                .sum::<AB::Expr>(),
+        );
+    });
+    acc_limbs.for_each(|limb| builder.assert_zero(limb));
+}
+
+#[inline]
+fn send_encoded_msg<AB>(builder: &mut AB, cols: &MsgEncodingCols<AB::Var>)
+where
+    AB: InteractionBuilder<F = F>,
+{
+    builder.push_send(
+        Bus::EncodedMsg as usize,
+        cols.encoded_msg,
+        cols.is_last_step::<AB>() * cols.mult,
+    );
+}
+
+#[inline]
+fn send_range_checks<AB>(builder: &mut AB, cols: &MsgEncodingCols<AB::Var>)
+where
+    AB: InteractionBuilder<F = F>,
+{
+    let is_active = cols.inds.is_active::<AB>();
+    cols.ls_limbs
+        .iter()
+        .chain(&cols.acc_limbs)
+        .chain(&cols.carries)
+        .for_each(|limb| send_range_check(builder, *limb, LIMB_BITS, is_active.clone()));
+    send_range_check(builder, cols.ms_limb, F_MS_LIMB_BITS, is_active.clone());
+    send_range_check(
+        builder,
+        AB::Expr::from_canonical_u32(F_MS_LIMB) - cols.ms_limb,
+        F_MS_LIMB_BITS,
+        is_active,
+    );
+}
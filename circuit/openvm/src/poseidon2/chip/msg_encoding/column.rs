@@ -0,0 +1,102 @@
+use_instance!({
+    chip::{
+        decomposition::{LIMB_BITS, NUM_LIMBS},
+        msg_encoding::NUM_MSG_ACC_LIMBS,
+    },
+    hash_sig::MSG_FE_LEN,
+});
+use crate::{
+    gadget::{cycle_bits::CycleBits, is_equal::IsEqualCols},
+    util::AlignBorrow,
+};
+use core::{
+    array::from_fn,
+    borrow::{Borrow, BorrowMut},
+};
+use p3_air::AirBuilder;
+use p3_field::FieldAlgebra;
+
+pub const NUM_MSG_ENCODING_COLS: usize = size_of::<MsgEncodingCols<u8>>();
+
+#[repr(C)]
+pub struct MsgEncodingCols<T> {
+    /// One-hot vector indicating current accumulation step, where
+    /// `encoded_msg[MSG_FE_LEN - 1 - step]` is accumulated at `step` as the
+    /// most significant one first.
+    pub inds: CycleBits<T, MSG_FE_LEN>,
+    /// Encoded message, which is the same in all active rows.
+    pub encoded_msg: [T; MSG_FE_LEN],
+    /// Least significant limbs of the element accumulated at this step in
+    /// little-endian, each in `0..1 << LIMB_BITS`.
+    pub ls_limbs: [T; NUM_LIMBS - 1],
+    /// Most significant limb of the element accumulated at this step in
+    /// `0..=F_MS_LIMB`.
+    pub ms_limb: T,
+    /// Whether `ms_limb == F_MS_LIMB`, in which case `ls_limbs` should be
+    /// zero for the decomposition to be canonical.
+    pub is_ms_limb_max: IsEqualCols<T>,
+    /// Limbs of accumulation value in little-endian.
+    pub acc_limbs: [T; NUM_MSG_ACC_LIMBS],
+    /// Carries of limbs addition.
+    pub carries: [T; NUM_MSG_ACC_LIMBS - 1],
+    /// Number of signatures receiving the encoded message, which is only
+    /// sent at the last step.
+    pub mult: T,
+}
+
+impl<T: Copy> MsgEncodingCols<T> {
+    #[inline]
+    pub fn is_last_step<AB: AirBuilder>(&self) -> AB::Expr
+    where
+        T: Into<AB::Expr>,
+    {
+        self.inds[MSG_FE_LEN - 1].into()
+    }
+
+    /// Returns limbs of the element accumulated at this step in
+    /// little-endian.
+    #[inline]
+    pub fn limbs<AB: AirBuilder>(&self) -> [AB::Expr; NUM_LIMBS]
+    where
+        T: Into<AB::Expr>,
+    {
+        from_fn(|idx| {
+            self.ls_limbs
+                .get(idx)
+                .copied()
+                .unwrap_or(self.ms_limb)
+                .into()
+        })
+    }
+
+    /// Returns the element accumulated at this step composed from its limbs.
+    #[inline]
+    pub fn element<AB: AirBuilder>(&self) -> AB::Expr
+    where
+        T: Into<AB::Expr>,
+    {
+        self.limbs::<AB>()
+            .into_iter()
+            .enumerate()
+            .map(|(idx, limb)| limb * AB::Expr::from_canonical_u32(1 << (idx * LIMB_BITS)))
+            .sum()
+    }
+}
+
+impl<T> AlignBorrow<T> for MsgEncodingCols<T> {
+    const SIZE: usize = NUM_MSG_ENCODING_COLS;
+}
+
+impl<T> Borrow<MsgEncodingCols<T>> for [T] {
+    #[inline]
+    fn borrow(&self) -> &MsgEncodingCols<T> {
+        MsgEncodingCols::align_borrow(self)
+    }
+}
+
+impl<T> BorrowMut<MsgEncodingCols<T>> for [T] {
+    #[inline]
+    fn borrow_mut(&mut self) -> &mut MsgEncodingCols<T> {
+        MsgEncodingCols::align_borrow_mut(self)
+    }
+}
@@ -0,0 +1,195 @@
+use_instance!({
+    chip::{
+        decomposition::{F_MS_LIMB, F_MS_LIMB_BITS, LIMB_BITS, LIMB_MASK, NUM_LIMBS},
+        msg_encoding::{
+            column::{MsgEncodingCols, NUM_MSG_ENCODING_COLS},
+            public_values, trace_height, MsgEncodingAir, NUM_MSG_ACC_LIMBS,
+        },
+        range_check::RangeChecker,
+    },
+    hash_sig::{encode_msg, MSG_FE_LEN, MSG_LEN},
+    F,
+});
+use crate::util::{
+    field::{MaybeUninitField, MaybeUninitFieldSlice},
+    trace::UninitTrace,
+    zip,
+};
+use core::{any::type_name, array::from_fn, mem::MaybeUninit};
+use openvm_stark_backend::{
+    config::{Domain, StarkGenericConfig},
+    prover::types::{AirProofInput, AirProofRawInput},
+    rap::AnyRap,
+    Chip, ChipUsageGetter,
+};
+use p3_commit::PolynomialSpace;
+use p3_field::{FieldAlgebra, PrimeField32};
+use p3_matrix::dense::RowMajorMatrix;
+use std::sync::Arc;
+
+#[derive(Clone, Copy, Debug)]
+pub struct MsgEncodingChip<'a> {
+    extra_capacity_bits: usize,
+    msg: [u8; MSG_LEN],
+    num_sigs: usize,
+    range_checker: &'a RangeChecker,
+}
+
+impl<'a> MsgEncodingChip<'a> {
+    /// Returns a `MsgEncodingChip` sending the encoded `msg` to `num_sigs`
+    /// participating signatures.
+    pub const fn new(
+        extra_capacity_bits: usize,
+        msg: [u8; MSG_LEN],
+        num_sigs: usize,
+        range_checker: &'a RangeChecker,
+    ) -> Self {
+        Self {
+            extra_capacity_bits,
+            msg,
+            num_sigs,
+            range_checker,
+        }
+    }
+}
+
+impl ChipUsageGetter for MsgEncodingChip<'_> {
+    fn air_name(&self) -> String {
+        type_name::<MsgEncodingAir>().to_string()
+    }
+
+    fn current_trace_height(&self) -> usize {
+        trace_height()
+    }
+
+    fn trace_width(&self) -> usize {
+        NUM_MSG_ENCODING_COLS
+    }
+}
+
+impl<SC: StarkGenericConfig> Chip<SC> for MsgEncodingChip<'_>
+where
+    Domain<SC>: PolynomialSpace<Val = F>,
+{
+    fn air(&self) -> Arc<dyn AnyRap<SC>> {
+        Arc::new(MsgEncodingAir)
+    }
+
+    fn generate_air_proof_input(self) -> AirProofInput<SC> {
+        AirProofInput {
+            cached_mains_pdata: Vec::new(),
+            raw: AirProofRawInput {
+                cached_mains: Vec::new(),
+                common_main: Some(generate_trace_rows(
+                    self.extra_capacity_bits,
+                    self.msg,
+                    self.num_sigs,
+                    self.range_checker,
+                )),
+                public_values: public_values(self.msg),
+            },
+        }
+    }
+}
+
+/// Generates rows accumulating `encode_msg(msg)`, which is sent to `num_sigs`
+/// participating signatures, and records their range checks into
+/// `range_checker`.
+pub fn generate_trace_rows(
+    extra_capacity_bits: usize,
+    msg: [u8; MSG_LEN],
+    num_sigs: usize,
+    range_checker: &RangeChecker,
+) -> RowMajorMatrix<F> {
+    let mut trace = UninitTrace::new(NUM_MSG_ENCODING_COLS, trace_height(), extra_capacity_bits);
+    let rows = unsafe { trace.rows_mut::<MsgEncodingCols<MaybeUninit<F>>>() };
+
+    let encoded_msg = encode_msg::<F>(msg);
+    let (rows, padding_rows) = rows.split_at_mut(MSG_FE_LEN);
+    let mut acc_limbs = [0; NUM_MSG_ACC_LIMBS];
+    zip!(rows, encoded_msg.iter().rev())
+        .enumerate()
+        .for_each(|(step, (row, element))| {
+            let element = element.as_canonical_u32();
+            let limbs: [u32; NUM_LIMBS] = from_fn(|idx| (element >> (idx * LIMB_BITS)) & LIMB_MASK);
+            let mut carries = [0; NUM_MSG_ACC_LIMBS - 1];
+            let mut carry = 0;
+            let prev = acc_limbs;
+            (0..NUM_MSG_ACC_LIMBS).for_each(|i| {
+                let value = prev[i]
+                    + i.checked_sub(NUM_LIMBS - 1)
+                        .map_or(0, |j| prev[j] * F_MS_LIMB)
+                    + limbs.get(i).copied().unwrap_or_default()
+                    + carry;
+                acc_limbs[i] = value & LIMB_MASK;
+                carry = value >> LIMB_BITS;
+                if let Some(cell) = carries.get_mut(i) {
+                    *cell = carry;
+                }
+            });
+            debug_assert_eq!(carry, 0);
+            let mult = if step == MSG_FE_LEN - 1 { num_sigs } else { 0 };
+            generate_trace_row(
+                row,
+                step,
+                &encoded_msg,
+                &limbs,
+                &acc_limbs,
+                &carries,
+                mult,
+                range_checker,
+            );
+        });
+    padding_rows.iter_mut().for_each(generate_trace_row_padding);
+
+    unsafe { trace.assume_init() }
+}
+
+#[allow(clippy::too_many_arguments)]
+#[inline]
+fn generate_trace_row(
+    row: &mut MsgEncodingCols<MaybeUninit<F>>,
+    step: usize,
+    encoded_msg: &[F; MSG_FE_LEN],
+    limbs: &[u32; NUM_LIMBS],
+    acc_limbs: &[u32; NUM_MSG_ACC_LIMBS],
+    carries: &[u32; NUM_MSG_ACC_LIMBS - 1],
+    mult: usize,
+    range_checker: &RangeChecker,
+) {
+    let (ms_limb, ls_limbs) = limbs.split_last().unwrap();
+    ls_limbs
+        .iter()
+        .chain(acc_limbs)
+        .chain(carries)
+        .for_each(|limb| range_checker.add_count(*limb, LIMB_BITS));
+    range_checker.add_count(*ms_limb, F_MS_LIMB_BITS);
+    range_checker.add_count(F_MS_LIMB - ms_limb, F_MS_LIMB_BITS);
+    row.inds.populate(Some(step));
+    row.encoded_msg.fill_from_slice(encoded_msg);
+    row.ls_limbs
+        .fill_from_iter(ls_limbs.iter().copied().map(F::from_canonical_u32));
+    row.ms_limb.write_u32(*ms_limb);
+    row.is_ms_limb_max.populate(
+        F::from_canonical_u32(*ms_limb),
+        F::from_canonical_u32(F_MS_LIMB),
+    );
+    row.acc_limbs
+        .fill_from_iter(acc_limbs.iter().copied().map(F::from_canonical_u32));
+    row.carries
+        .fill_from_iter(carries.iter().copied().map(F::from_canonical_u32));
+    row.mult.write_usize(mult);
+}
+
+#[inline]
+fn generate_trace_row_padding(row: &mut MsgEncodingCols<MaybeUninit<F>>) {
+    row.inds.populate(None);
+    row.encoded_msg.fill_zero();
+    row.ls_limbs.fill_zero();
+    row.ms_limb.write_zero();
+    row.is_ms_limb_max
+        .populate(F::ZERO, F::from_canonical_u32(F_MS_LIMB));
+    row.acc_limbs.fill_zero();
+    row.carries.fill_zero();
+    row.mult.write_zero();
+}
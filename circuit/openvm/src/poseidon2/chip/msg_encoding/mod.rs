@@ -0,0 +1,76 @@
+use_instance!({
+    chip::decomposition::LIMB_BITS,
+    hash_sig::{MSG_FE_LEN, MSG_LEN},
+    F,
+});
+use p3_field::{FieldAlgebra, PrimeField32};
+
+mod air;
+pub(super) mod column;
+#[cfg(feature = "prover")]
+pub(super) mod generation;
+
+pub use air::MsgEncodingAir;
+#[cfg(feature = "prover")]
+pub use generation::MsgEncodingChip;
+
+/// Number of limbs of `sum(encoded_msg[i] * p^i)`, which is less than
+/// `p^MSG_FE_LEN`.
+pub const NUM_MSG_ACC_LIMBS: usize =
+    (MSG_FE_LEN * F::ORDER_U32.next_power_of_two().ilog2() as usize).div_ceil(LIMB_BITS);
+
+/// Number of message bytes compared with each pair of accumulation limbs,
+/// whose width is a multiple of byte.
+pub const BYTES_PER_LIMB_PAIR: usize = {
+    assert!((2 * LIMB_BITS) % 8 == 0);
+    2 * LIMB_BITS / 8
+};
+
+const __: () = assert!(2 * MSG_LEN.div_ceil(BYTES_PER_LIMB_PAIR) <= NUM_MSG_ACC_LIMBS);
+
+/// Returns public values of [`MsgEncodingAir`], which are the raw bytes of
+/// `msg`.
+pub fn public_values(msg: [u8; MSG_LEN]) -> Vec<F> {
+    msg.map(F::from_canonical_u8).to_vec()
+}
+
+pub const fn trace_height() -> usize {
+    MSG_FE_LEN.next_power_of_two()
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod test {
+    use_instance!({
+        chip::{air_idx, generate_air_proof_inputs, AirKind, Layout},
+        hash_sig::test::mock_vi,
+        E, F,
+    });
+    use crate::util::engine::Engine;
+    use openvm_stark_backend::utils::disable_debug_builder;
+    use openvm_stark_sdk::engine::StarkEngine;
+    use p3_field::FieldAlgebra;
+
+    const NUM_SIGS: usize = 4;
+
+    #[test]
+    fn forged_msg() {
+        let engine = Engine::<F, E>::fastest();
+        let (airs, air_proof_inputs) =
+            generate_air_proof_inputs(engine.log_blowup(), mock_vi(NUM_SIGS), &[true; NUM_SIGS]);
+
+        let msg_encoding_air_idx = air_idx(Layout::default(), AirKind::MsgEncoding).unwrap();
+        disable_debug_builder();
+        for (idx, perturb) in [
+            (0, F::ONE),
+            (31, F::ONE),
+            (31, F::from_canonical_u32(1 << 8)),
+        ] {
+            let mut air_proof_inputs = air_proof_inputs.clone();
+            air_proof_inputs[msg_encoding_air_idx].raw.public_values[idx] += perturb;
+            assert!(engine
+                .run_test_impl(airs.clone(), air_proof_inputs)
+                .is_err());
+        }
+        engine.run_test_impl(airs, air_proof_inputs).unwrap();
+    }
+}
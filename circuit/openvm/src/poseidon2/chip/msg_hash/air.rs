@@ -0,0 +1,170 @@
+use_instance!({
+    chip::{
+        msg_hash::{
+            column::{MsgHashCols, NUM_MSG_HASH_COLS},
+            poseidon2::{PARTIAL_ROUNDS, WIDTH},
+        },
+        Bus,
+    },
+    Poseidon2LinearLayers, F, HALF_FULL_ROUNDS, RC24, SBOX_DEGREE, SBOX_REGISTERS,
+});
+use crate::{gadget::select, util::zip};
+use core::{borrow::Borrow, iter};
+use openvm_stark_backend::{
+    air_builders::sub::SubAirBuilder,
+    interaction::InteractionBuilder,
+    rap::{BaseAirWithPublicValues, PartitionedBaseAir},
+};
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_field::FieldAlgebra;
+use p3_matrix::Matrix;
+use p3_poseidon2_air::{num_cols, Poseidon2Air};
+
+/// Alias for `Poseidon2Air` specialized with message-hash-related parameters.
+type Poseidon2AirMsgHash = Poseidon2Air<
+    F,
+    Poseidon2LinearLayers<WIDTH>,
+    WIDTH,
+    SBOX_DEGREE,
+    SBOX_REGISTERS,
+    HALF_FULL_ROUNDS,
+    PARTIAL_ROUNDS,
+>;
+
+#[derive(Debug)]
+pub struct MsgHashAir(Poseidon2AirMsgHash);
+
+impl Default for MsgHashAir {
+    fn default() -> Self {
+        Self(Poseidon2Air::new(RC24.into()))
+    }
+}
+
+impl BaseAir<F> for MsgHashAir {
+    fn width(&self) -> usize {
+        NUM_MSG_HASH_COLS
+    }
+}
+
+impl PartitionedBaseAir<F> for MsgHashAir {}
+
+impl BaseAirWithPublicValues<F> for MsgHashAir {
+    fn num_public_values(&self) -> usize {
+        1
+    }
+}
+
+impl<AB> Air<AB> for MsgHashAir
+where
+    AB: InteractionBuilder<F = F> + AirBuilderWithPublicValues,
+    AB::Expr: FieldAlgebra<F = F>,
+{
+    fn eval(&self, builder: &mut AB) {
+        self.0
+            .eval(&mut SubAirBuilder::<_, Poseidon2AirMsgHash, _>::new(
+                builder,
+                0
+                    ..num_cols::<
+                        WIDTH,
+                        SBOX_DEGREE,
+                        SBOX_REGISTERS,
+                        HALF_FULL_ROUNDS,
+                        PARTIAL_ROUNDS,
+                    >(),
+            ));
+
+        let epoch: AB::Expr = builder.public_values()[0].into();
+
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let next = main.row_slice(1);
+        let local: &MsgHashCols<AB::Var> = (*local).borrow();
+        let next: &MsgHashCols<AB::Var> = (*next).borrow();
+
+        // When every rows
+        eval_every_row(builder, epoch, local);
+
+        // When first row
+        {
+            let mut builder = builder.when_first_row();
+
+            builder.assert_one(*local.is_active);
+            builder.assert_zero(local.sig_idx);
+        }
+
+        // When transition
+        {
+            let mut builder = builder.when_transition();
+
+            eval_transition(&mut builder, local, next);
+        }
+
+        // Interaction
+        receive_encoded_msg(builder, local);
+        receive_msg_hash(builder, local);
+    }
+}
+
+/// Constrains the tweak to be `encode_tweak_msg(epoch)`, which is
+/// `[(epoch << 2) | 2, 0]`.
+#[inline]
+fn eval_every_row<AB>(builder: &mut AB, epoch: AB::Expr, cols: &MsgHashCols<AB::Var>)
+where
+    AB: AirBuilder<F = F>,
+{
+    cols.is_active.eval_every_row(builder);
+
+    let mut builder = builder.when(*cols.is_active);
+
+    zip!(
+        cols.encoded_tweak_msg(),
+        [
+            epoch * F::from_canonical_u32(1 << 2) + F::TWO,
+            AB::Expr::ZERO
+        ]
+    )
+    .for_each(|(a, b)| builder.assert_eq(a, b));
+    cols.padding().map(|v| builder.assert_zero(v));
+}
+
+#[inline]
+fn eval_transition<AB>(builder: &mut AB, local: &MsgHashCols<AB::Var>, next: &MsgHashCols<AB::Var>)
+where
+    AB: AirBuilder<F = F>,
+{
+    local.is_active.eval_transition(builder, &next.is_active);
+    builder.assert_eq(
+        next.sig_idx,
+        select(
+            (*next.is_active).into(),
+            AB::Expr::ZERO,
+            local.sig_idx + AB::Expr::ONE,
+        ),
+    );
+}
+
+#[inline]
+fn receive_encoded_msg<AB>(builder: &mut AB, cols: &MsgHashCols<AB::Var>)
+where
+    AB: InteractionBuilder<F = F>,
+{
+    builder.push_receive(
+        Bus::EncodedMsg as usize,
+        cols.encoded_msg(),
+        *cols.is_active,
+    );
+}
+
+#[inline]
+fn receive_msg_hash<AB>(builder: &mut AB, cols: &MsgHashCols<AB::Var>)
+where
+    AB: InteractionBuilder<F = F>,
+{
+    builder.push_receive(
+        Bus::MsgHash as usize,
+        iter::once(cols.sig_idx.into())
+            .chain(cols.parameter().map(Into::into))
+            .chain(cols.msg_hash::<AB>()),
+        *cols.is_active,
+    );
+}
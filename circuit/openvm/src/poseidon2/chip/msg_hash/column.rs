@@ -0,0 +1,89 @@
+use_instance!({
+    chip::msg_hash::poseidon2::{PARTIAL_ROUNDS, WIDTH},
+    hash_sig::{MSG_FE_LEN, MSG_HASH_FE_LEN, PARAM_FE_LEN, RHO_FE_LEN, TWEAK_FE_LEN},
+    HALF_FULL_ROUNDS, SBOX_DEGREE, SBOX_REGISTERS,
+});
+use crate::{gadget::lower_rows_filter::LowerRowsFilterCols, util::AlignBorrow};
+use core::{
+    array::from_fn,
+    borrow::{Borrow, BorrowMut},
+    slice,
+};
+use p3_air::AirBuilder;
+use p3_poseidon2_util::air::{outputs, Poseidon2Cols};
+
+pub const NUM_MSG_HASH_COLS: usize = size_of::<MsgHashCols<u8>>();
+
+const NUM_PADDING: usize = WIDTH - (RHO_FE_LEN + PARAM_FE_LEN + TWEAK_FE_LEN + MSG_FE_LEN);
+
+#[repr(C)]
+pub struct MsgHashCols<T> {
+    pub perm:
+        Poseidon2Cols<T, WIDTH, SBOX_DEGREE, SBOX_REGISTERS, HALF_FULL_ROUNDS, PARTIAL_ROUNDS>,
+    /// Whether this sig is active or not.
+    pub is_active: LowerRowsFilterCols<T>,
+    /// Signature index.
+    pub sig_idx: T,
+}
+
+impl<T> MsgHashCols<T> {
+    #[inline]
+    pub const fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(core::ptr::from_ref(self).cast::<T>(), NUM_MSG_HASH_COLS) }
+    }
+
+    #[inline]
+    pub const fn as_slice_mut(&mut self) -> &mut [T] {
+        unsafe {
+            slice::from_raw_parts_mut(core::ptr::from_mut(self).cast::<T>(), NUM_MSG_HASH_COLS)
+        }
+    }
+}
+
+impl<T: Copy> MsgHashCols<T> {
+    #[inline]
+    pub fn parameter(&self) -> [T; PARAM_FE_LEN] {
+        from_fn(|i| self.perm.inputs[RHO_FE_LEN + i])
+    }
+
+    #[inline]
+    pub fn encoded_tweak_msg(&self) -> [T; TWEAK_FE_LEN] {
+        from_fn(|i| self.perm.inputs[RHO_FE_LEN + PARAM_FE_LEN + i])
+    }
+
+    #[inline]
+    pub fn encoded_msg(&self) -> [T; MSG_FE_LEN] {
+        from_fn(|i| self.perm.inputs[RHO_FE_LEN + PARAM_FE_LEN + TWEAK_FE_LEN + i])
+    }
+
+    #[inline]
+    pub fn padding(&self) -> [T; NUM_PADDING] {
+        from_fn(|i| self.perm.inputs[WIDTH - NUM_PADDING + i])
+    }
+
+    #[inline]
+    pub fn msg_hash<AB: AirBuilder>(&self) -> [AB::Expr; MSG_HASH_FE_LEN]
+    where
+        T: Into<AB::Expr>,
+    {
+        from_fn(|i| self.perm.inputs[i].into() + outputs(&self.perm)[i].into())
+    }
+}
+
+impl<T> AlignBorrow<T> for MsgHashCols<T> {
+    const SIZE: usize = NUM_MSG_HASH_COLS;
+}
+
+impl<T> Borrow<MsgHashCols<T>> for [T] {
+    #[inline]
+    fn borrow(&self) -> &MsgHashCols<T> {
+        MsgHashCols::align_borrow(self)
+    }
+}
+
+impl<T> BorrowMut<MsgHashCols<T>> for [T] {
+    #[inline]
+    fn borrow_mut(&mut self) -> &mut MsgHashCols<T> {
+        MsgHashCols::align_borrow_mut(self)
+    }
+}
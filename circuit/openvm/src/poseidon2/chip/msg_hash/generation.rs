@@ -0,0 +1,98 @@
+use_instance!({
+    chip::msg_hash::{
+        column::{MsgHashCols, NUM_MSG_HASH_COLS},
+        poseidon2::{PARTIAL_ROUNDS, WIDTH},
+        trace_height, NUM_ROWS_PER_SIG,
+    },
+    hash_sig::{VerificationTrace, MSG_FE_LEN},
+    Poseidon2LinearLayers, F, HALF_FULL_ROUNDS, RC24, SBOX_DEGREE, SBOX_REGISTERS,
+});
+use crate::util::{field::MaybeUninitField, par_zip, trace::UninitTrace};
+use core::mem::MaybeUninit;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_maybe_rayon::prelude::*;
+use p3_poseidon2_util::air::generate_trace_rows_for_perm;
+
+pub fn uninit_trace(extra_capacity_bits: usize, num_sigs: usize) -> UninitTrace<F> {
+    UninitTrace::new(
+        NUM_MSG_HASH_COLS,
+        trace_height(num_sigs),
+        extra_capacity_bits,
+    )
+}
+
+/// Generates rows of `traces` as signatures starting from `sig_offset`.
+pub fn generate_trace_rows_chunk(
+    trace: &mut UninitTrace<F>,
+    epoch: u32,
+    encoded_msg: [F; MSG_FE_LEN],
+    sig_offset: usize,
+    traces: &[VerificationTrace],
+) {
+    let rows = unsafe { trace.rows_mut::<MsgHashCols<MaybeUninit<F>>>() };
+    let rows = &mut rows[sig_offset * NUM_ROWS_PER_SIG..][..traces.len() * NUM_ROWS_PER_SIG];
+
+    par_zip!(rows, traces)
+        .enumerate()
+        .for_each(|(idx, (row, trace))| {
+            generate_trace_row_sig(row, epoch, encoded_msg, sig_offset + idx, trace);
+        });
+}
+
+/// Generates padding rows after `num_sigs` signatures and returns the trace.
+pub fn finalize(mut trace: UninitTrace<F>, num_sigs: usize) -> RowMajorMatrix<F> {
+    let rows = unsafe { trace.rows_mut::<MsgHashCols<MaybeUninit<F>>>() };
+    generate_trace_rows_padding(&mut rows[num_sigs * NUM_ROWS_PER_SIG..]);
+
+    unsafe { trace.assume_init() }
+}
+
+#[inline]
+fn generate_trace_row_sig(
+    row: &mut MsgHashCols<MaybeUninit<F>>,
+    epoch: u32,
+    encoded_msg: [F; MSG_FE_LEN],
+    sig_idx: usize,
+    trace: &VerificationTrace,
+) {
+    row.is_active.populate(true);
+    row.sig_idx.write_usize(sig_idx);
+    generate_trace_rows_for_perm::<
+        F,
+        Poseidon2LinearLayers<WIDTH>,
+        WIDTH,
+        SBOX_DEGREE,
+        SBOX_REGISTERS,
+        HALF_FULL_ROUNDS,
+        PARTIAL_ROUNDS,
+    >(
+        &mut row.perm,
+        trace.msg_hash_preimage(epoch, encoded_msg),
+        &RC24,
+    );
+}
+
+#[inline]
+fn generate_trace_rows_padding(rows: &mut [MsgHashCols<MaybeUninit<F>>]) {
+    if let Some((template, rows)) = rows.split_first_mut() {
+        generate_trace_row_padding(template);
+        let template = template.as_slice();
+        rows.par_iter_mut()
+            .for_each(|row| row.as_slice_mut().copy_from_slice(template));
+    }
+}
+
+#[inline]
+fn generate_trace_row_padding(row: &mut MsgHashCols<MaybeUninit<F>>) {
+    row.is_active.populate(false);
+    row.sig_idx.write_zero();
+    generate_trace_rows_for_perm::<
+        F,
+        Poseidon2LinearLayers<WIDTH>,
+        WIDTH,
+        SBOX_DEGREE,
+        SBOX_REGISTERS,
+        HALF_FULL_ROUNDS,
+        PARTIAL_ROUNDS,
+    >(&mut row.perm, Default::default(), &RC24);
+}
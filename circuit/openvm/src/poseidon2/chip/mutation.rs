@@ -0,0 +1,679 @@
+//! Soundness tests perturbing a single cell of honest traces, each of which
+//! should be rejected by constraints or interactions.
+//!
+//! Forgeries that need consistent rewriting of Poseidon2 rows, e.g. forged
+//! tweaks of Merkle path, aren't covered here but by the `forged_tweak` tests
+//! of `merkle_path` and `merkle_tree`.
+
+use_instance!({
+    chip::{
+        air_idx,
+        chain::column::{ChainCols, NUM_CHAIN_COLS},
+        decomposition::column::DecompositionCols,
+        generate_air_proof_inputs_in_chunks, generate_air_proof_inputs_with_registry,
+        main::column::MainCols,
+        merkle_leaf::column::MerkleLeafCols,
+        merkle_path::column::MerklePathCols,
+        merkle_tree::column::MerkleTreeCols,
+        msg_encoding::column::MsgEncodingCols,
+        msg_hash::column::MsgHashCols,
+        participating_weight,
+        participation::column::ParticipationCols,
+        range_check::column::RangeCheckCols,
+        registry::{column::RegistryMultCols, Registry},
+        unique_signer::column::{KeyElementCols, UniqueSignerCols},
+        AirKind, Layout, MerkleLayout, SignerSetLayout, DEFAULT_CHUNK_SIZE,
+    },
+    hash_sig::{test::mock_vi, LOG_LIFETIME, MSG_FE_LEN, MSG_HASH_FE_LEN, SPONGE_PERM},
+    E, F,
+});
+use crate::util::engine::{Config, Engine};
+use core::{mem::offset_of, num::NonZeroUsize};
+use openvm_stark_backend::utils::disable_debug_builder;
+use openvm_stark_sdk::engine::StarkEngine;
+use p3_field::FieldAlgebra;
+use p3_matrix::Matrix;
+
+const PARTICIPATION: [bool; 4] = [true, true, false, true];
+
+/// Weights of signers in [`SignerSetLayout::Registry`].
+const WEIGHTS: [u32; 4] = [3, 5, 7, 11];
+
+#[derive(Clone, Copy, Debug)]
+struct Mutation {
+    name: &'static str,
+    air: AirKind,
+    row: usize,
+    col: usize,
+    perturb: fn(F) -> F,
+}
+
+impl Mutation {
+    const fn new(
+        name: &'static str,
+        air: AirKind,
+        row: usize,
+        col: usize,
+        perturb: fn(F) -> F,
+    ) -> Self {
+        Self {
+            name,
+            air,
+            row,
+            col,
+            perturb,
+        }
+    }
+}
+
+fn flip(value: F) -> F {
+    F::ONE - value
+}
+
+fn inc(value: F) -> F {
+    value + F::ONE
+}
+
+fn dec(value: F) -> F {
+    value - F::ONE
+}
+
+/// Returns mutations on targeted columns of the default [`Layout`], offsets
+/// are taken from column structs instantiated with `u8` so they are in unit
+/// of cells.
+fn mutations() -> Vec<Mutation> {
+    let decomposition_row = MSG_HASH_FE_LEN;
+    vec![
+        // Main
+        Mutation::new(
+            "non-participating signature claims participation",
+            AirKind::Main,
+            2,
+            offset_of!(MainCols<u8>, is_participating),
+            flip,
+        ),
+        Mutation::new(
+            "participating signature claims non-participation",
+            AirKind::Main,
+            0,
+            offset_of!(MainCols<u8>, is_participating),
+            flip,
+        ),
+        Mutation::new(
+            "participant_idx",
+            AirKind::Main,
+            1,
+            offset_of!(MainCols<u8>, participant_idx),
+            inc,
+        ),
+        Mutation::new(
+            "merkle_root",
+            AirKind::Main,
+            0,
+            offset_of!(MainCols<u8>, merkle_root),
+            inc,
+        ),
+        Mutation::new(
+            "msg_hash",
+            AirKind::Main,
+            0,
+            offset_of!(MainCols<u8>, msg_hash) + MSG_HASH_FE_LEN - 1,
+            inc,
+        ),
+        Mutation::new(
+            "weight",
+            AirKind::Main,
+            0,
+            offset_of!(MainCols<u8>, weight),
+            inc,
+        ),
+        Mutation::new(
+            "non-participating weight",
+            AirKind::Main,
+            2,
+            offset_of!(MainCols<u8>, weight),
+            dec,
+        ),
+        Mutation::new(
+            "weight_sum",
+            AirKind::Main,
+            1,
+            offset_of!(MainCols<u8>, weight_sum),
+            inc,
+        ),
+        // Chain
+        Mutation::new(
+            "is_x_i",
+            AirKind::Chain(0),
+            0,
+            offset_of!(ChainCols<u8>, is_x_i),
+            flip,
+        ),
+        Mutation::new(
+            "is_x_i",
+            AirKind::Chain(0),
+            1,
+            offset_of!(ChainCols<u8>, is_x_i),
+            flip,
+        ),
+        Mutation::new(
+            "chain_step_bits[0]",
+            AirKind::Chain(0),
+            0,
+            offset_of!(ChainCols<u8>, chain_step_bits),
+            flip,
+        ),
+        Mutation::new(
+            "chain_step_bits[1]",
+            AirKind::Chain(0),
+            1,
+            offset_of!(ChainCols<u8>, chain_step_bits) + 1,
+            flip,
+        ),
+        Mutation::new(
+            "sig_step",
+            AirKind::Chain(0),
+            1,
+            offset_of!(ChainCols<u8>, sig_step.step),
+            inc,
+        ),
+        Mutation::new(
+            "sig_idx",
+            AirKind::Chain(0),
+            0,
+            offset_of!(ChainCols<u8>, sig_idx),
+            inc,
+        ),
+        Mutation::new(
+            "chain_idx",
+            AirKind::Chain(0),
+            1,
+            offset_of!(ChainCols<u8>, chain_idx.value),
+            inc,
+        ),
+        // Message hash
+        Mutation::new(
+            "sig_idx",
+            AirKind::MsgHash,
+            1,
+            offset_of!(MsgHashCols<u8>, sig_idx),
+            inc,
+        ),
+        // Merkle leaf
+        Mutation::new(
+            "sig_idx",
+            AirKind::MerkleLeaf,
+            0,
+            offset_of!(MerkleLeafCols<u8>, sig_idx),
+            inc,
+        ),
+        Mutation::new(
+            "leaf_chunk_idx",
+            AirKind::MerkleLeaf,
+            1,
+            offset_of!(MerkleLeafCols<u8>, leaf_chunk_idx),
+            inc,
+        ),
+        // Merkle path
+        Mutation::new(
+            "is_right",
+            AirKind::MerklePath,
+            0,
+            offset_of!(MerklePathCols<u8>, is_right),
+            flip,
+        ),
+        Mutation::new(
+            "is_right",
+            AirKind::MerklePath,
+            1,
+            offset_of!(MerklePathCols<u8>, is_right),
+            flip,
+        ),
+        Mutation::new(
+            "epoch_dec",
+            AirKind::MerklePath,
+            1,
+            offset_of!(MerklePathCols<u8>, epoch_dec),
+            inc,
+        ),
+        Mutation::new(
+            "epoch_limbs[0]",
+            AirKind::MerklePath,
+            0,
+            offset_of!(MerklePathCols<u8>, epoch_limbs),
+            inc,
+        ),
+        Mutation::new(
+            "level",
+            AirKind::MerklePath,
+            1,
+            offset_of!(MerklePathCols<u8>, level.step),
+            inc,
+        ),
+        Mutation::new(
+            "sig_idx",
+            AirKind::MerklePath,
+            0,
+            offset_of!(MerklePathCols<u8>, sig_idx),
+            inc,
+        ),
+        // Decomposition
+        Mutation::new(
+            "carries[0]",
+            AirKind::Decomposition,
+            0,
+            offset_of!(DecompositionCols<u8>, carries),
+            inc,
+        ),
+        Mutation::new(
+            "carries[0]",
+            AirKind::Decomposition,
+            1,
+            offset_of!(DecompositionCols<u8>, carries),
+            dec,
+        ),
+        Mutation::new(
+            "acc_limbs[0]",
+            AirKind::Decomposition,
+            1,
+            offset_of!(DecompositionCols<u8>, acc_limbs),
+            inc,
+        ),
+        Mutation::new(
+            "decomposition_bits[0]",
+            AirKind::Decomposition,
+            decomposition_row,
+            offset_of!(DecompositionCols<u8>, decomposition_bits),
+            flip,
+        ),
+        Mutation::new(
+            "sum",
+            AirKind::Decomposition,
+            decomposition_row,
+            offset_of!(DecompositionCols<u8>, sum),
+            inc,
+        ),
+        // Range check
+        Mutation::new(
+            "mult",
+            AirKind::RangeCheck,
+            0,
+            offset_of!(RangeCheckCols<u8>, mult),
+            inc,
+        ),
+        Mutation::new(
+            "mult",
+            AirKind::RangeCheck,
+            1,
+            offset_of!(RangeCheckCols<u8>, mult),
+            dec,
+        ),
+        Mutation::new(
+            "bits",
+            AirKind::RangeCheck,
+            2,
+            offset_of!(RangeCheckCols<u8>, bits),
+            inc,
+        ),
+        Mutation::new(
+            "bound",
+            AirKind::RangeCheck,
+            2,
+            offset_of!(RangeCheckCols<u8>, bound),
+            inc,
+        ),
+        // Unique signer
+        Mutation::new(
+            "padding claims participation",
+            AirKind::UniqueSigner,
+            PARTICIPATION.len() - 1,
+            offset_of!(UniqueSignerCols<u8>, is_real),
+            flip,
+        ),
+        Mutation::new(
+            "key ms_limb",
+            AirKind::UniqueSigner,
+            0,
+            offset_of!(UniqueSignerCols<u8>, key) + offset_of!(KeyElementCols<u8>, ms_limb),
+            inc,
+        ),
+        Mutation::new(
+            "first_diff",
+            AirKind::UniqueSigner,
+            0,
+            offset_of!(UniqueSignerCols<u8>, first_diff),
+            flip,
+        ),
+        Mutation::new(
+            "diff",
+            AirKind::UniqueSigner,
+            1,
+            offset_of!(UniqueSignerCols<u8>, diff),
+            inc,
+        ),
+        // Message encoding
+        Mutation::new(
+            "encoded_msg",
+            AirKind::MsgEncoding,
+            0,
+            offset_of!(MsgEncodingCols<u8>, encoded_msg),
+            inc,
+        ),
+        Mutation::new(
+            "ms_limb",
+            AirKind::MsgEncoding,
+            0,
+            offset_of!(MsgEncodingCols<u8>, ms_limb),
+            inc,
+        ),
+        Mutation::new(
+            "acc_limbs[0]",
+            AirKind::MsgEncoding,
+            1,
+            offset_of!(MsgEncodingCols<u8>, acc_limbs),
+            inc,
+        ),
+        Mutation::new(
+            "carries[0]",
+            AirKind::MsgEncoding,
+            1,
+            offset_of!(MsgEncodingCols<u8>, carries),
+            inc,
+        ),
+        Mutation::new(
+            "mult",
+            AirKind::MsgEncoding,
+            MSG_FE_LEN - 1,
+            offset_of!(MsgEncodingCols<u8>, mult),
+            dec,
+        ),
+        Mutation::new(
+            "padding restarts accumulation",
+            AirKind::MsgEncoding,
+            MSG_FE_LEN,
+            offset_of!(MsgEncodingCols<u8>, inds),
+            flip,
+        ),
+        // Participation
+        Mutation::new(
+            "participation bit",
+            AirKind::Participation,
+            0,
+            offset_of!(ParticipationCols<u8>, bits) + 2,
+            flip,
+        ),
+        Mutation::new(
+            "word_idx",
+            AirKind::Participation,
+            0,
+            offset_of!(ParticipationCols<u8>, word_idx),
+            inc,
+        ),
+    ]
+}
+
+/// Returns mutations on [`MerkleTreeCols`] of [`MerkleLayout::Fused`], where
+/// rows of each signature are Merkle leaf, Merkle path and then message hash.
+fn merkle_tree_mutations() -> Vec<Mutation> {
+    let path_row = SPONGE_PERM;
+    let msg_row = SPONGE_PERM + LOG_LIFETIME;
+    vec![
+        Mutation::new(
+            "sig_idx",
+            AirKind::MerkleTree,
+            1,
+            offset_of!(MerkleTreeCols<u8>, sig_idx),
+            inc,
+        ),
+        Mutation::new(
+            "is_merkle_leaf",
+            AirKind::MerkleTree,
+            0,
+            offset_of!(MerkleTreeCols<u8>, is_merkle_leaf),
+            flip,
+        ),
+        Mutation::new(
+            "is_msg",
+            AirKind::MerkleTree,
+            msg_row,
+            offset_of!(MerkleTreeCols<u8>, is_msg),
+            flip,
+        ),
+        Mutation::new(
+            "leaf_chunk_idx",
+            AirKind::MerkleTree,
+            0,
+            offset_of!(MerkleTreeCols<u8>, leaf_chunk_idx),
+            inc,
+        ),
+        Mutation::new(
+            "is_right",
+            AirKind::MerkleTree,
+            path_row,
+            offset_of!(MerkleTreeCols<u8>, is_right),
+            flip,
+        ),
+        Mutation::new(
+            "epoch_dec",
+            AirKind::MerkleTree,
+            path_row,
+            offset_of!(MerkleTreeCols<u8>, epoch_dec),
+            inc,
+        ),
+        Mutation::new(
+            "epoch_limbs[0]",
+            AirKind::MerkleTree,
+            path_row,
+            offset_of!(MerkleTreeCols<u8>, epoch_limbs),
+            inc,
+        ),
+        Mutation::new(
+            "level",
+            AirKind::MerkleTree,
+            path_row + 1,
+            offset_of!(MerkleTreeCols<u8>, level.step),
+            inc,
+        ),
+    ]
+}
+
+/// Returns mutations on the second compression in the first row of
+/// [`ChainCols`] with 2 compressions per row.
+fn chain_compression_mutations() -> Vec<Mutation> {
+    vec![
+        Mutation::new(
+            "sig_idx",
+            AirKind::Chain(0),
+            0,
+            NUM_CHAIN_COLS + offset_of!(ChainCols<u8>, sig_idx),
+            inc,
+        ),
+        Mutation::new(
+            "sig_step",
+            AirKind::Chain(0),
+            0,
+            NUM_CHAIN_COLS + offset_of!(ChainCols<u8>, sig_step.step),
+            inc,
+        ),
+        Mutation::new(
+            "chain_step_bits[0]",
+            AirKind::Chain(0),
+            0,
+            NUM_CHAIN_COLS + offset_of!(ChainCols<u8>, chain_step_bits),
+            flip,
+        ),
+        Mutation::new(
+            "is_x_i",
+            AirKind::Chain(0),
+            0,
+            NUM_CHAIN_COLS + offset_of!(ChainCols<u8>, is_x_i),
+            flip,
+        ),
+    ]
+}
+
+/// Returns mutations on the first row of each of 2 [`ChainCols`] instances,
+/// whose first signature is bound to public values.
+fn chain_instance_mutations() -> Vec<Mutation> {
+    (0..2)
+        .flat_map(|idx| {
+            [
+                Mutation::new(
+                    "sig_idx",
+                    AirKind::Chain(idx),
+                    0,
+                    offset_of!(ChainCols<u8>, sig_idx),
+                    inc,
+                ),
+                Mutation::new(
+                    "is_x_i",
+                    AirKind::Chain(idx),
+                    0,
+                    offset_of!(ChainCols<u8>, is_x_i),
+                    flip,
+                ),
+            ]
+        })
+        .collect()
+}
+
+/// Returns mutations on public keys and weights looked up from the registry
+/// in [`SignerSetLayout::Registry`].
+fn registry_mutations() -> Vec<Mutation> {
+    vec![
+        Mutation::new(
+            "weight",
+            AirKind::Main,
+            0,
+            offset_of!(MainCols<u8>, weight),
+            inc,
+        ),
+        Mutation::new(
+            "non-participating weight",
+            AirKind::Main,
+            2,
+            offset_of!(MainCols<u8>, weight),
+            dec,
+        ),
+        Mutation::new(
+            "merkle_root",
+            AirKind::Main,
+            0,
+            offset_of!(MainCols<u8>, merkle_root),
+            inc,
+        ),
+        Mutation::new(
+            "mult",
+            AirKind::Registry,
+            0,
+            offset_of!(RegistryMultCols<u8>, mult),
+            inc,
+        ),
+        Mutation::new(
+            "mult",
+            AirKind::Registry,
+            2,
+            offset_of!(RegistryMultCols<u8>, mult),
+            dec,
+        ),
+    ]
+}
+
+/// Proves honest traces with `layout` and each of `mutations` applied, and
+/// returns the mutations that aren't rejected.
+fn survivors(layout: Layout, mutations: Vec<Mutation>) -> Vec<Mutation> {
+    let engine = Engine::<F, E>::fastest();
+    let vi = mock_vi(PARTICIPATION.len());
+    let (airs, air_proof_inputs) = match layout.signer_set {
+        SignerSetLayout::Sponge => generate_air_proof_inputs_in_chunks(
+            engine.log_blowup(),
+            vi,
+            &PARTICIPATION,
+            DEFAULT_CHUNK_SIZE,
+            layout,
+        ),
+        SignerSetLayout::Registry => {
+            let public_keys = vi.pairs.iter().map(|(pk, _)| *pk).collect();
+            let registry = Registry::<Config<F, E>>::commit(
+                engine.config().pcs(),
+                public_keys,
+                WEIGHTS.to_vec(),
+            );
+            generate_air_proof_inputs_with_registry(
+                engine.log_blowup(),
+                vi,
+                &PARTICIPATION,
+                DEFAULT_CHUNK_SIZE,
+                layout,
+                &registry,
+                participating_weight(&PARTICIPATION, &WEIGHTS),
+            )
+        }
+    };
+
+    disable_debug_builder();
+    assert!(engine
+        .run_test_impl(airs.clone(), air_proof_inputs.clone())
+        .is_ok());
+
+    mutations
+        .into_iter()
+        .filter(|mutation| {
+            let mut air_proof_inputs = air_proof_inputs.clone();
+            let trace = air_proof_inputs[air_idx(layout, mutation.air).unwrap()]
+                .raw
+                .common_main
+                .as_mut()
+                .unwrap();
+            let idx = mutation.row * trace.width() + mutation.col;
+            trace.values[idx] = (mutation.perturb)(trace.values[idx]);
+            engine.run_test_impl(airs.clone(), air_proof_inputs).is_ok()
+        })
+        .collect()
+}
+
+#[test]
+fn mutation() {
+    let survivors = survivors(Layout::default(), mutations());
+    assert!(survivors.is_empty(), "Mutations survived: {survivors:#?}");
+}
+
+#[test]
+fn mutation_fused() {
+    let layout = Layout {
+        merkle: MerkleLayout::Fused,
+        ..Layout::default()
+    };
+    let survivors = survivors(layout, merkle_tree_mutations());
+    assert!(survivors.is_empty(), "Mutations survived: {survivors:#?}");
+}
+
+#[test]
+fn mutation_chain_compressions_per_row() {
+    let layout = Layout {
+        chain_compressions_per_row: NonZeroUsize::new(2).unwrap(),
+        ..Layout::default()
+    };
+    let survivors = survivors(layout, chain_compression_mutations());
+    assert!(survivors.is_empty(), "Mutations survived: {survivors:#?}");
+}
+
+#[test]
+fn mutation_chain_instances() {
+    let layout = Layout {
+        chain_instances: NonZeroUsize::new(2).unwrap(),
+        ..Layout::default()
+    };
+    let survivors = survivors(layout, chain_instance_mutations());
+    assert!(survivors.is_empty(), "Mutations survived: {survivors:#?}");
+}
+
+#[test]
+fn mutation_registry() {
+    let layout = Layout {
+        signer_set: SignerSetLayout::Registry,
+        ..Layout::default()
+    };
+    let survivors = survivors(layout, registry_mutations());
+    assert!(survivors.is_empty(), "Mutations survived: {survivors:#?}");
+}
@@ -0,0 +1,158 @@
+use_instance!({
+    chip::{
+        participation::{
+            column::{ParticipationCols, NUM_PARTICIPATION_COLS},
+            MAX_PARTICIPATION_WORDS, PARTICIPATION_INDEX_BASE, PARTICIPATION_WORD_BITS,
+        },
+        Bus,
+    },
+    F,
+});
+use crate::util::zip;
+use core::borrow::Borrow;
+use itertools::Itertools;
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    rap::{BaseAirWithPublicValues, PartitionedBaseAir},
+};
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_field::FieldAlgebra;
+use p3_matrix::Matrix;
+
+/// Exposes the participation bitmap as public values, one word of
+/// `PARTICIPATION_WORD_BITS` bits per row.
+///
+/// Each row selects its word from public values by the one-hot digits of
+/// `word_idx` in base `PARTICIPATION_INDEX_BASE`, so the width doesn't depend
+/// on number of words.
+#[derive(Clone, Copy, Debug)]
+pub struct ParticipationAir {
+    num_words: usize,
+}
+
+impl ParticipationAir {
+    pub const fn new(num_words: usize) -> Self {
+        assert!(
+            num_words > 0 && num_words <= MAX_PARTICIPATION_WORDS,
+            "Number of participation words should be in 1..=MAX_PARTICIPATION_WORDS"
+        );
+        Self { num_words }
+    }
+}
+
+impl BaseAir<F> for ParticipationAir {
+    fn width(&self) -> usize {
+        NUM_PARTICIPATION_COLS
+    }
+}
+
+impl PartitionedBaseAir<F> for ParticipationAir {}
+
+impl BaseAirWithPublicValues<F> for ParticipationAir {
+    fn num_public_values(&self) -> usize {
+        self.num_words
+    }
+}
+
+impl<AB> Air<AB> for ParticipationAir
+where
+    AB: InteractionBuilder<F = F> + AirBuilderWithPublicValues,
+{
+    fn eval(&self, builder: &mut AB) {
+        let bitmap: Vec<AB::Expr> = builder
+            .public_values()
+            .iter()
+            .copied()
+            .map_into()
+            .collect_vec();
+
+        let main = builder.main();
+
+        let local = main.row_slice(0);
+        let next = main.row_slice(1);
+        let local: &ParticipationCols<AB::Var> = (*local).borrow();
+        let next: &ParticipationCols<AB::Var> = (*next).borrow();
+
+        // When every row
+        eval_every_row(builder, &bitmap, local);
+
+        // When first row
+        {
+            let mut builder = builder.when_first_row();
+
+            builder.assert_zero(local.word_idx);
+            builder.assert_one(local.lo_inds[0]);
+            builder.assert_one(local.hi_inds[0]);
+        }
+
+        // When transition
+        {
+            let mut builder = builder.when_transition();
+
+            builder.assert_eq(next.word_idx, local.word_idx + AB::Expr::ONE);
+            local.lo_inds.eval_transition(&mut builder, &next.lo_inds);
+            builder.assert_eq(
+                next.hi_inds.active_idx::<AB>(),
+                local.hi_inds.active_idx::<AB>() + local.lo_inds[PARTICIPATION_INDEX_BASE - 1],
+            );
+        }
+
+        // When last row
+        {
+            let mut builder = builder.when_last_row();
+
+            // Every word in public values is selected by a row.
+            let last_word_idx = self.num_words - 1;
+            builder.assert_one(local.lo_inds[last_word_idx % PARTICIPATION_INDEX_BASE]);
+            builder.assert_one(local.hi_inds[last_word_idx / PARTICIPATION_INDEX_BASE]);
+        }
+
+        // Interaction
+        send_participation(builder, local);
+    }
+}
+
+#[inline]
+fn eval_every_row<AB>(builder: &mut AB, bitmap: &[AB::Expr], cols: &ParticipationCols<AB::Var>)
+where
+    AB: AirBuilder<F = F>,
+{
+    cols.lo_inds.eval_every_row(builder);
+    cols.hi_inds.eval_every_row(builder);
+    builder.assert_one(cols.lo_inds.is_active::<AB>());
+    builder.assert_one(cols.hi_inds.is_active::<AB>());
+    cols.bits.map(|bit| builder.assert_bool(bit));
+    let word = zip!(bitmap.chunks(PARTICIPATION_INDEX_BASE), cols.hi_inds.iter())
+        .map(|(words, hi_ind)| {
+            zip!(words, cols.lo_inds.iter())
+                .map(|(word, lo_ind)| word.clone() * *lo_ind)
+                .sum::<AB::Expr>()
+                * *hi_ind
+        })
+        .sum::<AB::Expr>();
+    builder.assert_eq(
+        word,
+        cols.bits
+            .into_iter()
+            .rfold(AB::Expr::ZERO, |acc, bit| acc.double() + bit),
+    );
+}
+
+/// Sends index of each participating signature, so non-participating ones
+/// don't need to be received by the main trace.
+#[inline]
+fn send_participation<AB>(builder: &mut AB, cols: &ParticipationCols<AB::Var>)
+where
+    AB: InteractionBuilder<F = F>,
+{
+    cols.bits.into_iter().enumerate().for_each(|(idx, bit)| {
+        builder.push_send(
+            Bus::Participation as usize,
+            [
+                cols.word_idx * F::from_canonical_usize(PARTICIPATION_WORD_BITS)
+                    + F::from_canonical_usize(idx),
+            ],
+            bit,
+        );
+    });
+}
@@ -0,0 +1,35 @@
+use_instance!(chip::participation::{PARTICIPATION_INDEX_BASE, PARTICIPATION_WORD_BITS});
+use crate::{gadget::cycle_bits::CycleBits, util::AlignBorrow};
+use core::borrow::{Borrow, BorrowMut};
+
+pub const NUM_PARTICIPATION_COLS: usize = size_of::<ParticipationCols<u8>>();
+
+#[repr(C)]
+pub struct ParticipationCols<T> {
+    /// Word index, which is also the row index.
+    pub word_idx: T,
+    /// One-hot vector of `word_idx % PARTICIPATION_INDEX_BASE`.
+    pub lo_inds: CycleBits<T, PARTICIPATION_INDEX_BASE>,
+    /// One-hot vector of `word_idx / PARTICIPATION_INDEX_BASE`.
+    pub hi_inds: CycleBits<T, PARTICIPATION_INDEX_BASE>,
+    /// Bits of current word in little-endian.
+    pub bits: [T; PARTICIPATION_WORD_BITS],
+}
+
+impl<T> AlignBorrow<T> for ParticipationCols<T> {
+    const SIZE: usize = NUM_PARTICIPATION_COLS;
+}
+
+impl<T> Borrow<ParticipationCols<T>> for [T] {
+    #[inline]
+    fn borrow(&self) -> &ParticipationCols<T> {
+        ParticipationCols::align_borrow(self)
+    }
+}
+
+impl<T> BorrowMut<ParticipationCols<T>> for [T] {
+    #[inline]
+    fn borrow_mut(&mut self) -> &mut ParticipationCols<T> {
+        ParticipationCols::align_borrow_mut(self)
+    }
+}
@@ -0,0 +1,106 @@
+use_instance!({
+    chip::participation::{
+        column::{ParticipationCols, NUM_PARTICIPATION_COLS},
+        num_words, participation_bitmap, trace_height, ParticipationAir, PARTICIPATION_INDEX_BASE,
+        PARTICIPATION_WORD_BITS,
+    },
+    F,
+});
+use crate::util::{
+    field::{MaybeUninitField, MaybeUninitFieldSlice},
+    par_zip,
+    trace::UninitTrace,
+};
+use core::{any::type_name, mem::MaybeUninit};
+use openvm_stark_backend::{
+    config::{Domain, StarkGenericConfig},
+    prover::types::{AirProofInput, AirProofRawInput},
+    rap::AnyRap,
+    Chip, ChipUsageGetter,
+};
+use p3_commit::PolynomialSpace;
+use p3_field::{FieldAlgebra, PrimeField32};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_maybe_rayon::prelude::*;
+use std::sync::Arc;
+
+#[derive(Clone, Copy, Debug)]
+pub struct ParticipationChip<'a> {
+    extra_capacity_bits: usize,
+    participation: &'a [bool],
+}
+
+impl<'a> ParticipationChip<'a> {
+    pub const fn new(extra_capacity_bits: usize, participation: &'a [bool]) -> Self {
+        Self {
+            extra_capacity_bits,
+            participation,
+        }
+    }
+}
+
+impl ChipUsageGetter for ParticipationChip<'_> {
+    fn air_name(&self) -> String {
+        type_name::<ParticipationAir>().to_string()
+    }
+
+    fn current_trace_height(&self) -> usize {
+        trace_height(self.participation)
+    }
+
+    fn trace_width(&self) -> usize {
+        NUM_PARTICIPATION_COLS
+    }
+}
+
+impl<SC: StarkGenericConfig> Chip<SC> for ParticipationChip<'_>
+where
+    Domain<SC>: PolynomialSpace<Val = F>,
+{
+    fn air(&self) -> Arc<dyn AnyRap<SC>> {
+        Arc::new(ParticipationAir::new(num_words(self.participation.len())))
+    }
+
+    fn generate_air_proof_input(self) -> AirProofInput<SC> {
+        AirProofInput {
+            cached_mains_pdata: Vec::new(),
+            raw: AirProofRawInput {
+                cached_mains: Vec::new(),
+                common_main: Some(generate_trace_rows(
+                    self.extra_capacity_bits,
+                    self.participation,
+                )),
+                public_values: participation_bitmap(self.participation),
+            },
+        }
+    }
+}
+
+pub fn generate_trace_rows(
+    extra_capacity_bits: usize,
+    participation: &[bool],
+) -> RowMajorMatrix<F> {
+    let bitmap = participation_bitmap(participation);
+    let mut trace = UninitTrace::new(
+        NUM_PARTICIPATION_COLS,
+        trace_height(participation),
+        extra_capacity_bits,
+    );
+    let rows = unsafe { trace.rows_mut::<ParticipationCols<MaybeUninit<F>>>() };
+
+    par_zip!(rows, bitmap)
+        .enumerate()
+        .for_each(|(word_idx, (row, word))| {
+            let word = word.as_canonical_u32();
+            row.word_idx.write_usize(word_idx);
+            row.lo_inds
+                .populate(Some(word_idx % PARTICIPATION_INDEX_BASE));
+            row.hi_inds
+                .populate(Some(word_idx / PARTICIPATION_INDEX_BASE));
+            row.bits.fill_from_iter(
+                (0..PARTICIPATION_WORD_BITS).map(|i| F::from_bool((word >> i) & 1 == 1)),
+            );
+        });
+
+    unsafe { trace.assume_init() }
+}
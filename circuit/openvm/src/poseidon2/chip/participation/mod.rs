@@ -0,0 +1,71 @@
+use_instance!(F);
+use p3_field::{FieldAlgebra, PrimeField32};
+
+pub use air::ParticipationAir;
+#[cfg(feature = "prover")]
+pub use generation::ParticipationChip;
+
+/// Number of participation bits packed in a single public value.
+pub const PARTICIPATION_WORD_BITS: usize = 16;
+
+/// Base of the two one-hot digits of word index, which select the word of
+/// each row from public values.
+pub const PARTICIPATION_INDEX_BASE: usize = 64;
+
+/// Maximum number of participation words, indexable by two digits.
+pub const MAX_PARTICIPATION_WORDS: usize = PARTICIPATION_INDEX_BASE * PARTICIPATION_INDEX_BASE;
+
+/// Returns number of participation words for `num_sigs` signatures, which is
+/// also the height of participation trace.
+///
+/// Bits of padding signatures up to `num_words(num_sigs) *
+/// PARTICIPATION_WORD_BITS` are zero.
+pub const fn num_words(num_sigs: usize) -> usize {
+    let num_bits = num_sigs.next_power_of_two();
+    if num_bits < PARTICIPATION_WORD_BITS {
+        1
+    } else {
+        num_bits / PARTICIPATION_WORD_BITS
+    }
+}
+
+pub const fn trace_height(participation: &[bool]) -> usize {
+    num_words(participation.len())
+}
+
+/// Returns participation bitmap in words of `PARTICIPATION_WORD_BITS` bits,
+/// where bit `i % PARTICIPATION_WORD_BITS` of word `i / PARTICIPATION_WORD_BITS`
+/// indicates whether signature `i` is participating or not.
+pub fn participation_bitmap(participation: &[bool]) -> Vec<F> {
+    let mut bitmap = vec![F::ZERO; num_words(participation.len())];
+    participation
+        .chunks(PARTICIPATION_WORD_BITS)
+        .zip(&mut bitmap)
+        .for_each(|(bits, word)| {
+            *word = F::from_canonical_u32(
+                bits.iter()
+                    .rfold(0, |acc, bit| (acc << 1) | u32::from(*bit)),
+            );
+        });
+    bitmap
+}
+
+/// Returns participation of `num_sigs` signatures from `bitmap`, or `None` if
+/// `bitmap` is not a valid encoding of it.
+pub fn participation_from_bitmap(bitmap: &[F], num_sigs: usize) -> Option<Vec<bool>> {
+    if bitmap.len() != num_words(num_sigs) {
+        return None;
+    }
+    let participation = (0..num_sigs)
+        .map(|i| {
+            let word = bitmap[i / PARTICIPATION_WORD_BITS].as_canonical_u32();
+            (word >> (i % PARTICIPATION_WORD_BITS)) & 1 == 1
+        })
+        .collect::<Vec<_>>();
+    (participation_bitmap(&participation) == bitmap).then_some(participation)
+}
+
+mod air;
+mod column;
+#[cfg(feature = "prover")]
+mod generation;
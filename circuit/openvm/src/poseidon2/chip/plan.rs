@@ -0,0 +1,143 @@
+//! Planning of AIR layouts to reduce area of traces padded to power of two.
+
+use_instance!(chip::{trace_heights, widths, Layout, MerkleLayout});
+use core::{iter::zip, num::NonZeroUsize};
+use itertools::iproduct;
+
+/// Candidates of [`Layout::chain_compressions_per_row`] considered by [`plan`].
+pub const CHAIN_COMPRESSIONS_PER_ROW: [NonZeroUsize; 2] =
+    [NonZeroUsize::MIN, NonZeroUsize::new(2).unwrap()];
+
+/// Candidates of [`Layout::chain_instances`] considered by [`plan`].
+pub const CHAIN_INSTANCES: [NonZeroUsize; 2] = [NonZeroUsize::MIN, NonZeroUsize::new(2).unwrap()];
+
+/// Layout of AIRs with area of their traces in cells.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Plan {
+    pub layout: Layout,
+    /// Cells of all traces including padding rows.
+    pub area: usize,
+    /// Cells of padding rows only.
+    pub padding_area: usize,
+}
+
+impl Plan {
+    /// Returns plan of aggregating `num_sigs` signatures of which
+    /// `num_participating` are participating with AIRs in `layout`.
+    pub fn new(num_sigs: usize, num_participating: usize, layout: Layout) -> Self {
+        let (area, padding_area) = zip(
+            widths(layout),
+            trace_heights(num_sigs, num_participating, layout),
+        )
+        .fold((0, 0), |(area, padding_area), (width, height)| {
+            (
+                area + width * height.height,
+                padding_area + width * height.padding_rows(),
+            )
+        });
+        Self {
+            layout,
+            area,
+            padding_area,
+        }
+    }
+}
+
+/// Returns the plan with the least area of aggregating `num_sigs` signatures
+/// of which `num_participating` are participating.
+///
+/// Each trace is padded to power of two independently, so whether the fused
+/// Merkle tree trace, rounded up once, wastes less than the split ones, each
+/// rounded up separately, depends on the number of participating signatures.
+/// So does whether the chain trace, the tallest one, is better split into two
+/// instances, the first one filled up to power of two and the second one
+/// padding only the rest, or laid out with different compressions per row.
+/// Ties are broken in favor of [`Layout::default`].
+pub fn plan(num_sigs: usize, num_participating: usize) -> Plan {
+    iproduct!(
+        [MerkleLayout::Split, MerkleLayout::Fused],
+        CHAIN_COMPRESSIONS_PER_ROW,
+        CHAIN_INSTANCES
+    )
+    .map(
+        |(merkle, chain_compressions_per_row, chain_instances)| Layout {
+            merkle,
+            chain_compressions_per_row,
+            chain_instances,
+            ..Layout::default()
+        },
+    )
+    .map(|layout| Plan::new(num_sigs, num_participating, layout))
+    .min_by_key(|plan| plan.area)
+    .unwrap()
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod test {
+    use_instance!({
+        chip::{
+            generate_air_proof_inputs_in_chunks,
+            plan::{plan, Plan, CHAIN_COMPRESSIONS_PER_ROW, CHAIN_INSTANCES},
+            Layout, MerkleLayout, DEFAULT_CHUNK_SIZE,
+        },
+        hash_sig::test::mock_vi,
+        E, F,
+    });
+    use crate::util::engine::{Config, Engine};
+    use itertools::iproduct;
+    use openvm_stark_sdk::engine::StarkEngine;
+    use p3_matrix::Matrix;
+
+    fn layouts() -> impl Iterator<Item = Layout> {
+        iproduct!(
+            [MerkleLayout::Fused, MerkleLayout::Split],
+            CHAIN_COMPRESSIONS_PER_ROW,
+            CHAIN_INSTANCES
+        )
+        .map(
+            |(merkle, chain_compressions_per_row, chain_instances)| Layout {
+                merkle,
+                chain_compressions_per_row,
+                chain_instances,
+                ..Layout::default()
+            },
+        )
+    }
+
+    #[test]
+    fn area() {
+        let engine = Engine::<F, E>::fastest();
+        for num_sigs in [5, 8, 9] {
+            for layout in layouts() {
+                let vi = mock_vi(num_sigs);
+                let (_, air_proof_inputs) = generate_air_proof_inputs_in_chunks::<Config<F, E>>(
+                    engine.log_blowup(),
+                    vi,
+                    &vec![true; num_sigs],
+                    DEFAULT_CHUNK_SIZE,
+                    layout,
+                );
+                let area = air_proof_inputs
+                    .iter()
+                    .map(|input| {
+                        let trace = input.raw.common_main.as_ref().unwrap();
+                        trace.width() * trace.height()
+                    })
+                    .sum::<usize>();
+                let plan = Plan::new(num_sigs, num_sigs, layout);
+                assert_eq!(plan.area, area);
+                assert!(plan.padding_area < plan.area);
+            }
+        }
+    }
+
+    #[test]
+    fn least_area() {
+        for num_sigs in 1..1 << 10 {
+            let chosen = plan(num_sigs, num_sigs);
+            for layout in layouts() {
+                assert!(chosen.area <= Plan::new(num_sigs, num_sigs, layout).area);
+            }
+        }
+    }
+}
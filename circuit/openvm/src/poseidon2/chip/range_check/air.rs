@@ -1,10 +1,11 @@
-use crate::poseidon2::{
+use_instance!({
     chip::{
         range_check::column::{RangeCheckCols, NUM_RANGE_CHECK_COLS},
         Bus,
     },
     F,
-};
+});
+use crate::gadget::not;
 use core::borrow::Borrow;
 use openvm_stark_backend::{
     interaction::InteractionBuilder,
@@ -14,7 +15,13 @@ use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
 use p3_field::FieldAlgebra;
 use p3_matrix::Matrix;
 
-#[derive(Clone, Copy, Debug)]
+/// Table of `(value, bits)` for every `bits` from `0` and `value` in
+/// `0..1 << bits` in order.
+///
+/// Every row is a valid range check however many rows there are, so the
+/// height isn't constrained, and the table is sized by the widest range check
+/// recorded by [`RangeChecker`](super::RangeChecker) instead.
+#[derive(Clone, Copy, Debug, Default)]
 pub struct RangeCheckAir;
 
 impl BaseAir<F> for RangeCheckAir {
@@ -39,11 +46,18 @@ where
         let local: &RangeCheckCols<AB::Var> = (*local).borrow();
         let next: &RangeCheckCols<AB::Var> = (*next).borrow();
 
+        // When every row
+        local
+            .is_last_value
+            .eval(builder, local.value + AB::Expr::ONE, local.bound);
+
         // When first row
         {
             let mut builder = builder.when_first_row();
 
             builder.assert_zero(local.value);
+            builder.assert_zero(local.bits);
+            builder.assert_one(local.bound);
         }
 
         // When transition
@@ -66,7 +80,13 @@ fn eval_range_check_transition<AB>(
 ) where
     AB: AirBuilder<F = F>,
 {
-    builder.assert_eq(next.value, local.value + AB::Expr::ONE);
+    let is_last_value: AB::Expr = local.is_last_value.output.into();
+    builder.assert_eq(
+        next.value,
+        (local.value + AB::Expr::ONE) * not(is_last_value.clone()),
+    );
+    builder.assert_eq(next.bits, local.bits + is_last_value.clone());
+    builder.assert_eq(next.bound, local.bound * (AB::Expr::ONE + is_last_value));
 }
 
 #[inline]
@@ -74,5 +94,27 @@ fn receive_range_check<AB>(builder: &mut AB, cols: &RangeCheckCols<AB::Var>)
 where
     AB: InteractionBuilder<F = F>,
 {
-    builder.push_receive(Bus::RangeCheck as usize, [cols.value], cols.mult);
+    builder.push_receive(Bus::RangeCheck as usize, [cols.value, cols.bits], cols.mult);
+}
+
+/// Sends `value` to be checked in range `0..1 << bits` by [`RangeCheckAir`]
+/// for `count` times.
+///
+/// The `bits` should be at most
+/// [`RANGE_CHECK_BITS`](super::super::RANGE_CHECK_BITS), which bounds the
+/// widest table [`RangeChecker`](super::RangeChecker) could record.
+#[inline]
+pub fn send_range_check<AB>(
+    builder: &mut AB,
+    value: impl Into<AB::Expr>,
+    bits: usize,
+    count: impl Into<AB::Expr>,
+) where
+    AB: InteractionBuilder<F = F>,
+{
+    builder.push_send(
+        Bus::RangeCheck as usize,
+        [value.into(), AB::Expr::from_canonical_usize(bits)],
+        count,
+    );
 }
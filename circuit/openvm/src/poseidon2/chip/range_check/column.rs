@@ -1,11 +1,19 @@
-use crate::util::AlignBorrow;
+use crate::{gadget::is_equal::IsEqualCols, util::AlignBorrow};
 use core::borrow::{Borrow, BorrowMut};
 
 pub const NUM_RANGE_CHECK_COLS: usize = size_of::<RangeCheckCols<u8>>();
 
 #[repr(C)]
 pub struct RangeCheckCols<T> {
+    /// Value in range `0..bound`.
     pub value: T,
+    /// Width of range in bits.
+    pub bits: T,
+    /// Upper bound of range, which is `1 << bits`.
+    pub bound: T,
+    /// Whether `value + 1 == bound`, so it's the last value of the range.
+    pub is_last_value: IsEqualCols<T>,
+    /// Multiplicity of `(value, bits)` being looked up.
     pub mult: T,
 }
 
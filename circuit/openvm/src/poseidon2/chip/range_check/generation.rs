@@ -1,45 +1,173 @@
-use crate::{
-    poseidon2::{
-        chip::{
-            decomposition::LIMB_BITS,
-            range_check::column::{RangeCheckCols, NUM_RANGE_CHECK_COLS},
-        },
-        F,
+use_instance!({
+    chip::range_check::{
+        column::{RangeCheckCols, NUM_RANGE_CHECK_COLS},
+        trace_height, RangeCheckAir,
     },
-    util::{field::MaybeUninitField, par_zip},
+    F,
+});
+use crate::util::{field::MaybeUninitField, par_zip, trace::UninitTrace};
+use core::{any::type_name, iter::repeat_with, mem::MaybeUninit};
+use openvm_stark_backend::{
+    config::{Domain, StarkGenericConfig},
+    prover::types::{AirProofInput, AirProofRawInput},
+    rap::AnyRap,
+    Chip, ChipUsageGetter,
 };
-use core::mem::MaybeUninit;
-use p3_matrix::dense::{RowMajorMatrix, RowMajorMatrixViewMut};
+use p3_commit::PolynomialSpace;
+use p3_field::FieldAlgebra;
+use p3_matrix::dense::RowMajorMatrix;
 use p3_maybe_rayon::prelude::*;
+use std::sync::{
+    atomic::{AtomicU32, Ordering},
+    Arc,
+};
+
+/// Range check service shared by chips sending to
+/// [`Bus::RangeCheck`](super::super::Bus::RangeCheck), which accumulates
+/// multiplicities of [`RangeCheckAir`] from concurrent trace generation, and
+/// sizes the table by the widest recorded range check.
+#[derive(Debug)]
+pub struct RangeChecker {
+    max_bits: usize,
+    pub(super) mult: Vec<AtomicU32>,
+}
+
+impl RangeChecker {
+    /// Returns range checker of widths up to `max_bits` with zero
+    /// multiplicities, where `max_bits` only bounds the capacity.
+    pub fn new(max_bits: usize) -> Self {
+        Self {
+            max_bits,
+            mult: repeat_with(|| AtomicU32::new(0))
+                .take(trace_height(max_bits))
+                .collect(),
+        }
+    }
+
+    pub const fn max_bits(&self) -> usize {
+        self.max_bits
+    }
+
+    /// Returns bits of the smallest table covering all recorded range checks.
+    pub fn bits(&self) -> usize {
+        self.mult
+            .iter()
+            .rposition(|mult| mult.load(Ordering::Relaxed) != 0)
+            .map_or(0, |idx| (idx + 1).ilog2() as usize)
+    }
+
+    /// Records a range check of `value` in `0..1 << bits`, which should be
+    /// paired with a [`send_range_check`](super::send_range_check) in the AIR.
+    #[inline]
+    pub fn add_count(&self, value: u32, bits: usize) {
+        self.add_counts(value, bits, 1);
+    }
+
+    /// Same as [`RangeChecker::add_count`], but records `count` range checks
+    /// of `value` at once.
+    #[inline]
+    pub fn add_counts(&self, value: u32, bits: usize, count: u32) {
+        debug_assert!(bits <= self.max_bits);
+        debug_assert!(u64::from(value) < 1 << bits);
+        self.mult[row_idx(value, bits)].fetch_add(count, Ordering::Relaxed);
+    }
+}
+
+#[derive(Debug)]
+pub struct RangeCheckChip {
+    extra_capacity_bits: usize,
+    range_checker: RangeChecker,
+}
+
+impl RangeCheckChip {
+    pub const fn new(extra_capacity_bits: usize, range_checker: RangeChecker) -> Self {
+        Self {
+            extra_capacity_bits,
+            range_checker,
+        }
+    }
+}
+
+impl ChipUsageGetter for RangeCheckChip {
+    fn air_name(&self) -> String {
+        type_name::<RangeCheckAir>().to_string()
+    }
+
+    fn current_trace_height(&self) -> usize {
+        trace_height(self.range_checker.bits())
+    }
 
-pub const fn trace_height() -> usize {
-    1 << LIMB_BITS
+    fn trace_width(&self) -> usize {
+        NUM_RANGE_CHECK_COLS
+    }
 }
 
-pub fn generate_trace_rows(extra_capacity_bits: usize, mult: Vec<u32>) -> RowMajorMatrix<F> {
-    let height = 1 << LIMB_BITS;
-    let size = height * NUM_RANGE_CHECK_COLS;
-    let mut vec = Vec::with_capacity(size << extra_capacity_bits);
-    let trace = &mut vec.spare_capacity_mut()[..size];
-    let trace = RowMajorMatrixViewMut::new(trace, NUM_RANGE_CHECK_COLS);
+impl<SC: StarkGenericConfig> Chip<SC> for RangeCheckChip
+where
+    Domain<SC>: PolynomialSpace<Val = F>,
+{
+    fn air(&self) -> Arc<dyn AnyRap<SC>> {
+        Arc::new(RangeCheckAir)
+    }
 
-    let (prefix, rows, suffix) = unsafe {
-        trace
-            .values
-            .align_to_mut::<RangeCheckCols<MaybeUninit<F>>>()
-    };
-    assert!(prefix.is_empty(), "Alignment should match");
-    assert!(suffix.is_empty(), "Alignment should match");
-    assert_eq!(rows.len(), height);
+    fn generate_air_proof_input(self) -> AirProofInput<SC> {
+        let bits = self.range_checker.bits();
+        AirProofInput {
+            cached_mains_pdata: Vec::new(),
+            raw: AirProofRawInput {
+                cached_mains: Vec::new(),
+                common_main: Some(generate_trace_rows(
+                    self.extra_capacity_bits,
+                    bits,
+                    self.range_checker.mult,
+                )),
+                public_values: Vec::new(),
+            },
+        }
+    }
+}
+
+/// Returns index of the row of `(value, bits)`.
+#[inline]
+pub const fn row_idx(value: u32, bits: usize) -> usize {
+    (1 << bits) - 1 + value as usize
+}
+
+/// Generates table of `bits` with multiplicities `mult`, which could be
+/// recorded for a wider table as long as the rest are zero.
+pub fn generate_trace_rows(
+    extra_capacity_bits: usize,
+    bits: usize,
+    mut mult: Vec<AtomicU32>,
+) -> RowMajorMatrix<F> {
+    debug_assert!(mult[trace_height(bits)..]
+        .iter()
+        .all(|mult| mult.load(Ordering::Relaxed) == 0));
+    mult.truncate(trace_height(bits));
+    let mut trace = UninitTrace::new(
+        NUM_RANGE_CHECK_COLS,
+        trace_height(bits),
+        extra_capacity_bits,
+    );
+    let rows = unsafe { trace.rows_mut::<RangeCheckCols<MaybeUninit<F>>>() };
 
     par_zip!(rows, mult)
         .enumerate()
-        .for_each(|(idx, (row, mult))| {
-            row.value.write_usize(idx);
-            row.mult.write_u32(mult);
-        });
+        .for_each(|(idx, (row, mult))| generate_trace_row(row, idx, mult.into_inner()));
 
-    unsafe { vec.set_len(size) };
+    unsafe { trace.assume_init() }
+}
 
-    RowMajorMatrix::new(vec, NUM_RANGE_CHECK_COLS)
+#[inline]
+fn generate_trace_row(row: &mut RangeCheckCols<MaybeUninit<F>>, idx: usize, mult: u32) {
+    let bits = (idx + 1).ilog2() as usize;
+    let value = idx + 1 - (1 << bits);
+    row.value.write_usize(value);
+    row.bits.write_usize(bits);
+    row.bound.write_usize(1 << bits);
+    row.is_last_value.populate(
+        F::from_canonical_usize(value + 1),
+        F::from_canonical_usize(1 << bits),
+    );
+    row.mult.write_u32(mult);
 }
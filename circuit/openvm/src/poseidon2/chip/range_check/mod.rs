@@ -1,70 +1,77 @@
-use crate::poseidon2::{
-    chip::range_check::{
-        air::RangeCheckAir,
-        column::NUM_RANGE_CHECK_COLS,
-        generation::{generate_trace_rows, trace_height},
-    },
-    F,
-};
-use core::any::type_name;
-use openvm_stark_backend::{
-    config::{Domain, StarkGenericConfig},
-    prover::types::{AirProofInput, AirProofRawInput},
-    rap::AnyRap,
-    Chip, ChipUsageGetter,
-};
-use p3_commit::PolynomialSpace;
-use std::sync::Arc;
-
 mod air;
-mod column;
-mod generation;
+pub(super) mod column;
+#[cfg(feature = "prover")]
+pub(super) mod generation;
 
-#[derive(Clone, Debug)]
-pub struct RangeCheckChip {
-    extra_capacity_bits: usize,
-    mult: Vec<u32>,
-}
+pub use air::{send_range_check, RangeCheckAir};
+#[cfg(feature = "prover")]
+pub use generation::{RangeCheckChip, RangeChecker};
 
-impl RangeCheckChip {
-    pub const fn new(extra_capacity_bits: usize, mult: Vec<u32>) -> Self {
-        Self {
-            extra_capacity_bits,
-            mult,
+/// Returns bits of the smallest table covering range checks of all `widths`.
+pub const fn table_bits(widths: &[usize]) -> usize {
+    let mut bits = 0;
+    let mut idx = 0;
+    while idx < widths.len() {
+        if widths[idx] > bits {
+            bits = widths[idx];
         }
+        idx += 1;
     }
+    bits
 }
 
-impl ChipUsageGetter for RangeCheckChip {
-    fn air_name(&self) -> String {
-        type_name::<RangeCheckAir>().to_string()
-    }
-
-    fn current_trace_height(&self) -> usize {
-        trace_height()
-    }
-
-    fn trace_width(&self) -> usize {
-        NUM_RANGE_CHECK_COLS
-    }
+pub const fn trace_height(bits: usize) -> usize {
+    1 << (bits + 1)
 }
 
-impl<SC: StarkGenericConfig> Chip<SC> for RangeCheckChip
-where
-    Domain<SC>: PolynomialSpace<Val = F>,
-{
-    fn air(&self) -> Arc<dyn AnyRap<SC>> {
-        Arc::new(RangeCheckAir)
+#[cfg(all(test, feature = "prover"))]
+mod test {
+    use_instance!({
+        chip::range_check::{
+            column::{RangeCheckCols, NUM_RANGE_CHECK_COLS},
+            generation::generate_trace_rows,
+            RangeChecker,
+        },
+        F,
+    });
+    use core::borrow::Borrow;
+    use p3_field::FieldAlgebra;
+    use p3_matrix::Matrix;
+
+    #[test]
+    fn table() {
+        let max_bits = 4;
+        let range_checker = RangeChecker::new(max_bits);
+        let expected = (0..=max_bits)
+            .flat_map(|bits| (0..1 << bits).map(move |value| (value, bits)))
+            .inspect(|(value, bits)| range_checker.add_count(*value, *bits))
+            .map(|(value, bits)| (value, bits, 1))
+            .chain([(0, max_bits + 1, 0)])
+            .collect::<Vec<_>>();
+        assert_eq!(range_checker.bits(), max_bits);
+        let trace = generate_trace_rows(0, max_bits, range_checker.mult);
+        assert_eq!(trace.height(), expected.len());
+        trace
+            .values
+            .chunks_exact(NUM_RANGE_CHECK_COLS)
+            .zip(expected)
+            .for_each(|(row, (value, bits, mult))| {
+                let row: &RangeCheckCols<F> = row.borrow();
+                assert_eq!(row.value, F::from_canonical_u32(value));
+                assert_eq!(row.bits, F::from_canonical_usize(bits));
+                assert_eq!(row.bound, F::from_canonical_u32(1 << bits));
+                assert_eq!(row.mult, F::from_canonical_u32(mult));
+            });
     }
 
-    fn generate_air_proof_input(self) -> AirProofInput<SC> {
-        AirProofInput {
-            cached_mains_pdata: Vec::new(),
-            raw: AirProofRawInput {
-                cached_mains: Vec::new(),
-                common_main: Some(generate_trace_rows(self.extra_capacity_bits, self.mult)),
-                public_values: Vec::new(),
-            },
-        }
+    #[test]
+    fn table_bits_from_counts() {
+        let range_checker = RangeChecker::new(4);
+        assert_eq!(range_checker.bits(), 0);
+        range_checker.add_count(3, 2);
+        range_checker.add_count(1, 1);
+        assert_eq!(range_checker.bits(), 2);
+        let trace = generate_trace_rows(0, range_checker.bits(), range_checker.mult);
+        assert_eq!(trace.height(), 8);
     }
 }
@@ -0,0 +1,94 @@
+use_instance!({
+    chip::{
+        registry::column::{
+            RegistryCols, RegistryMultCols, NUM_REGISTRY_COLS, NUM_REGISTRY_MULT_COLS,
+        },
+        Bus,
+    },
+    F,
+});
+use core::{borrow::Borrow, iter};
+use openvm_stark_backend::{
+    air_builders::PartitionedAirBuilder,
+    interaction::InteractionBuilder,
+    rap::{BaseAirWithPublicValues, PartitionedBaseAir},
+};
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_field::FieldAlgebra;
+use p3_matrix::Matrix;
+
+/// Provides public key and weight of every row of main trace from the
+/// registry, which is a cached trace of them indexed by signature index, so its
+/// commitment is computed once and reused by every proof instead of being
+/// exposed as public values.
+#[derive(Clone, Copy, Debug)]
+pub struct RegistryAir;
+
+impl BaseAir<F> for RegistryAir {
+    fn width(&self) -> usize {
+        NUM_REGISTRY_COLS + NUM_REGISTRY_MULT_COLS
+    }
+}
+
+impl PartitionedBaseAir<F> for RegistryAir {
+    fn cached_main_widths(&self) -> Vec<usize> {
+        vec![NUM_REGISTRY_COLS]
+    }
+
+    fn common_main_width(&self) -> usize {
+        NUM_REGISTRY_MULT_COLS
+    }
+}
+
+impl BaseAirWithPublicValues<F> for RegistryAir {}
+
+impl<AB> Air<AB> for RegistryAir
+where
+    AB: InteractionBuilder<F = F> + PartitionedAirBuilder + AirBuilderWithPublicValues,
+{
+    fn eval(&self, builder: &mut AB) {
+        let registry = &builder.cached_mains()[0];
+        let (local, next) = (
+            registry.row_slice(0).to_vec(),
+            registry.row_slice(1).to_vec(),
+        );
+        let mult = builder.common_main().row_slice(0).to_vec();
+        let local: &RegistryCols<AB::Var> = local.as_slice().borrow();
+        let next: &RegistryCols<AB::Var> = next.as_slice().borrow();
+        let mult: &RegistryMultCols<AB::Var> = mult.as_slice().borrow();
+
+        // When first row
+        {
+            let mut builder = builder.when_first_row();
+
+            builder.assert_zero(local.sig_idx);
+        }
+
+        // When transition
+        {
+            let mut builder = builder.when_transition();
+
+            builder.assert_eq(next.sig_idx, local.sig_idx + AB::Expr::ONE);
+        }
+
+        // Interaction
+        receive_signer(builder, local, mult);
+    }
+}
+
+#[inline]
+fn receive_signer<AB>(
+    builder: &mut AB,
+    cols: &RegistryCols<AB::Var>,
+    mult: &RegistryMultCols<AB::Var>,
+) where
+    AB: InteractionBuilder<F = F>,
+{
+    builder.push_receive(
+        Bus::SignerSet as usize,
+        iter::once(cols.sig_idx)
+            .chain(cols.public_key())
+            .chain([cols.weight]),
+        mult.mult,
+    );
+}
@@ -0,0 +1,69 @@
+use_instance!(hash_sig::{HASH_FE_LEN, PARAM_FE_LEN});
+use crate::util::AlignBorrow;
+use core::borrow::{Borrow, BorrowMut};
+
+pub const NUM_REGISTRY_COLS: usize = size_of::<RegistryCols<u8>>();
+
+pub const NUM_REGISTRY_MULT_COLS: usize = size_of::<RegistryMultCols<u8>>();
+
+/// Columns of the cached trace, committed once for the whole registry.
+#[repr(C)]
+pub struct RegistryCols<T> {
+    /// Signature index in the whole batch.
+    pub sig_idx: T,
+    /// Public key registered at `sig_idx`.
+    pub parameter: [T; PARAM_FE_LEN],
+    pub merkle_root: [T; HASH_FE_LEN],
+    /// Weight of signer registered at `sig_idx`, or zero for padding.
+    pub weight: T,
+}
+
+impl<T: Copy> RegistryCols<T> {
+    #[inline]
+    pub fn public_key(&self) -> impl Iterator<Item = T> {
+        self.parameter.into_iter().chain(self.merkle_root)
+    }
+}
+
+/// Columns of the common main trace, generated for each proof.
+#[repr(C)]
+pub struct RegistryMultCols<T> {
+    /// Number of lookups of the public key in the same row of cached trace.
+    pub mult: T,
+}
+
+impl<T> AlignBorrow<T> for RegistryCols<T> {
+    const SIZE: usize = NUM_REGISTRY_COLS;
+}
+
+impl<T> Borrow<RegistryCols<T>> for [T] {
+    #[inline]
+    fn borrow(&self) -> &RegistryCols<T> {
+        RegistryCols::align_borrow(self)
+    }
+}
+
+impl<T> BorrowMut<RegistryCols<T>> for [T] {
+    #[inline]
+    fn borrow_mut(&mut self) -> &mut RegistryCols<T> {
+        RegistryCols::align_borrow_mut(self)
+    }
+}
+
+impl<T> AlignBorrow<T> for RegistryMultCols<T> {
+    const SIZE: usize = NUM_REGISTRY_MULT_COLS;
+}
+
+impl<T> Borrow<RegistryMultCols<T>> for [T] {
+    #[inline]
+    fn borrow(&self) -> &RegistryMultCols<T> {
+        RegistryMultCols::align_borrow(self)
+    }
+}
+
+impl<T> BorrowMut<RegistryMultCols<T>> for [T] {
+    #[inline]
+    fn borrow_mut(&mut self) -> &mut RegistryMultCols<T> {
+        RegistryMultCols::align_borrow_mut(self)
+    }
+}
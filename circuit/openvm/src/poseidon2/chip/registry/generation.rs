@@ -0,0 +1,179 @@
+use_instance!({
+    chip::{
+        main::TOTAL_WEIGHT_BITS,
+        registry::{
+            column::{RegistryCols, RegistryMultCols, NUM_REGISTRY_COLS, NUM_REGISTRY_MULT_COLS},
+            is_valid_weights, trace_height, RegistryAir,
+        },
+    },
+    hash_sig::PublicKey,
+    F,
+});
+use crate::util::{
+    field::{MaybeUninitField, MaybeUninitFieldSlice},
+    trace::UninitTrace,
+};
+use core::{any::type_name, mem::MaybeUninit};
+use openvm_stark_backend::{
+    config::{Com, Domain, StarkGenericConfig},
+    prover::{
+        trace::{ProverTraceData, TraceCommitter},
+        types::{AirProofInput, AirProofRawInput},
+    },
+    rap::AnyRap,
+    Chip, ChipUsageGetter,
+};
+use p3_commit::PolynomialSpace;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_maybe_rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Public keys and weights of a signer set committed as cached trace of
+/// [`RegistryAir`], so it's committed once when the signer set changes and the
+/// commitment is reused by every proof.
+#[derive(Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "ProverTraceData<SC>: Serialize",
+    deserialize = "ProverTraceData<SC>: Deserialize<'de>"
+))]
+pub struct Registry<SC: StarkGenericConfig> {
+    public_keys: Vec<PublicKey>,
+    weights: Vec<u32>,
+    trace: Arc<RowMajorMatrix<F>>,
+    data: ProverTraceData<SC>,
+}
+
+impl<SC: StarkGenericConfig> Registry<SC>
+where
+    Domain<SC>: PolynomialSpace<Val = F>,
+{
+    /// Commits `public_keys` and `weights` of all signatures in order with
+    /// `pcs`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `weights` differ from `public_keys` in length, or aren't
+    /// [valid](super::is_valid_weights).
+    pub fn commit(pcs: &SC::Pcs, public_keys: Vec<PublicKey>, weights: Vec<u32>) -> Self {
+        assert_eq!(public_keys.len(), weights.len());
+        assert!(
+            is_valid_weights(&weights),
+            "Total weight should be less than 2^{TOTAL_WEIGHT_BITS}"
+        );
+        let trace = generate_cached_trace_rows(&public_keys, &weights);
+        let data = TraceCommitter::<SC>::new(pcs).commit(vec![trace.clone()]);
+        Self {
+            public_keys,
+            weights,
+            trace: Arc::new(trace),
+            data,
+        }
+    }
+
+    pub fn public_keys(&self) -> &[PublicKey] {
+        &self.public_keys
+    }
+
+    pub fn weights(&self) -> &[u32] {
+        &self.weights
+    }
+
+    pub fn total_weight(&self) -> u32 {
+        self.weights.iter().sum()
+    }
+
+    /// Returns the commitment of registry, which is the commitment of the
+    /// cached trace in proofs.
+    pub const fn commitment(&self) -> &Com<SC> {
+        &self.data.commit
+    }
+}
+
+pub struct RegistryChip<'a, SC: StarkGenericConfig> {
+    extra_capacity_bits: usize,
+    registry: &'a Registry<SC>,
+}
+
+impl<'a, SC: StarkGenericConfig> RegistryChip<'a, SC> {
+    pub const fn new(extra_capacity_bits: usize, registry: &'a Registry<SC>) -> Self {
+        Self {
+            extra_capacity_bits,
+            registry,
+        }
+    }
+}
+
+impl<SC: StarkGenericConfig> ChipUsageGetter for RegistryChip<'_, SC> {
+    fn air_name(&self) -> String {
+        type_name::<RegistryAir>().to_string()
+    }
+
+    fn current_trace_height(&self) -> usize {
+        trace_height(self.registry.public_keys.len())
+    }
+
+    fn trace_width(&self) -> usize {
+        NUM_REGISTRY_COLS + NUM_REGISTRY_MULT_COLS
+    }
+}
+
+impl<SC: StarkGenericConfig> Chip<SC> for RegistryChip<'_, SC>
+where
+    Domain<SC>: PolynomialSpace<Val = F>,
+{
+    fn air(&self) -> Arc<dyn AnyRap<SC>> {
+        Arc::new(RegistryAir)
+    }
+
+    fn generate_air_proof_input(self) -> AirProofInput<SC> {
+        AirProofInput {
+            cached_mains_pdata: vec![self.registry.data.clone()],
+            raw: AirProofRawInput {
+                cached_mains: vec![self.registry.trace.clone()],
+                common_main: Some(generate_trace_rows(
+                    self.extra_capacity_bits,
+                    self.registry.public_keys.len(),
+                )),
+                public_values: Vec::new(),
+            },
+        }
+    }
+}
+
+/// Returns cached trace of `public_keys` and `weights` in order, padded with
+/// zero public keys and weights to height of main trace.
+pub fn generate_cached_trace_rows(public_keys: &[PublicKey], weights: &[u32]) -> RowMajorMatrix<F> {
+    let mut trace = UninitTrace::new(NUM_REGISTRY_COLS, trace_height(public_keys.len()), 0);
+    let rows = unsafe { trace.rows_mut::<RegistryCols<MaybeUninit<F>>>() };
+
+    rows.par_iter_mut().enumerate().for_each(|(sig_idx, row)| {
+        row.sig_idx.write_usize(sig_idx);
+        if let Some(pk) = public_keys.get(sig_idx) {
+            row.parameter.fill_from_slice(&pk.parameter);
+            row.merkle_root.fill_from_slice(&pk.merkle_root);
+            row.weight.write_u32(weights[sig_idx]);
+        } else {
+            row.parameter.fill_zero();
+            row.merkle_root.fill_zero();
+            row.weight.write_zero();
+        }
+    });
+
+    unsafe { trace.assume_init() }
+}
+
+/// Returns common main trace of `num_sigs` signatures, where every public key
+/// is looked up once by its row of main trace.
+pub fn generate_trace_rows(extra_capacity_bits: usize, num_sigs: usize) -> RowMajorMatrix<F> {
+    let mut trace = UninitTrace::new(
+        NUM_REGISTRY_MULT_COLS,
+        trace_height(num_sigs),
+        extra_capacity_bits,
+    );
+    let rows = unsafe { trace.rows_mut::<RegistryMultCols<MaybeUninit<F>>>() };
+
+    rows.par_iter_mut().for_each(|row| row.mult.write_one());
+
+    unsafe { trace.assume_init() }
+}
@@ -0,0 +1,21 @@
+use_instance!(chip::main::TOTAL_WEIGHT_BITS);
+
+mod air;
+pub(super) mod column;
+#[cfg(feature = "prover")]
+pub(super) mod generation;
+
+pub use air::RegistryAir;
+#[cfg(feature = "prover")]
+pub use generation::{Registry, RegistryChip};
+
+/// Returns whether `weights` can be committed in a registry, which is when
+/// their sum is less than `1 << TOTAL_WEIGHT_BITS`.
+pub fn is_valid_weights(weights: &[u32]) -> bool {
+    weights.iter().map(|weight| u64::from(*weight)).sum::<u64>() < 1 << TOTAL_WEIGHT_BITS
+}
+
+/// Same as height of main trace, one row for each signature including padding.
+pub const fn trace_height(num_sigs: usize) -> usize {
+    num_sigs.next_power_of_two()
+}
@@ -0,0 +1,156 @@
+use_instance!({
+    chip::{
+        signer_set::{
+            column::{SignerSetCols, NUM_SIGNER_SET_COLS},
+            poseidon2::{PARTIAL_ROUNDS, WIDTH},
+            PUBLIC_KEY_FE_LEN,
+        },
+        Bus,
+    },
+    hash_sig::{HASH_FE_LEN, SPONGE_CAPACITY_VALUES, SPONGE_RATE},
+    Poseidon2LinearLayers, F, HALF_FULL_ROUNDS, RC24, SBOX_DEGREE, SBOX_REGISTERS,
+});
+use crate::util::zip;
+use core::{borrow::Borrow, iter};
+use openvm_stark_backend::{
+    air_builders::sub::SubAirBuilder,
+    interaction::InteractionBuilder,
+    rap::{BaseAirWithPublicValues, PartitionedBaseAir},
+};
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_field::FieldAlgebra;
+use p3_matrix::Matrix;
+use p3_poseidon2_air::{num_cols, Poseidon2Air};
+use p3_poseidon2_util::air::outputs;
+
+/// Alias for `Poseidon2Air` specialized with signer-set-related parameters.
+type Poseidon2AirSignerSet = Poseidon2Air<
+    F,
+    Poseidon2LinearLayers<WIDTH>,
+    WIDTH,
+    SBOX_DEGREE,
+    SBOX_REGISTERS,
+    HALF_FULL_ROUNDS,
+    PARTIAL_ROUNDS,
+>;
+
+/// Absorbs public key of every row of main trace into a sponge, one public key
+/// per row, and exposes the squeezed digest as public values.
+///
+/// Every signer weighs `1` and padding weighs `0`, so participating weight is
+/// at most the number of participating signatures.
+#[derive(Debug)]
+pub struct SignerSetAir(Poseidon2AirSignerSet);
+
+impl Default for SignerSetAir {
+    fn default() -> Self {
+        Self(Poseidon2Air::new(RC24.into()))
+    }
+}
+
+impl BaseAir<F> for SignerSetAir {
+    fn width(&self) -> usize {
+        NUM_SIGNER_SET_COLS
+    }
+}
+
+impl PartitionedBaseAir<F> for SignerSetAir {}
+
+impl BaseAirWithPublicValues<F> for SignerSetAir {
+    fn num_public_values(&self) -> usize {
+        HASH_FE_LEN
+    }
+}
+
+impl<AB> Air<AB> for SignerSetAir
+where
+    AB: InteractionBuilder<F = F> + AirBuilderWithPublicValues,
+    AB::Expr: FieldAlgebra<F = F>,
+{
+    fn eval(&self, builder: &mut AB) {
+        self.0
+            .eval(&mut SubAirBuilder::<_, Poseidon2AirSignerSet, _>::new(
+                builder,
+                0
+                    ..num_cols::<
+                        WIDTH,
+                        SBOX_DEGREE,
+                        SBOX_REGISTERS,
+                        HALF_FULL_ROUNDS,
+                        PARTIAL_ROUNDS,
+                    >(),
+            ));
+
+        let main = builder.main();
+
+        let commitment = builder.public_values().to_vec();
+
+        let local = main.row_slice(0);
+        let next = main.row_slice(1);
+        let local: &SignerSetCols<AB::Var> = (*local).borrow();
+        let next: &SignerSetCols<AB::Var> = (*next).borrow();
+
+        // When every rows
+        builder.assert_bool(local.is_real);
+
+        // When first row
+        {
+            let mut builder = builder.when_first_row();
+
+            builder.assert_zero(local.sig_idx);
+            zip!(
+                local.perm.inputs,
+                local
+                    .public_key()
+                    .map(Into::into)
+                    .chain(iter::repeat_n(
+                        AB::Expr::ZERO,
+                        SPONGE_RATE - PUBLIC_KEY_FE_LEN
+                    ))
+                    .chain(SPONGE_CAPACITY_VALUES.map(Into::into))
+            )
+            .for_each(|(a, b)| builder.assert_eq(a, b));
+        }
+
+        // When transition
+        {
+            let mut builder = builder.when_transition();
+
+            builder.assert_eq(next.sig_idx, local.sig_idx + AB::Expr::ONE);
+            zip!(
+                next.perm.inputs,
+                *outputs(&local.perm),
+                next.public_key()
+                    .map(Into::into)
+                    .chain(iter::repeat(AB::Expr::ZERO))
+                    .take(WIDTH)
+            )
+            .for_each(|(input, output, block)| builder.assert_eq(input, output + block));
+        }
+
+        // When last row
+        {
+            let mut builder = builder.when_last_row();
+
+            zip!(&outputs(&local.perm)[..HASH_FE_LEN], commitment)
+                .for_each(|(a, b)| builder.assert_eq(*a, b));
+        }
+
+        // Interaction
+        receive_signer(builder, local);
+    }
+}
+
+#[inline]
+fn receive_signer<AB>(builder: &mut AB, cols: &SignerSetCols<AB::Var>)
+where
+    AB: InteractionBuilder<F = F>,
+{
+    builder.push_receive(
+        Bus::SignerSet as usize,
+        iter::once(cols.sig_idx)
+            .chain(cols.public_key())
+            .chain([cols.is_real]),
+        AB::Expr::ONE,
+    );
+}
@@ -0,0 +1,49 @@
+use_instance!({
+    chip::signer_set::poseidon2::{PARTIAL_ROUNDS, WIDTH},
+    hash_sig::{HASH_FE_LEN, PARAM_FE_LEN},
+    HALF_FULL_ROUNDS, SBOX_DEGREE, SBOX_REGISTERS,
+});
+use crate::util::AlignBorrow;
+use core::borrow::{Borrow, BorrowMut};
+use p3_poseidon2_util::air::Poseidon2Cols;
+
+pub const NUM_SIGNER_SET_COLS: usize = size_of::<SignerSetCols<u8>>();
+
+#[repr(C)]
+pub struct SignerSetCols<T> {
+    pub perm:
+        Poseidon2Cols<T, WIDTH, SBOX_DEGREE, SBOX_REGISTERS, HALF_FULL_ROUNDS, PARTIAL_ROUNDS>,
+    /// Signature index in the whole batch.
+    pub sig_idx: T,
+    /// Whether this row is of a signer instead of padding, which is also the
+    /// weight of signer, so participating weight counts signatures.
+    pub is_real: T,
+    /// Public key absorbed in this row.
+    pub parameter: [T; PARAM_FE_LEN],
+    pub merkle_root: [T; HASH_FE_LEN],
+}
+
+impl<T: Copy> SignerSetCols<T> {
+    #[inline]
+    pub fn public_key(&self) -> impl Iterator<Item = T> {
+        self.parameter.into_iter().chain(self.merkle_root)
+    }
+}
+
+impl<T> AlignBorrow<T> for SignerSetCols<T> {
+    const SIZE: usize = NUM_SIGNER_SET_COLS;
+}
+
+impl<T> Borrow<SignerSetCols<T>> for [T] {
+    #[inline]
+    fn borrow(&self) -> &SignerSetCols<T> {
+        SignerSetCols::align_borrow(self)
+    }
+}
+
+impl<T> BorrowMut<SignerSetCols<T>> for [T] {
+    #[inline]
+    fn borrow_mut(&mut self) -> &mut SignerSetCols<T> {
+        SignerSetCols::align_borrow_mut(self)
+    }
+}
@@ -0,0 +1,115 @@
+use_instance!({
+    chip::signer_set::{
+        column::{SignerSetCols, NUM_SIGNER_SET_COLS},
+        initial_state,
+        poseidon2::{PARTIAL_ROUNDS, WIDTH},
+        public_key, signer_set_commitment, trace_height, SignerSetAir, PUBLIC_KEY_FE_LEN,
+    },
+    hash_sig::{PublicKey, PARAM_FE_LEN},
+    Poseidon2LinearLayers, F, HALF_FULL_ROUNDS, RC24, SBOX_DEGREE, SBOX_REGISTERS,
+});
+use crate::util::{
+    field::{MaybeUninitField, MaybeUninitFieldSlice},
+    trace::UninitTrace,
+    zip,
+};
+use core::{any::type_name, array::from_fn, mem::MaybeUninit};
+use openvm_stark_backend::{
+    config::{Domain, StarkGenericConfig},
+    prover::types::{AirProofInput, AirProofRawInput},
+    rap::AnyRap,
+    Chip, ChipUsageGetter,
+};
+use p3_commit::PolynomialSpace;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_poseidon2_util::air::{generate_trace_rows_for_perm, outputs};
+use std::sync::Arc;
+
+#[derive(Clone, Copy, Debug)]
+pub struct SignerSetChip<'a> {
+    extra_capacity_bits: usize,
+    public_keys: &'a [PublicKey],
+}
+
+impl<'a> SignerSetChip<'a> {
+    /// Returns a `SignerSetChip` with `public_keys` of all signatures in order,
+    /// participating or not.
+    pub const fn new(extra_capacity_bits: usize, public_keys: &'a [PublicKey]) -> Self {
+        Self {
+            extra_capacity_bits,
+            public_keys,
+        }
+    }
+}
+
+impl ChipUsageGetter for SignerSetChip<'_> {
+    fn air_name(&self) -> String {
+        type_name::<SignerSetAir>().to_string()
+    }
+
+    fn current_trace_height(&self) -> usize {
+        trace_height(self.public_keys)
+    }
+
+    fn trace_width(&self) -> usize {
+        NUM_SIGNER_SET_COLS
+    }
+}
+
+impl<SC: StarkGenericConfig> Chip<SC> for SignerSetChip<'_>
+where
+    Domain<SC>: PolynomialSpace<Val = F>,
+{
+    fn air(&self) -> Arc<dyn AnyRap<SC>> {
+        Arc::new(SignerSetAir::default())
+    }
+
+    fn generate_air_proof_input(self) -> AirProofInput<SC> {
+        AirProofInput {
+            cached_mains_pdata: Vec::new(),
+            raw: AirProofRawInput {
+                cached_mains: Vec::new(),
+                common_main: Some(generate_trace_rows(
+                    self.extra_capacity_bits,
+                    self.public_keys,
+                )),
+                public_values: signer_set_commitment(self.public_keys).to_vec(),
+            },
+        }
+    }
+}
+
+pub fn generate_trace_rows(
+    extra_capacity_bits: usize,
+    public_keys: &[PublicKey],
+) -> RowMajorMatrix<F> {
+    let mut trace = UninitTrace::new(
+        NUM_SIGNER_SET_COLS,
+        trace_height(public_keys),
+        extra_capacity_bits,
+    );
+    let rows = unsafe { trace.rows_mut::<SignerSetCols<MaybeUninit<F>>>() };
+
+    rows.iter_mut()
+        .enumerate()
+        .fold(initial_state(), |mut input, (sig_idx, row)| {
+            let public_key = public_key(public_keys, sig_idx);
+            zip!(&mut input[..PUBLIC_KEY_FE_LEN], public_key).for_each(|(s, v)| *s += v);
+            row.sig_idx.write_usize(sig_idx);
+            row.is_real.write_bool(sig_idx < public_keys.len());
+            row.parameter.fill_from_slice(&public_key[..PARAM_FE_LEN]);
+            row.merkle_root.fill_from_slice(&public_key[PARAM_FE_LEN..]);
+            generate_trace_rows_for_perm::<
+                F,
+                Poseidon2LinearLayers<WIDTH>,
+                WIDTH,
+                SBOX_DEGREE,
+                SBOX_REGISTERS,
+                HALF_FULL_ROUNDS,
+                PARTIAL_ROUNDS,
+            >(&mut row.perm, input, &RC24);
+            unsafe { from_fn(|i| outputs(&row.perm)[i].assume_init()) }
+        });
+
+    unsafe { trace.assume_init() }
+}
@@ -0,0 +1,65 @@
+use_instance!({
+    chip::signer_set::poseidon2::WIDTH,
+    hash_sig::{PublicKey, HASH_FE_LEN, PARAM_FE_LEN, SPONGE_CAPACITY_VALUES, SPONGE_RATE},
+    Poseidon2Parameter, F,
+});
+use crate::util::{concat_array, zip};
+use core::array::from_fn;
+use hash_sig_verifier::instantiation::poseidon2::Poseidon2Parameter as _;
+
+pub use air::SignerSetAir;
+#[cfg(feature = "prover")]
+pub use generation::SignerSetChip;
+
+/// Number of field elements of a public key.
+pub const PUBLIC_KEY_FE_LEN: usize = PARAM_FE_LEN + HASH_FE_LEN;
+
+mod air;
+mod column;
+#[cfg(feature = "prover")]
+mod generation;
+
+mod poseidon2 {
+    use_instance!(partial_round);
+
+    pub const WIDTH: usize = 24;
+    pub const PARTIAL_ROUNDS: usize = partial_round::<WIDTH>();
+}
+
+/// Same as height of main trace, one row for each signature including padding.
+pub const fn trace_height(public_keys: &[PublicKey]) -> usize {
+    public_keys.len().next_power_of_two()
+}
+
+/// Returns commitment of `public_keys`, which is the sponge digest of public
+/// keys in order, one public key per absorption and padded with zero public
+/// keys to height of main trace.
+pub fn signer_set_commitment(public_keys: &[PublicKey]) -> [F; HASH_FE_LEN] {
+    let state = (0..trace_height(public_keys)).fold(initial_state(), |mut state, sig_idx| {
+        zip!(
+            &mut state[..PUBLIC_KEY_FE_LEN],
+            public_key(public_keys, sig_idx)
+        )
+        .for_each(|(s, v)| *s += v);
+        Poseidon2Parameter::permutation_t24(state)
+    });
+    from_fn(|i| state[i])
+}
+
+#[inline]
+fn initial_state() -> [F; WIDTH] {
+    from_fn(|i| {
+        i.checked_sub(SPONGE_RATE)
+            .map(|i| SPONGE_CAPACITY_VALUES[i])
+            .unwrap_or_default()
+    })
+}
+
+/// Returns public key of `sig_idx` as field elements, or zeros for padding.
+#[inline]
+fn public_key(public_keys: &[PublicKey], sig_idx: usize) -> [F; PUBLIC_KEY_FE_LEN] {
+    public_keys
+        .get(sig_idx)
+        .map(|pk| concat_array![pk.parameter, pk.merkle_root])
+        .unwrap_or_default()
+}
@@ -0,0 +1,142 @@
+use_instance!({
+    chip::{
+        decomposition::{F_MS_LIMB, F_MS_LIMB_BITS, LIMB_BITS},
+        range_check::send_range_check,
+        unique_signer::column::{UniqueSignerCols, NUM_UNIQUE_SIGNER_COLS},
+        Bus,
+    },
+    F,
+});
+use crate::{gadget::not, util::zip};
+use core::borrow::Borrow;
+use itertools::Itertools;
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    rap::{BaseAirWithPublicValues, PartitionedBaseAir},
+};
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_field::FieldAlgebra;
+use p3_matrix::Matrix;
+
+/// Keys of participating signers sorted strictly ascending, which receives
+/// the key of every participating row of main trace once, so no signer can
+/// participate more than once.
+///
+/// Keys are compared by their canonical limbs, so a field element can't be
+/// represented twice to pass as different keys.
+#[derive(Clone, Copy, Debug)]
+pub struct UniqueSignerAir;
+
+impl BaseAir<F> for UniqueSignerAir {
+    fn width(&self) -> usize {
+        NUM_UNIQUE_SIGNER_COLS
+    }
+}
+
+impl PartitionedBaseAir<F> for UniqueSignerAir {}
+
+impl BaseAirWithPublicValues<F> for UniqueSignerAir {}
+
+impl<AB> Air<AB> for UniqueSignerAir
+where
+    AB: InteractionBuilder<F = F> + AirBuilderWithPublicValues,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+
+        let local = main.row_slice(0);
+        let next = main.row_slice(1);
+        let local: &UniqueSignerCols<AB::Var> = (*local).borrow();
+        let next: &UniqueSignerCols<AB::Var> = (*next).borrow();
+
+        // When every row
+        eval_every_row(builder, local);
+
+        // When transition
+        {
+            let mut builder = builder.when_transition();
+
+            eval_transition(&mut builder, local, next);
+        }
+
+        // When last row
+        builder.when_last_row().assert_zero(local.has_next::<AB>());
+
+        // Interaction
+        receive_unique_signer(builder, local);
+        send_range_checks(builder, local);
+    }
+}
+
+#[inline]
+fn eval_every_row<AB>(builder: &mut AB, cols: &UniqueSignerCols<AB::Var>)
+where
+    AB: AirBuilder<F = F>,
+{
+    builder.assert_bool(cols.is_real);
+    cols.first_diff.map(|bit| builder.assert_bool(bit));
+    cols.key.iter().for_each(|element| {
+        element.is_ms_limb_max.eval(
+            builder,
+            element.ms_limb,
+            AB::Expr::from_canonical_u32(F_MS_LIMB),
+        );
+        // When MSL == F_MS_LIMB, least significant limbs should be 0.
+        builder
+            .when(element.is_ms_limb_max.output)
+            .assert_zero(element.ls_limbs.into_iter().map_into().sum::<AB::Expr>());
+    });
+}
+
+#[inline]
+fn eval_transition<AB>(
+    builder: &mut AB,
+    local: &UniqueSignerCols<AB::Var>,
+    next: &UniqueSignerCols<AB::Var>,
+) where
+    AB: AirBuilder<F = F>,
+{
+    builder.assert_zero(next.is_real * not::<AB::Expr>(local.is_real.into()));
+    builder.assert_eq(local.has_next::<AB>(), next.is_real);
+
+    // Limbs before the first differing one should be equal.
+    let mut is_diff_seen = AB::Expr::ZERO;
+    let mut diff = AB::Expr::ZERO;
+    zip!(local.first_diff, local.limbs(), next.limbs()).for_each(
+        |(first_diff, local_limb, next_limb)| {
+            let first_diff: AB::Expr = first_diff.into();
+            is_diff_seen += first_diff.clone();
+            builder.assert_zero((next.is_real - is_diff_seen.clone()) * (next_limb - local_limb));
+            diff += first_diff * (next_limb - local_limb);
+        },
+    );
+    builder.assert_eq(local.diff, diff - next.is_real);
+}
+
+#[inline]
+fn receive_unique_signer<AB>(builder: &mut AB, cols: &UniqueSignerCols<AB::Var>)
+where
+    AB: InteractionBuilder<F = F>,
+{
+    builder.push_receive(Bus::UniqueSigner as usize, cols.key::<AB>(), cols.is_real);
+}
+
+#[inline]
+fn send_range_checks<AB>(builder: &mut AB, cols: &UniqueSignerCols<AB::Var>)
+where
+    AB: InteractionBuilder<F = F>,
+{
+    cols.key.iter().for_each(|element| {
+        element.ls_limbs.iter().for_each(|limb| {
+            send_range_check(builder, *limb, LIMB_BITS, cols.is_real);
+        });
+        send_range_check(builder, element.ms_limb, F_MS_LIMB_BITS, cols.is_real);
+        send_range_check(
+            builder,
+            AB::Expr::from_canonical_u32(F_MS_LIMB) - element.ms_limb,
+            F_MS_LIMB_BITS,
+            cols.is_real,
+        );
+    });
+    send_range_check(builder, cols.diff, LIMB_BITS, cols.has_next::<AB>());
+}
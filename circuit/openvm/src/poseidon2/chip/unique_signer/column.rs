@@ -0,0 +1,105 @@
+use_instance!(chip::{
+    decomposition::{LIMB_BITS, NUM_LIMBS},
+    unique_signer::{KEY_FE_LEN, NUM_KEY_LIMBS},
+});
+use crate::{gadget::is_equal::IsEqualCols, util::AlignBorrow};
+use core::{
+    array::from_fn,
+    borrow::{Borrow, BorrowMut},
+};
+use itertools::Itertools;
+use p3_air::AirBuilder;
+use p3_field::FieldAlgebra;
+
+pub const NUM_UNIQUE_SIGNER_COLS: usize = size_of::<UniqueSignerCols<u8>>();
+
+#[repr(C)]
+pub struct UniqueSignerCols<T> {
+    /// Whether this row is of a participating signer, all of which come
+    /// before padding rows.
+    pub is_real: T,
+    /// Canonical limbs of each element of signer key.
+    pub key: [KeyElementCols<T>; KEY_FE_LEN],
+    /// One-hot vector of the most significant limb differing from next row,
+    /// in the order of [`UniqueSignerCols::limbs`], or all zero if next row
+    /// is padding.
+    pub first_diff: [T; NUM_KEY_LIMBS],
+    /// Difference of the first differing limb minus one, which is range
+    /// checked so next key is strictly greater.
+    pub diff: T,
+}
+
+#[repr(C)]
+pub struct KeyElementCols<T> {
+    /// Least significant limbs in little-endian, each in `0..1 << LIMB_BITS`.
+    pub ls_limbs: [T; NUM_LIMBS - 1],
+    /// Most significant limb in `0..=F_MS_LIMB`.
+    pub ms_limb: T,
+    /// Whether `ms_limb == F_MS_LIMB`, in which case `ls_limbs` should be
+    /// zero for the decomposition to be canonical.
+    pub is_ms_limb_max: IsEqualCols<T>,
+}
+
+impl<T: Copy> UniqueSignerCols<T> {
+    /// Returns limbs of key from the most significant one.
+    #[inline]
+    pub fn limbs(&self) -> [T; NUM_KEY_LIMBS] {
+        from_fn(|idx| {
+            let element = &self.key[idx / NUM_LIMBS];
+            match idx % NUM_LIMBS {
+                0 => element.ms_limb,
+                limb_idx => element.ls_limbs[NUM_LIMBS - 1 - limb_idx],
+            }
+        })
+    }
+
+    #[inline]
+    pub fn key<AB: AirBuilder>(&self) -> [AB::Expr; KEY_FE_LEN]
+    where
+        T: Into<AB::Expr>,
+    {
+        self.key.each_ref().map(KeyElementCols::value::<AB>)
+    }
+
+    #[inline]
+    pub fn has_next<AB: AirBuilder>(&self) -> AB::Expr
+    where
+        T: Into<AB::Expr>,
+    {
+        self.first_diff.into_iter().map_into().sum()
+    }
+}
+
+impl<T: Copy> KeyElementCols<T> {
+    #[inline]
+    pub fn value<AB: AirBuilder>(&self) -> AB::Expr
+    where
+        T: Into<AB::Expr>,
+    {
+        self.ls_limbs
+            .into_iter()
+            .chain([self.ms_limb])
+            .map_into::<AB::Expr>()
+            .enumerate()
+            .map(|(idx, limb)| limb * AB::Expr::from_canonical_u32(1 << (idx * LIMB_BITS)))
+            .sum()
+    }
+}
+
+impl<T> AlignBorrow<T> for UniqueSignerCols<T> {
+    const SIZE: usize = NUM_UNIQUE_SIGNER_COLS;
+}
+
+impl<T> Borrow<UniqueSignerCols<T>> for [T] {
+    #[inline]
+    fn borrow(&self) -> &UniqueSignerCols<T> {
+        UniqueSignerCols::align_borrow(self)
+    }
+}
+
+impl<T> BorrowMut<UniqueSignerCols<T>> for [T] {
+    #[inline]
+    fn borrow_mut(&mut self) -> &mut UniqueSignerCols<T> {
+        UniqueSignerCols::align_borrow_mut(self)
+    }
+}
@@ -0,0 +1,187 @@
+use_instance!({
+    chip::{
+        decomposition::{F_MS_LIMB, F_MS_LIMB_BITS, LIMB_BITS, LIMB_MASK, NUM_LIMBS},
+        range_check::RangeChecker,
+        unique_signer::{
+            column::{UniqueSignerCols, NUM_UNIQUE_SIGNER_COLS},
+            trace_height, UniqueSignerAir, KEY_FE_LEN, NUM_KEY_LIMBS,
+        },
+    },
+    F,
+});
+use crate::util::{
+    field::{MaybeUninitField, MaybeUninitFieldSlice},
+    trace::UninitTrace,
+};
+use core::{any::type_name, array::from_fn, mem::MaybeUninit};
+use openvm_stark_backend::{
+    config::{Domain, StarkGenericConfig},
+    prover::types::{AirProofInput, AirProofRawInput},
+    rap::AnyRap,
+    Chip, ChipUsageGetter,
+};
+use p3_commit::PolynomialSpace;
+use p3_field::{FieldAlgebra, PrimeField32};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_maybe_rayon::prelude::*;
+use std::sync::Arc;
+
+#[derive(Clone, Copy, Debug)]
+pub struct UniqueSignerChip<'a> {
+    extra_capacity_bits: usize,
+    keys: &'a [[F; KEY_FE_LEN]],
+    range_checker: &'a RangeChecker,
+}
+
+impl<'a> UniqueSignerChip<'a> {
+    /// Returns a `UniqueSignerChip` with `keys` of participating signers in
+    /// any order.
+    pub const fn new(
+        extra_capacity_bits: usize,
+        keys: &'a [[F; KEY_FE_LEN]],
+        range_checker: &'a RangeChecker,
+    ) -> Self {
+        Self {
+            extra_capacity_bits,
+            keys,
+            range_checker,
+        }
+    }
+}
+
+impl ChipUsageGetter for UniqueSignerChip<'_> {
+    fn air_name(&self) -> String {
+        type_name::<UniqueSignerAir>().to_string()
+    }
+
+    fn current_trace_height(&self) -> usize {
+        trace_height(self.keys.len())
+    }
+
+    fn trace_width(&self) -> usize {
+        NUM_UNIQUE_SIGNER_COLS
+    }
+}
+
+impl<SC: StarkGenericConfig> Chip<SC> for UniqueSignerChip<'_>
+where
+    Domain<SC>: PolynomialSpace<Val = F>,
+{
+    fn air(&self) -> Arc<dyn AnyRap<SC>> {
+        Arc::new(UniqueSignerAir)
+    }
+
+    fn generate_air_proof_input(self) -> AirProofInput<SC> {
+        AirProofInput {
+            cached_mains_pdata: Vec::new(),
+            raw: AirProofRawInput {
+                cached_mains: Vec::new(),
+                common_main: Some(generate_trace_rows(
+                    self.extra_capacity_bits,
+                    self.keys,
+                    self.range_checker,
+                )),
+                public_values: Vec::new(),
+            },
+        }
+    }
+}
+
+/// Generates rows of `keys` of participating signers in ascending order, and
+/// records their range checks into `range_checker`.
+///
+/// Keys are assumed to be distinct, otherwise the witness is unprovable.
+pub fn generate_trace_rows(
+    extra_capacity_bits: usize,
+    keys: &[[F; KEY_FE_LEN]],
+    range_checker: &RangeChecker,
+) -> RowMajorMatrix<F> {
+    let mut trace = UninitTrace::new(
+        NUM_UNIQUE_SIGNER_COLS,
+        trace_height(keys.len()),
+        extra_capacity_bits,
+    );
+    let rows = unsafe { trace.rows_mut::<UniqueSignerCols<MaybeUninit<F>>>() };
+
+    let mut limbs = keys.par_iter().map(key_limbs).collect::<Vec<_>>();
+    limbs.sort_unstable();
+
+    let (rows, padding_rows) = rows.split_at_mut(limbs.len());
+    rows.par_iter_mut().enumerate().for_each(|(idx, row)| {
+        generate_trace_row(row, &limbs[idx], limbs.get(idx + 1), range_checker);
+    });
+    padding_rows
+        .par_iter_mut()
+        .for_each(generate_trace_row_padding);
+
+    unsafe { trace.assume_init() }
+}
+
+/// Returns canonical limbs of `key` from the most significant one, in the
+/// same order as [`UniqueSignerCols::limbs`].
+#[inline]
+fn key_limbs(key: &[F; KEY_FE_LEN]) -> [u32; NUM_KEY_LIMBS] {
+    from_fn(|idx| {
+        let value = key[idx / NUM_LIMBS].as_canonical_u32();
+        (value >> ((NUM_LIMBS - 1 - idx % NUM_LIMBS) * LIMB_BITS)) & LIMB_MASK
+    })
+}
+
+#[inline]
+fn generate_trace_row(
+    row: &mut UniqueSignerCols<MaybeUninit<F>>,
+    limbs: &[u32; NUM_KEY_LIMBS],
+    next: Option<&[u32; NUM_KEY_LIMBS]>,
+    range_checker: &RangeChecker,
+) {
+    row.is_real.write_one();
+    limbs
+        .chunks(NUM_LIMBS)
+        .zip(&mut row.key)
+        .for_each(|(limbs, element)| {
+            let (ms_limb, ls_limbs) = limbs.split_first().unwrap();
+            ls_limbs
+                .iter()
+                .for_each(|limb| range_checker.add_count(*limb, LIMB_BITS));
+            range_checker.add_count(*ms_limb, F_MS_LIMB_BITS);
+            range_checker.add_count(F_MS_LIMB - ms_limb, F_MS_LIMB_BITS);
+            element
+                .ls_limbs
+                .fill_from_iter(ls_limbs.iter().rev().copied().map(F::from_canonical_u32));
+            element.ms_limb.write_u32(*ms_limb);
+            element.is_ms_limb_max.populate(
+                F::from_canonical_u32(*ms_limb),
+                F::from_canonical_u32(F_MS_LIMB),
+            );
+        });
+    match next.and_then(|next| {
+        (0..NUM_KEY_LIMBS)
+            .find(|idx| limbs[*idx] != next[*idx])
+            .map(|idx| (idx, next[idx] - limbs[idx] - 1))
+    }) {
+        Some((first_diff, diff)) => {
+            range_checker.add_count(diff, LIMB_BITS);
+            row.first_diff
+                .fill_from_iter((0..NUM_KEY_LIMBS).map(|idx| F::from_bool(idx == first_diff)));
+            row.diff.write_u32(diff);
+        }
+        None => {
+            row.first_diff.fill_zero();
+            row.diff.write_zero();
+        }
+    }
+}
+
+#[inline]
+fn generate_trace_row_padding(row: &mut UniqueSignerCols<MaybeUninit<F>>) {
+    row.is_real.write_zero();
+    row.key.iter_mut().for_each(|element| {
+        element.ls_limbs.fill_zero();
+        element.ms_limb.write_zero();
+        element
+            .is_ms_limb_max
+            .populate(F::ZERO, F::from_canonical_u32(F_MS_LIMB));
+    });
+    row.first_diff.fill_zero();
+    row.diff.write_zero();
+}
@@ -0,0 +1,89 @@
+use_instance!({chip::decomposition::NUM_LIMBS, hash_sig::PublicKey, F});
+use core::iter::zip;
+use p3_field::PrimeField32;
+use std::collections::HashSet;
+
+mod air;
+pub(super) mod column;
+#[cfg(feature = "prover")]
+pub(super) mod generation;
+
+pub use air::UniqueSignerAir;
+#[cfg(feature = "prover")]
+pub use generation::UniqueSignerChip;
+
+/// Number of field elements of signer key, which is the prefix of Merkle
+/// root of public key.
+///
+/// Distinct public keys with the same key can't participate together, which
+/// happens with probability about `n^2 / p^KEY_FE_LEN` for `n` honest
+/// signers.
+pub const KEY_FE_LEN: usize = 2;
+
+pub const NUM_KEY_LIMBS: usize = KEY_FE_LEN * NUM_LIMBS;
+
+/// Returns key of signer of `pk`, which must be distinct among participating
+/// signers.
+#[inline]
+pub fn signer_key(pk: &PublicKey) -> [F; KEY_FE_LEN] {
+    pk.merkle_root[..KEY_FE_LEN].try_into().unwrap()
+}
+
+/// Returns indices of signatures flagged in `participation` whose signer key
+/// is the same as an earlier participating one, in ascending order.
+pub fn duplicate_signers(public_keys: &[PublicKey], participation: &[bool]) -> Vec<usize> {
+    let mut seen = HashSet::new();
+    zip(public_keys, participation)
+        .enumerate()
+        .filter(|(_, (_, is_participating))| **is_participating)
+        .filter(|(_, (pk, _))| !seen.insert(signer_key(pk).map(|value| value.as_canonical_u32())))
+        .map(|(idx, _)| idx)
+        .collect()
+}
+
+pub const fn trace_height(num_sigs: usize) -> usize {
+    num_sigs.next_power_of_two()
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod test {
+    use_instance!({
+        chip::{
+            generate_air_proof_inputs,
+            unique_signer::{duplicate_signers, signer_key, KEY_FE_LEN},
+        },
+        hash_sig::test::mock_vi,
+        E, F,
+    });
+    use crate::util::engine::Engine;
+    use openvm_stark_backend::utils::disable_debug_builder;
+    use openvm_stark_sdk::engine::StarkEngine;
+
+    #[test]
+    fn duplicates() {
+        let vi = mock_vi(6);
+        let mut public_keys = vi.pairs.iter().map(|(pk, _)| *pk).collect::<Vec<_>>();
+        let participation = [true, true, false, true, true, true];
+        assert!(duplicate_signers(&public_keys, &participation).is_empty());
+
+        public_keys[2] = public_keys[0];
+        assert!(duplicate_signers(&public_keys, &participation).is_empty());
+
+        public_keys[3] = public_keys[0];
+        public_keys[5].merkle_root[..KEY_FE_LEN].copy_from_slice(&signer_key(&public_keys[1]));
+        assert_eq!(duplicate_signers(&public_keys, &participation), [3, 5]);
+    }
+
+    #[test]
+    fn duplicate_signer() {
+        let engine = Engine::<F, E>::fastest();
+        let mut vi = mock_vi(4);
+        vi.pairs[3] = vi.pairs[1];
+        let participation = [true; 4];
+        let (airs, air_proof_inputs) =
+            generate_air_proof_inputs(engine.log_blowup(), vi, &participation);
+
+        disable_debug_builder();
+        assert!(engine.run_test_impl(airs, air_proof_inputs).is_err());
+    }
+}
@@ -1,4 +1,4 @@
-use crate::poseidon2::{Poseidon2Parameter, F};
+use super::{Poseidon2Parameter, F};
 use core::array::from_fn;
 use hash_sig_verifier::{
     concat_array,
@@ -112,9 +112,43 @@ pub fn chain_and_input(
 
 #[cfg(test)]
 pub mod test {
-    use crate::poseidon2::hash_sig::VerificationInput;
+    use super::{
+        encode_tweak_merkle_tree, Poseidon2Parameter, VerificationInput, VerificationTrace, F,
+        HASH_FE_LEN, LOG_LIFETIME, SPONGE_INPUT_SIZE, TWEAK_FE_LEN,
+    };
+    use hash_sig_verifier::{concat_array, instantiation::poseidon2::Poseidon2Parameter as _};
 
     pub fn mock_vi(size: usize) -> VerificationInput {
         hash_sig_testdata::mock_vi(size)
     }
+
+    /// Merkle root of `trace` computed with tweak of path at `forged_level`
+    /// replaced by `forged_tweak`.
+    pub fn forged_merkle_root(
+        epoch: u32,
+        trace: &VerificationTrace,
+        forged_level: usize,
+        forged_tweak: [F; TWEAK_FE_LEN],
+    ) -> [F; HASH_FE_LEN] {
+        let leaf_hash = Poseidon2Parameter::sponge::<SPONGE_INPUT_SIZE, HASH_FE_LEN>(
+            trace.merkle_tree_leaf(epoch),
+        );
+        (0..LOG_LIFETIME).fold(leaf_hash, |node, level| {
+            let tweak = if level == forged_level {
+                forged_tweak
+            } else {
+                encode_tweak_merkle_tree(level as u8 + 1, epoch >> (level + 1))
+            };
+            let sibling = trace.sig.merkle_siblings[level];
+            Poseidon2Parameter::compress_t24::<24, HASH_FE_LEN>(concat_array![
+                trace.pk.parameter,
+                tweak,
+                if (epoch >> level) & 1 == 1 {
+                    [sibling, node].into_iter().flatten()
+                } else {
+                    [node, sibling].into_iter().flatten()
+                }
+            ])
+        })
+    }
 }
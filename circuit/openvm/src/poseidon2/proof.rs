@@ -0,0 +1,148 @@
+use super::{aggregator::VerifyingKey, hash_sig::MSG_LEN, E, F};
+use crate::util::{
+    engine::{Config, Engine, VerifierConfig},
+    key::Fingerprint,
+};
+use core::fmt;
+use openvm_stark_backend::proof::Proof;
+use p3_field::{FieldExtensionAlgebra, PrimeField32};
+use serde::{Deserialize, Serialize};
+
+/// Magic bytes prefixing an encoded [`AggregateProof`].
+pub const MAGIC: [u8; 4] = *b"HSAG";
+
+/// Version of the circuit and encoding, bumped on any change that makes
+/// previously produced proofs unverifiable.
+pub const VERSION: u32 = 5;
+
+/// Parameters a verifier must agree on before verifying the proof.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Header {
+    pub version: u32,
+    pub modulus: u32,
+    pub extension_degree: u32,
+    pub log_blowup: u32,
+    pub num_queries: u32,
+    pub proof_of_work_bits: u32,
+    pub vk_fingerprint: Fingerprint,
+}
+
+impl Header {
+    pub fn new(engine: &Engine<F, E>, vk: &VerifyingKey) -> Self {
+        Self {
+            version: VERSION,
+            modulus: F::ORDER_U32,
+            extension_degree: <E as FieldExtensionAlgebra<F>>::D as u32,
+            log_blowup: engine.log_blowup() as u32,
+            num_queries: engine.num_queries() as u32,
+            proof_of_work_bits: engine.proof_of_work_bits() as u32,
+            vk_fingerprint: vk.fingerprint(),
+        }
+    }
+
+    /// Returns configuration to verify the proof with, which takes the FRI
+    /// parameters from the header, so a verifier doesn't need to be told them.
+    pub fn verifier_config(&self) -> VerifierConfig<F, E> {
+        VerifierConfig::new(
+            self.log_blowup as usize,
+            self.num_queries as usize,
+            self.proof_of_work_bits as usize,
+        )
+    }
+}
+
+/// Aggregate proof together with the statement it proves, which is signatures
+/// on `msg` at `epoch` of signers flagged in `participation` are valid and
+/// weigh at least `threshold`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AggregateProof {
+    pub header: Header,
+    pub epoch: u32,
+    pub msg: [u8; MSG_LEN],
+    pub threshold: u32,
+    pub participation: Vec<bool>,
+    pub(crate) proof: Proof<Config<F, E>>,
+}
+
+#[derive(Debug)]
+pub enum DecodeError {
+    /// Bytes don't start with [`MAGIC`].
+    Magic,
+    /// Proof is encoded by another version.
+    Version(u32),
+    Bincode(bincode::Error),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Magic => write!(f, "not an aggregate proof"),
+            Self::Version(version) => {
+                write!(f, "unsupported version {version}, expected {VERSION}")
+            }
+            Self::Bincode(err) => write!(f, "malformed aggregate proof: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Incompatibility {
+    Version,
+    Field,
+    VerifyingKey,
+}
+
+impl fmt::Display for Incompatibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Version => write!(f, "version"),
+            Self::Field => write!(f, "field"),
+            Self::VerifyingKey => write!(f, "verifying key"),
+        }
+    }
+}
+
+impl AggregateProof {
+    /// Encodes as [`MAGIC`] ++ `VERSION` in little-endian ++ bincode of `self`.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::from(MAGIC);
+        bytes.extend(self.header.version.to_le_bytes());
+        bincode::serialize_into(&mut bytes, self).unwrap();
+        bytes
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let bytes = bytes.strip_prefix(&MAGIC).ok_or(DecodeError::Magic)?;
+        let (version, bytes) = bytes.split_first_chunk().ok_or(DecodeError::Magic)?;
+        let version = u32::from_le_bytes(*version);
+        if version != VERSION {
+            return Err(DecodeError::Version(version));
+        }
+        let proof: Self = bincode::deserialize(bytes).map_err(DecodeError::Bincode)?;
+        if proof.header.version != version {
+            return Err(DecodeError::Version(proof.header.version));
+        }
+        Ok(proof)
+    }
+
+    /// Checks the proof is produced with proving key of `vk`.
+    ///
+    /// FRI parameters aren't checked, since the proof is verified with the
+    /// ones in its header, which are checked to be secure instead.
+    pub fn check_compatibility(&self, vk: &VerifyingKey) -> Result<(), Incompatibility> {
+        let header = &self.header;
+        if header.version != VERSION {
+            Err(Incompatibility::Version)
+        } else if (header.modulus, header.extension_degree)
+            != (F::ORDER_U32, <E as FieldExtensionAlgebra<F>>::D as u32)
+        {
+            Err(Incompatibility::Field)
+        } else if header.vk_fingerprint != vk.fingerprint() {
+            Err(Incompatibility::VerifyingKey)
+        } else {
+            Ok(())
+        }
+    }
+}
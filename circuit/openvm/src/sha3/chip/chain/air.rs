@@ -0,0 +1,159 @@
+use_instance!({
+    chip::{
+        chain::column::{ChainCols, NUM_CHAIN_COLS},
+        padded_preimage, send_keccak_f, Bus, ONE_TIME_PK_OFFSET,
+    },
+    hash_sig::{CHAIN_LEN, CHUNK_SIZE, HASH_LIMBS, TWEAK_CHAIN_SEP},
+    F,
+});
+use crate::{gadget::not, util::zip};
+use core::{array::from_fn, borrow::Borrow, iter};
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    rap::{BaseAirWithPublicValues, PartitionedBaseAir},
+};
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_field::FieldAlgebra;
+use p3_matrix::Matrix;
+
+#[derive(Clone, Copy, Debug)]
+pub struct ChainAir;
+
+impl BaseAir<F> for ChainAir {
+    fn width(&self) -> usize {
+        NUM_CHAIN_COLS
+    }
+}
+
+impl PartitionedBaseAir<F> for ChainAir {}
+
+impl BaseAirWithPublicValues<F> for ChainAir {
+    fn num_public_values(&self) -> usize {
+        4
+    }
+}
+
+impl<AB> Air<AB> for ChainAir
+where
+    AB: InteractionBuilder<F = F> + AirBuilderWithPublicValues,
+{
+    fn eval(&self, builder: &mut AB) {
+        let epoch_be_bytes: [AB::Expr; 4] = from_fn(|i| builder.public_values()[i].into());
+
+        let main = builder.main();
+
+        let local = main.row_slice(0);
+        let local: &ChainCols<AB::Var> = (*local).borrow();
+
+        // When every rows
+        eval_every_row(builder, local);
+
+        // Interaction
+        receive_chain(builder, local);
+        send_chain_steps(builder, &epoch_be_bytes, local);
+        send_one_time_pk(builder, local);
+    }
+}
+
+#[inline]
+fn eval_every_row<AB>(builder: &mut AB, cols: &ChainCols<AB::Var>)
+where
+    AB: AirBuilder<F = F>,
+{
+    builder.assert_bool(cols.is_active);
+    cols.x_i_bits.map(|bit| builder.assert_bool(bit));
+    cols.step_input_bits
+        .iter()
+        .flatten()
+        .for_each(|bit| builder.assert_bool(*bit));
+    let is_x_i = |v: usize| {
+        (0..CHUNK_SIZE)
+            .map(|t| {
+                let bit: AB::Expr = cols.x_i_bits[t].into();
+                if (v >> t) & 1 == 1 {
+                    bit
+                } else {
+                    not(bit)
+                }
+            })
+            .product::<AB::Expr>()
+    };
+    (0..CHAIN_LEN).for_each(|k| {
+        builder.assert_eq(
+            cols.is_step_active[k],
+            (0..=k).map(is_x_i).sum::<AB::Expr>(),
+        );
+    });
+    (0..CHAIN_LEN - 1).for_each(|k| {
+        zip!(cols.step_input::<AB>(k + 1), cols.step_result::<AB>(k))
+            .for_each(|(a, b)| builder.assert_eq(a, b));
+    });
+    zip!(cols.one_time_pk, cols.step_result::<AB>(CHAIN_LEN - 1))
+        .for_each(|(a, b)| builder.assert_eq(a, b));
+}
+
+#[inline]
+fn receive_chain<AB>(builder: &mut AB, local: &ChainCols<AB::Var>)
+where
+    AB: InteractionBuilder<F = F>,
+{
+    builder.push_receive(
+        Bus::Chain as usize,
+        [
+            local.sig_idx.into(),
+            local.chain_idx.into(),
+            local.x_i::<AB>(),
+        ]
+        .into_iter()
+        .chain(local.parameter.map(Into::into)),
+        local.is_active,
+    );
+}
+
+#[inline]
+fn send_chain_steps<AB>(
+    builder: &mut AB,
+    epoch_be_bytes: &[AB::Expr; 4],
+    local: &ChainCols<AB::Var>,
+) where
+    AB: InteractionBuilder<F = F>,
+{
+    (0..CHAIN_LEN).for_each(|k| {
+        send_keccak_f(
+            builder,
+            padded_preimage(
+                local.parameter.map(Into::into),
+                iter::once(AB::Expr::from_canonical_u8(TWEAK_CHAIN_SEP))
+                    .chain(epoch_be_bytes.iter().cloned())
+                    .chain([
+                        AB::Expr::ZERO,
+                        local.chain_idx.into(),
+                        AB::Expr::ZERO,
+                        AB::Expr::from_canonical_usize(k + 1),
+                    ])
+                    .chain(local.step_input_bytes::<AB>(k)),
+            ),
+            &local.step_output[k],
+            local.is_active * local.is_step_active[k],
+        );
+    });
+}
+
+#[inline]
+fn send_one_time_pk<AB>(builder: &mut AB, local: &ChainCols<AB::Var>)
+where
+    AB: InteractionBuilder<F = F>,
+{
+    (0..HASH_LIMBS).for_each(|j| {
+        builder.push_send(
+            Bus::OneTimePk as usize,
+            [
+                local.sig_idx.into(),
+                local.chain_idx * AB::Expr::from_canonical_usize(HASH_LIMBS)
+                    + AB::Expr::from_canonical_usize(ONE_TIME_PK_OFFSET + j),
+                local.one_time_pk[j].into(),
+            ],
+            local.is_active,
+        );
+    });
+}
@@ -0,0 +1,95 @@
+use_instance!({
+    chip::{bytes_from_bits, digest, from_bits, limbs_from_bits},
+    hash_sig::{CHAIN_LEN, CHUNK_SIZE, HASH_LEN, HASH_LIMBS, PARAM_LIMBS, STATE_LIMBS},
+});
+use crate::{gadget::select, util::AlignBorrow};
+use core::{
+    array::from_fn,
+    borrow::{Borrow, BorrowMut},
+};
+use p3_air::AirBuilder;
+
+pub const NUM_CHAIN_COLS: usize = size_of::<ChainCols<u8>>();
+
+#[repr(C)]
+pub struct ChainCols<T> {
+    /// Whether this row is a chain or padding.
+    pub is_active: T,
+    /// Signature index.
+    pub sig_idx: T,
+    /// Chain index.
+    pub chain_idx: T,
+    /// `x_i` in little-endian bits.
+    pub x_i_bits: [T; CHUNK_SIZE],
+    pub parameter: [T; PARAM_LIMBS],
+    /// Whether step `k + 1` is greater than `x_i`, then the hash is computed,
+    /// otherwise the value is passed through.
+    pub is_step_active: [T; CHAIN_LEN],
+    /// Input value of each step in little-endian bits.
+    pub step_input_bits: [[T; 8 * HASH_LEN]; CHAIN_LEN],
+    /// Output state of each step permutation.
+    pub step_output: [[T; STATE_LIMBS]; CHAIN_LEN],
+    pub one_time_pk: [T; HASH_LIMBS],
+}
+
+impl<T: Copy> ChainCols<T> {
+    #[inline]
+    pub fn x_i<AB: AirBuilder>(&self) -> AB::Expr
+    where
+        T: Into<AB::Expr>,
+    {
+        from_bits(self.x_i_bits)
+    }
+
+    #[inline]
+    pub fn step_input_bytes<AB: AirBuilder>(&self, k: usize) -> [AB::Expr; HASH_LEN]
+    where
+        T: Into<AB::Expr>,
+    {
+        bytes_from_bits(&self.step_input_bits[k])
+    }
+
+    #[inline]
+    pub fn step_input<AB: AirBuilder>(&self, k: usize) -> [AB::Expr; HASH_LIMBS]
+    where
+        T: Into<AB::Expr>,
+    {
+        limbs_from_bits(&self.step_input_bits[k])
+    }
+
+    /// Returns output value of step `k + 1`, which is the digest if the step is
+    /// active, otherwise the input value.
+    #[inline]
+    pub fn step_result<AB: AirBuilder>(&self, k: usize) -> [AB::Expr; HASH_LIMBS]
+    where
+        T: Into<AB::Expr>,
+    {
+        let input = self.step_input::<AB>(k);
+        let digest = digest(&self.step_output[k]);
+        from_fn(|i| {
+            select(
+                self.is_step_active[k].into(),
+                input[i].clone(),
+                digest[i].into(),
+            )
+        })
+    }
+}
+
+impl<T> AlignBorrow<T> for ChainCols<T> {
+    const SIZE: usize = NUM_CHAIN_COLS;
+}
+
+impl<T> Borrow<ChainCols<T>> for [T] {
+    #[inline]
+    fn borrow(&self) -> &ChainCols<T> {
+        ChainCols::align_borrow(self)
+    }
+}
+
+impl<T> BorrowMut<ChainCols<T>> for [T] {
+    #[inline]
+    fn borrow_mut(&mut self) -> &mut ChainCols<T> {
+        ChainCols::align_borrow_mut(self)
+    }
+}
@@ -0,0 +1,106 @@
+use_instance!({
+    chip::{
+        bits,
+        chain::column::{ChainCols, NUM_CHAIN_COLS},
+        limbs,
+    },
+    hash_sig::{VerificationTrace, CHAIN_LEN, CHUNK_SIZE, NUM_CHUNKS},
+    F,
+});
+use crate::util::{
+    field::{MaybeUninitField, MaybeUninitFieldSlice},
+    par_zip, zip,
+};
+use core::mem::MaybeUninit;
+use p3_field::FieldAlgebra;
+use p3_matrix::dense::{RowMajorMatrix, RowMajorMatrixViewMut};
+use p3_maybe_rayon::prelude::*;
+
+pub const fn trace_height(traces: &[VerificationTrace]) -> usize {
+    (traces.len() * NUM_CHUNKS).next_power_of_two()
+}
+
+pub fn generate_trace_rows(
+    extra_capacity_bits: usize,
+    traces: &[VerificationTrace],
+) -> RowMajorMatrix<F> {
+    let height = trace_height(traces);
+    let size = height * NUM_CHAIN_COLS;
+    let mut vec = Vec::with_capacity(size << extra_capacity_bits);
+    let trace = &mut vec.spare_capacity_mut()[..size];
+    let trace = RowMajorMatrixViewMut::new(trace, NUM_CHAIN_COLS);
+
+    let (prefix, rows, suffix) =
+        unsafe { trace.values.align_to_mut::<ChainCols<MaybeUninit<_>>>() };
+    assert!(prefix.is_empty(), "Alignment should match");
+    assert!(suffix.is_empty(), "Alignment should match");
+    assert_eq!(rows.len(), height);
+
+    let (rows, padding_rows) = rows.split_at_mut(traces.len() * NUM_CHUNKS);
+
+    join(
+        || {
+            par_zip!(rows.par_chunks_mut(NUM_CHUNKS), traces)
+                .enumerate()
+                .for_each(|(sig_idx, (rows, trace))| {
+                    rows.iter_mut()
+                        .enumerate()
+                        .for_each(|(i, row)| generate_trace_row(row, sig_idx, i, trace));
+                });
+        },
+        || {
+            padding_rows
+                .par_iter_mut()
+                .for_each(generate_trace_row_padding);
+        },
+    );
+
+    unsafe { vec.set_len(size) };
+
+    RowMajorMatrix::new(vec, NUM_CHAIN_COLS)
+}
+
+#[inline]
+fn generate_trace_row(
+    row: &mut ChainCols<MaybeUninit<F>>,
+    sig_idx: usize,
+    i: usize,
+    trace: &VerificationTrace,
+) {
+    let x_i = trace.x[i];
+    row.is_active.write_one();
+    row.sig_idx.write_usize(sig_idx);
+    row.chain_idx.write_usize(i);
+    row.x_i_bits
+        .fill_from_iter((0..CHUNK_SIZE).map(|t| F::from_bool((x_i >> t) & 1 == 1)));
+    row.parameter.fill_from_iter(limbs(&trace.pk.parameter));
+    row.is_step_active
+        .fill_from_iter((1..=CHAIN_LEN as u16).map(|k| F::from_bool(k > x_i)));
+    zip!(
+        &mut row.step_input_bits,
+        &trace.chain_values[i][..CHAIN_LEN]
+    )
+    .for_each(|(cells, value)| cells.fill_from_iter(bits(value)));
+    zip!(&mut row.step_output, &trace.chain_perms[i]).for_each(|(cells, perm)| match perm {
+        Some(perm) => cells.fill_from_iter(perm.output_limbs().map(F::from_canonical_u16)),
+        None => cells.fill_zero(),
+    });
+    row.one_time_pk.fill_from_iter(limbs(&trace.one_time_pk(i)));
+}
+
+#[inline]
+fn generate_trace_row_padding(row: &mut ChainCols<MaybeUninit<F>>) {
+    row.is_active.write_zero();
+    row.sig_idx.write_zero();
+    row.chain_idx.write_zero();
+    row.x_i_bits.fill_zero();
+    row.parameter.fill_zero();
+    row.is_step_active.fill_one();
+    row.step_input_bits
+        .iter_mut()
+        .for_each(|cells| cells.fill_zero());
+    row.step_output
+        .iter_mut()
+        .for_each(|cells| cells.fill_zero());
+    row.one_time_pk.fill_zero();
+}
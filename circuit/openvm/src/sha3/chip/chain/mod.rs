@@ -0,0 +1,90 @@
+use_instance!({
+    chip::chain::column::NUM_CHAIN_COLS,
+    hash_sig::{VerificationTrace, NUM_CHUNKS},
+    F,
+});
+use core::any::type_name;
+use generation::{generate_trace_rows, trace_height};
+use openvm_stark_backend::{
+    config::{Domain, StarkGenericConfig},
+    prover::types::{AirProofInput, AirProofRawInput},
+    rap::AnyRap,
+    Chip, ChipUsageGetter,
+};
+use p3_commit::PolynomialSpace;
+use p3_field::FieldAlgebra;
+use std::sync::Arc;
+
+mod air;
+mod column;
+mod generation;
+
+pub use air::ChainAir;
+
+/// Chain index is encoded in a 16-bit big-endian tweak with the high byte
+/// assumed to be zero.
+const __: () = assert!(NUM_CHUNKS <= 1 << 8);
+
+/// Returns public values of [`ChainAir`].
+pub fn public_values(epoch: u32) -> Vec<F> {
+    epoch
+        .to_be_bytes()
+        .into_iter()
+        .map(F::from_canonical_u8)
+        .collect()
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ChainChip<'a> {
+    extra_capacity_bits: usize,
+    epoch: u32,
+    traces: &'a [VerificationTrace],
+}
+
+impl<'a> ChainChip<'a> {
+    pub const fn new(
+        extra_capacity_bits: usize,
+        epoch: u32,
+        traces: &'a [VerificationTrace],
+    ) -> Self {
+        Self {
+            extra_capacity_bits,
+            epoch,
+            traces,
+        }
+    }
+}
+
+impl ChipUsageGetter for ChainChip<'_> {
+    fn air_name(&self) -> String {
+        type_name::<ChainAir>().to_string()
+    }
+
+    fn current_trace_height(&self) -> usize {
+        trace_height(self.traces)
+    }
+
+    fn trace_width(&self) -> usize {
+        NUM_CHAIN_COLS
+    }
+}
+
+impl<SC: StarkGenericConfig> Chip<SC> for ChainChip<'_>
+where
+    Domain<SC>: PolynomialSpace<Val = F>,
+{
+    fn air(&self) -> Arc<dyn AnyRap<SC>> {
+        Arc::new(ChainAir)
+    }
+
+    fn generate_air_proof_input(self) -> AirProofInput<SC> {
+        AirProofInput {
+            cached_mains_pdata: Vec::new(),
+            raw: AirProofRawInput {
+                cached_mains: Vec::new(),
+                common_main: Some(generate_trace_rows(self.extra_capacity_bits, self.traces)),
+                public_values: public_values(self.epoch),
+            },
+        }
+    }
+}
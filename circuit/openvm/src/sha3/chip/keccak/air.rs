@@ -0,0 +1,58 @@
+use_instance!({chip::Bus, F});
+use core::borrow::Borrow;
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    rap::{BaseAirWithPublicValues, PartitionedBaseAir},
+};
+use p3_air::{Air, AirBuilderWithPublicValues, BaseAir};
+use p3_keccak_air::{KeccakAir, KeccakCols, NUM_KECCAK_COLS, U64_LIMBS};
+use p3_matrix::Matrix;
+
+/// Keccak-f permutation taking 24 rows, exporting input and output of
+/// permutation on the last round row, each as 100 little-endian limbs of 16
+/// bits, ordered lane by lane.
+#[derive(Clone, Copy, Debug)]
+pub struct KeccakFAir;
+
+impl BaseAir<F> for KeccakFAir {
+    fn width(&self) -> usize {
+        NUM_KECCAK_COLS
+    }
+}
+
+impl PartitionedBaseAir<F> for KeccakFAir {}
+
+impl BaseAirWithPublicValues<F> for KeccakFAir {}
+
+impl<AB> Air<AB> for KeccakFAir
+where
+    AB: InteractionBuilder<F = F> + AirBuilderWithPublicValues,
+{
+    fn eval(&self, builder: &mut AB) {
+        KeccakAir {}.eval(builder);
+
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &KeccakCols<AB::Var> = (*local).borrow();
+
+        // Interaction
+        receive_keccak_f(builder, local);
+    }
+}
+
+#[inline]
+fn receive_keccak_f<AB>(builder: &mut AB, local: &KeccakCols<AB::Var>)
+where
+    AB: InteractionBuilder<F = F>,
+{
+    let lanes = || (0..5).flat_map(|y| (0..5).map(move |x| (y, x)));
+    builder.push_receive(
+        Bus::KeccakF as usize,
+        lanes()
+            .flat_map(|(y, x)| local.preimage[y][x])
+            .chain(lanes().flat_map(|(y, x)| {
+                (0..U64_LIMBS).map(move |limb| local.a_prime_prime_prime(y, x, limb))
+            })),
+        local.export,
+    );
+}
@@ -0,0 +1,41 @@
+use_instance!({hash_sig::VerificationTrace, F});
+use core::borrow::BorrowMut;
+use itertools::Itertools;
+use p3_field::FieldAlgebra;
+use p3_keccak_air::{KeccakCols, NUM_ROUNDS};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_maybe_rayon::prelude::*;
+
+fn num_perms(traces: &[VerificationTrace]) -> usize {
+    traces
+        .iter()
+        .map(|trace| trace.keccak_perms().count())
+        .sum()
+}
+
+pub fn trace_height(traces: &[VerificationTrace]) -> usize {
+    (num_perms(traces) * NUM_ROUNDS).next_power_of_two()
+}
+
+pub fn generate_trace_rows(
+    extra_capacity_bits: usize,
+    traces: &[VerificationTrace],
+) -> RowMajorMatrix<F> {
+    let inputs = traces
+        .iter()
+        .flat_map(VerificationTrace::keccak_perms)
+        .map(|perm| perm.input)
+        .collect_vec();
+    let num_perms = inputs.len();
+
+    let mut trace = p3_keccak_air::generate_trace_rows(inputs, extra_capacity_bits);
+
+    trace.par_rows_mut().enumerate().for_each(|(row_idx, row)| {
+        let row: &mut KeccakCols<F> = row.borrow_mut();
+        row.export = F::from_bool(
+            row_idx % NUM_ROUNDS == NUM_ROUNDS - 1 && row_idx / NUM_ROUNDS < num_perms,
+        );
+    });
+
+    trace
+}
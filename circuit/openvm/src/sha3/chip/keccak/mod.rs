@@ -0,0 +1,66 @@
+use_instance!({hash_sig::VerificationTrace, F});
+use core::any::type_name;
+use generation::{generate_trace_rows, trace_height};
+use openvm_stark_backend::{
+    config::{Domain, StarkGenericConfig},
+    prover::types::{AirProofInput, AirProofRawInput},
+    rap::AnyRap,
+    Chip, ChipUsageGetter,
+};
+use p3_commit::PolynomialSpace;
+use p3_keccak_air::NUM_KECCAK_COLS;
+use std::sync::Arc;
+
+mod air;
+mod generation;
+
+pub use air::KeccakFAir;
+
+#[derive(Clone, Copy, Debug)]
+pub struct KeccakFChip<'a> {
+    extra_capacity_bits: usize,
+    traces: &'a [VerificationTrace],
+}
+
+impl<'a> KeccakFChip<'a> {
+    pub const fn new(extra_capacity_bits: usize, traces: &'a [VerificationTrace]) -> Self {
+        Self {
+            extra_capacity_bits,
+            traces,
+        }
+    }
+}
+
+impl ChipUsageGetter for KeccakFChip<'_> {
+    fn air_name(&self) -> String {
+        type_name::<KeccakFAir>().to_string()
+    }
+
+    fn current_trace_height(&self) -> usize {
+        trace_height(self.traces)
+    }
+
+    fn trace_width(&self) -> usize {
+        NUM_KECCAK_COLS
+    }
+}
+
+impl<SC: StarkGenericConfig> Chip<SC> for KeccakFChip<'_>
+where
+    Domain<SC>: PolynomialSpace<Val = F>,
+{
+    fn air(&self) -> Arc<dyn AnyRap<SC>> {
+        Arc::new(KeccakFAir)
+    }
+
+    fn generate_air_proof_input(self) -> AirProofInput<SC> {
+        AirProofInput {
+            cached_mains_pdata: Vec::new(),
+            raw: AirProofRawInput {
+                cached_mains: Vec::new(),
+                common_main: Some(generate_trace_rows(self.extra_capacity_bits, self.traces)),
+                public_values: Vec::new(),
+            },
+        }
+    }
+}
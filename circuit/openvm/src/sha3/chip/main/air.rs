@@ -0,0 +1,157 @@
+use_instance!({
+    chip::{
+        limbs_from_bits,
+        main::column::{MainCols, NUM_MAIN_COLS},
+        padded_preimage, send_keccak_f, Bus,
+    },
+    hash_sig::{LOG_LIFETIME, MSG_HASH_LIMBS, MSG_LEN, NUM_CHUNKS, TARGET_SUM, TWEAK_MSG_SEP},
+    F,
+});
+use crate::util::zip;
+use core::{array::from_fn, borrow::Borrow, iter};
+use itertools::Itertools;
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    rap::{BaseAirWithPublicValues, PartitionedBaseAir},
+};
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_field::FieldAlgebra;
+use p3_matrix::Matrix;
+
+pub const NUM_PUBLIC_VALUES: usize = 4 + MSG_LEN;
+
+#[derive(Clone, Copy, Debug)]
+pub struct MainAir;
+
+impl BaseAir<F> for MainAir {
+    fn width(&self) -> usize {
+        NUM_MAIN_COLS
+    }
+}
+
+impl PartitionedBaseAir<F> for MainAir {}
+
+impl BaseAirWithPublicValues<F> for MainAir {
+    fn num_public_values(&self) -> usize {
+        NUM_PUBLIC_VALUES
+    }
+}
+
+impl<AB> Air<AB> for MainAir
+where
+    AB: InteractionBuilder<F = F> + AirBuilderWithPublicValues,
+{
+    fn eval(&self, builder: &mut AB) {
+        let public_values: [AB::Expr; NUM_PUBLIC_VALUES] =
+            from_fn(|i| builder.public_values()[i].into());
+        let (epoch_le_bytes, msg) = public_values.split_at(4);
+
+        let main = builder.main();
+
+        let local = main.row_slice(0);
+        let next = main.row_slice(1);
+        let local: &MainCols<AB::Var> = (*local).borrow();
+        let next: &MainCols<AB::Var> = (*next).borrow();
+
+        // When every rows
+        builder.assert_bool(local.is_active);
+        local.parameter_bits.map(|bit| builder.assert_bool(bit));
+        local.rho_bits.map(|bit| builder.assert_bool(bit));
+        local.msg_hash_bits.map(|bit| builder.assert_bool(bit));
+        zip!(
+            &local.msg_hash[..MSG_HASH_LIMBS],
+            limbs_from_bits::<AB::Expr, MSG_HASH_LIMBS>(&local.msg_hash_bits)
+        )
+        .for_each(|(a, b)| builder.assert_eq(*a, b));
+        builder.when(local.is_active).assert_eq(
+            local.x::<AB>().into_iter().sum::<AB::Expr>(),
+            AB::Expr::from_canonical_u16(TARGET_SUM),
+        );
+
+        // When first row
+        builder.when_first_row().assert_zero(local.sig_idx);
+
+        // When transition
+        builder
+            .when_transition()
+            .assert_eq(next.sig_idx, local.sig_idx + AB::Expr::ONE);
+
+        // Interaction
+        send_msg_hash(builder, epoch_le_bytes, msg, local);
+        send_chain(builder, local);
+        send_parameter(builder, local);
+        receive_merkle_root(builder, local);
+    }
+}
+
+#[inline]
+fn send_msg_hash<AB>(
+    builder: &mut AB,
+    epoch_le_bytes: &[AB::Expr],
+    msg: &[AB::Expr],
+    local: &MainCols<AB::Var>,
+) where
+    AB: InteractionBuilder<F = F>,
+{
+    send_keccak_f(
+        builder,
+        padded_preimage(
+            [],
+            local
+                .rho_bytes::<AB>()
+                .into_iter()
+                .chain(local.parameter_bytes::<AB>())
+                .chain([AB::Expr::from_canonical_u8(TWEAK_MSG_SEP)])
+                .chain(epoch_le_bytes.iter().cloned())
+                .chain(msg.iter().cloned()),
+        ),
+        &local.msg_hash,
+        local.is_active,
+    );
+}
+
+#[inline]
+fn send_chain<AB>(builder: &mut AB, local: &MainCols<AB::Var>)
+where
+    AB: InteractionBuilder<F = F>,
+{
+    zip!(0..NUM_CHUNKS, local.x::<AB>()).for_each(|(i, x_i)| {
+        builder.push_send(
+            Bus::Chain as usize,
+            [local.sig_idx.into(), AB::Expr::from_canonical_usize(i), x_i]
+                .into_iter()
+                .chain(local.parameter::<AB>()),
+            local.is_active,
+        );
+    });
+}
+
+#[inline]
+fn send_parameter<AB>(builder: &mut AB, local: &MainCols<AB::Var>)
+where
+    AB: InteractionBuilder<F = F>,
+{
+    builder.push_send(
+        Bus::Parameter as usize,
+        iter::once(local.sig_idx.into()).chain(local.parameter::<AB>()),
+        local.is_active * AB::Expr::from_canonical_usize(1 + LOG_LIFETIME),
+    );
+}
+
+#[inline]
+fn receive_merkle_root<AB>(builder: &mut AB, local: &MainCols<AB::Var>)
+where
+    AB: InteractionBuilder<F = F>,
+{
+    builder.push_receive(
+        Bus::MerklePath as usize,
+        [
+            local.sig_idx.into(),
+            AB::Expr::from_canonical_usize(LOG_LIFETIME + 1),
+        ]
+        .into_iter()
+        .chain(local.merkle_root.iter().copied().map_into())
+        .chain([AB::Expr::ZERO]),
+        local.is_active,
+    );
+}
@@ -0,0 +1,91 @@
+use_instance!({
+    chip::{bytes_from_bits, from_bits, limbs_from_bits},
+    hash_sig::{
+        CHUNK_SIZE, HASH_LIMBS, MSG_HASH_LEN, NUM_CHUNKS, PARAM_LEN, PARAM_LIMBS, RHO_LEN,
+        STATE_LIMBS,
+    },
+});
+use crate::util::AlignBorrow;
+use core::{
+    array::from_fn,
+    borrow::{Borrow, BorrowMut},
+};
+use p3_air::AirBuilder;
+
+pub const NUM_MAIN_COLS: usize = size_of::<MainCols<u8>>();
+
+#[repr(C)]
+pub struct MainCols<T> {
+    /// Whether this row is a signature or padding.
+    pub is_active: T,
+    /// Signature index.
+    pub sig_idx: T,
+    /// Parameter in little-endian bits, misaligned with limbs in the message
+    /// hash preimage so it's decomposed.
+    pub parameter_bits: [T; 8 * PARAM_LEN],
+    pub merkle_root: [T; HASH_LIMBS],
+    pub rho_bits: [T; 8 * RHO_LEN],
+    /// Output state of the message hash permutation.
+    pub msg_hash: [T; STATE_LIMBS],
+    /// Message hash in little-endian bits, which is also `x_i` in
+    /// little-endian.
+    pub msg_hash_bits: [T; 8 * MSG_HASH_LEN],
+}
+
+impl<T: Copy> MainCols<T> {
+    #[inline]
+    pub fn parameter_bytes<AB: AirBuilder>(&self) -> [AB::Expr; PARAM_LEN]
+    where
+        T: Into<AB::Expr>,
+    {
+        bytes_from_bits(&self.parameter_bits)
+    }
+
+    #[inline]
+    pub fn parameter<AB: AirBuilder>(&self) -> [AB::Expr; PARAM_LIMBS]
+    where
+        T: Into<AB::Expr>,
+    {
+        limbs_from_bits(&self.parameter_bits)
+    }
+
+    #[inline]
+    pub fn rho_bytes<AB: AirBuilder>(&self) -> [AB::Expr; RHO_LEN]
+    where
+        T: Into<AB::Expr>,
+    {
+        bytes_from_bits(&self.rho_bits)
+    }
+
+    #[inline]
+    pub fn x<AB: AirBuilder>(&self) -> [AB::Expr; NUM_CHUNKS]
+    where
+        T: Into<AB::Expr>,
+    {
+        from_fn(|i| {
+            from_bits(
+                self.msg_hash_bits[CHUNK_SIZE * i..][..CHUNK_SIZE]
+                    .iter()
+                    .copied(),
+            )
+        })
+    }
+}
+
+impl<T> AlignBorrow<T> for MainCols<T> {
+    const SIZE: usize = NUM_MAIN_COLS;
+}
+
+impl<T> Borrow<MainCols<T>> for [T] {
+    #[inline]
+    fn borrow(&self) -> &MainCols<T> {
+        MainCols::align_borrow(self)
+    }
+}
+
+impl<T> BorrowMut<MainCols<T>> for [T] {
+    #[inline]
+    fn borrow_mut(&mut self) -> &mut MainCols<T> {
+        MainCols::align_borrow_mut(self)
+    }
+}
@@ -0,0 +1,58 @@
+use_instance!({
+    chip::{
+        bits, limbs,
+        main::column::{MainCols, NUM_MAIN_COLS},
+    },
+    hash_sig::{VerificationTrace, MSG_HASH_LEN},
+    F,
+});
+use crate::util::field::{MaybeUninitField, MaybeUninitFieldSlice};
+use core::mem::MaybeUninit;
+use p3_field::FieldAlgebra;
+use p3_matrix::dense::{RowMajorMatrix, RowMajorMatrixViewMut};
+use p3_maybe_rayon::prelude::*;
+
+pub const fn trace_height(traces: &[VerificationTrace]) -> usize {
+    traces.len().next_power_of_two()
+}
+
+pub fn generate_trace_rows(
+    extra_capacity_bits: usize,
+    traces: &[VerificationTrace],
+) -> RowMajorMatrix<F> {
+    let height = trace_height(traces);
+    let size = height * NUM_MAIN_COLS;
+    let mut vec = Vec::with_capacity(size << extra_capacity_bits);
+    let trace = &mut vec.spare_capacity_mut()[..size];
+    let trace = RowMajorMatrixViewMut::new(trace, NUM_MAIN_COLS);
+
+    let (prefix, rows, suffix) = unsafe { trace.values.align_to_mut::<MainCols<MaybeUninit<_>>>() };
+    assert!(prefix.is_empty(), "Alignment should match");
+    assert!(suffix.is_empty(), "Alignment should match");
+    assert_eq!(rows.len(), height);
+
+    rows.par_iter_mut().enumerate().for_each(|(sig_idx, row)| {
+        row.sig_idx.write_usize(sig_idx);
+        if let Some(trace) = traces.get(sig_idx) {
+            row.is_active.write_one();
+            row.parameter_bits.fill_from_iter(bits(&trace.pk.parameter));
+            row.merkle_root.fill_from_iter(limbs(&trace.pk.merkle_root));
+            row.rho_bits.fill_from_iter(bits(&trace.sig.rho));
+            row.msg_hash
+                .fill_from_iter(trace.msg_hash.output_limbs().map(F::from_canonical_u16));
+            row.msg_hash_bits
+                .fill_from_iter(bits(&trace.msg_hash.digest::<MSG_HASH_LEN>()));
+        } else {
+            row.is_active.write_zero();
+            row.parameter_bits.fill_zero();
+            row.merkle_root.fill_zero();
+            row.rho_bits.fill_zero();
+            row.msg_hash.fill_zero();
+            row.msg_hash_bits.fill_zero();
+        }
+    });
+
+    unsafe { vec.set_len(size) };
+
+    RowMajorMatrix::new(vec, NUM_MAIN_COLS)
+}
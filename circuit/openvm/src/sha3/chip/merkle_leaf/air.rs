@@ -0,0 +1,208 @@
+use_instance!({
+    chip::{
+        digest, limb_from_bytes,
+        merkle_leaf::{
+            column::{MerkleLeafCols, NUM_MERKLE_LEAF_COLS},
+            padding_limb, LAST_BLOCK_LIMBS,
+        },
+        send_keccak_f, Bus, ONE_TIME_PK_OFFSET,
+    },
+    hash_sig::{MERKLE_LEAF_BLOCKS, PARAM_LIMBS, RATE_LIMBS, TWEAK_MERKLE_TREE_SEP},
+    F,
+});
+use crate::{gadget::not, util::zip};
+use core::{array::from_fn, borrow::Borrow, iter};
+use itertools::Itertools;
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    rap::{BaseAirWithPublicValues, PartitionedBaseAir},
+};
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_field::FieldAlgebra;
+use p3_matrix::Matrix;
+
+#[derive(Clone, Copy, Debug)]
+pub struct MerkleLeafAir;
+
+impl BaseAir<F> for MerkleLeafAir {
+    fn width(&self) -> usize {
+        NUM_MERKLE_LEAF_COLS
+    }
+}
+
+impl PartitionedBaseAir<F> for MerkleLeafAir {}
+
+impl BaseAirWithPublicValues<F> for MerkleLeafAir {
+    fn num_public_values(&self) -> usize {
+        4
+    }
+}
+
+impl<AB> Air<AB> for MerkleLeafAir
+where
+    AB: InteractionBuilder<F = F> + AirBuilderWithPublicValues,
+{
+    fn eval(&self, builder: &mut AB) {
+        let epoch_be_bytes: [AB::Expr; 4] = from_fn(|i| builder.public_values()[i].into());
+
+        let main = builder.main();
+
+        let local = main.row_slice(0);
+        let local: &MerkleLeafCols<AB::Var> = (*local).borrow();
+
+        // When every rows
+        eval_every_row(builder, &epoch_be_bytes, local);
+
+        // Interaction
+        receive_parameter(builder, local);
+        receive_one_time_pk(builder, local);
+        receive_state(builder, local);
+        send_keccak_f(builder, local.input::<AB>(), &local.output, local.is_active);
+        send_state(builder, local);
+        send_merkle_path(builder, &epoch_be_bytes, local);
+    }
+}
+
+#[inline]
+fn eval_every_row<AB>(
+    builder: &mut AB,
+    epoch_be_bytes: &[AB::Expr; 4],
+    cols: &MerkleLeafCols<AB::Var>,
+) where
+    AB: AirBuilder<F = F>,
+{
+    builder.assert_bool(cols.is_active);
+    cols.is_first_block.eval(builder, cols.block_idx);
+    cols.is_last_block.eval(
+        builder,
+        cols.block_idx,
+        AB::Expr::from_canonical_usize(MERKLE_LEAF_BLOCKS - 1),
+    );
+    cols.block_bits.map(|bit| builder.assert_bool(bit));
+    cols.rate_bits.map(|bit| builder.assert_bool(bit));
+
+    let block = cols.block::<AB>();
+
+    // When first block
+    {
+        let is_first_block = cols.is_first_block.output;
+
+        cols.rate_bits
+            .map(|bit| builder.when(is_first_block).assert_zero(bit));
+        cols.capacity
+            .map(|v| builder.when(is_first_block).assert_zero(v));
+        let tweak = [
+            AB::Expr::from_canonical_u8(TWEAK_MERKLE_TREE_SEP),
+            AB::Expr::ZERO,
+        ]
+        .into_iter()
+        .chain(epoch_be_bytes.iter().cloned())
+        .tuples()
+        .map(|(lo, hi)| limb_from_bytes(lo, hi));
+        zip!(&block[PARAM_LIMBS..ONE_TIME_PK_OFFSET], tweak).for_each(|(a, b)| {
+            builder
+                .when(cols.is_active * is_first_block)
+                .assert_eq(a.clone(), b);
+        });
+    }
+
+    // When last block
+    {
+        let is_last_block = cols.is_last_block.output;
+
+        zip!(LAST_BLOCK_LIMBS..RATE_LIMBS, &block[LAST_BLOCK_LIMBS..]).for_each(|(j, a)| {
+            builder
+                .when(cols.is_active * is_last_block)
+                .assert_eq(a.clone(), AB::Expr::from_canonical_u32(padding_limb(j)));
+        });
+    }
+}
+
+#[inline]
+fn receive_parameter<AB>(builder: &mut AB, local: &MerkleLeafCols<AB::Var>)
+where
+    AB: InteractionBuilder<F = F>,
+{
+    builder.push_receive(
+        Bus::Parameter as usize,
+        iter::once(local.sig_idx.into()).chain(local.block::<AB>().into_iter().take(PARAM_LIMBS)),
+        local.is_active * local.is_first_block.output,
+    );
+}
+
+#[inline]
+fn receive_one_time_pk<AB>(builder: &mut AB, local: &MerkleLeafCols<AB::Var>)
+where
+    AB: InteractionBuilder<F = F>,
+{
+    zip!(0..RATE_LIMBS, local.block::<AB>()).for_each(|(j, limb)| {
+        let count = if j < ONE_TIME_PK_OFFSET {
+            local.is_active * not::<AB::Expr>(local.is_first_block.output.into())
+        } else if j >= LAST_BLOCK_LIMBS {
+            local.is_active * not::<AB::Expr>(local.is_last_block.output.into())
+        } else {
+            local.is_active.into()
+        };
+        builder.push_receive(
+            Bus::OneTimePk as usize,
+            [
+                local.sig_idx.into(),
+                local.block_idx * AB::Expr::from_canonical_usize(RATE_LIMBS)
+                    + AB::Expr::from_canonical_usize(j),
+                limb,
+            ],
+            count,
+        );
+    });
+}
+
+#[inline]
+fn receive_state<AB>(builder: &mut AB, local: &MerkleLeafCols<AB::Var>)
+where
+    AB: InteractionBuilder<F = F>,
+{
+    builder.push_receive(
+        Bus::MerkleLeafState as usize,
+        [local.sig_idx.into(), local.block_idx.into()]
+            .into_iter()
+            .chain(local.state::<AB>()),
+        local.is_active * not::<AB::Expr>(local.is_first_block.output.into()),
+    );
+}
+
+#[inline]
+fn send_state<AB>(builder: &mut AB, local: &MerkleLeafCols<AB::Var>)
+where
+    AB: InteractionBuilder<F = F>,
+{
+    builder.push_send(
+        Bus::MerkleLeafState as usize,
+        [local.sig_idx.into(), local.block_idx + AB::Expr::ONE]
+            .into_iter()
+            .chain(local.output.map(Into::into)),
+        local.is_active * not::<AB::Expr>(local.is_last_block.output.into()),
+    );
+}
+
+#[inline]
+fn send_merkle_path<AB>(
+    builder: &mut AB,
+    epoch_be_bytes: &[AB::Expr; 4],
+    local: &MerkleLeafCols<AB::Var>,
+) where
+    AB: InteractionBuilder<F = F>,
+{
+    let epoch = epoch_be_bytes
+        .iter()
+        .cloned()
+        .reduce(|acc, byte| acc * AB::Expr::from_canonical_u32(1 << 8) + byte)
+        .unwrap();
+    builder.push_send(
+        Bus::MerklePath as usize,
+        [local.sig_idx.into(), AB::Expr::ONE]
+            .into_iter()
+            .chain(digest(&local.output).map(Into::into))
+            .chain([epoch]),
+        local.is_active * local.is_last_block.output,
+    );
+}
@@ -0,0 +1,102 @@
+use_instance!({
+    chip::{from_bits, limbs_from_bits},
+    hash_sig::{CAPACITY_LIMBS, LIMB_BITS, RATE, RATE_LIMBS, STATE_LIMBS},
+});
+use crate::{
+    gadget::{is_equal::IsEqualCols, is_zero::IsZeroCols},
+    util::AlignBorrow,
+};
+use core::{
+    array::from_fn,
+    borrow::{Borrow, BorrowMut},
+};
+use p3_air::AirBuilder;
+use p3_field::FieldAlgebra;
+
+pub const NUM_MERKLE_LEAF_COLS: usize = size_of::<MerkleLeafCols<u8>>();
+
+#[repr(C)]
+pub struct MerkleLeafCols<T> {
+    /// Whether this row is a block or padding.
+    pub is_active: T,
+    /// Signature index.
+    pub sig_idx: T,
+    /// Index of the absorbed block.
+    pub block_idx: T,
+    /// Whether `block_idx` is the first block.
+    pub is_first_block: IsZeroCols<T>,
+    /// Whether `block_idx` is the last block.
+    pub is_last_block: IsEqualCols<T>,
+    /// Absorbed block in little-endian bits.
+    pub block_bits: [T; 8 * RATE],
+    /// Rate part of the state before absorbing in little-endian bits.
+    pub rate_bits: [T; 8 * RATE],
+    /// Capacity part of the state before absorbing.
+    pub capacity: [T; CAPACITY_LIMBS],
+    /// Output state of the permutation.
+    pub output: [T; STATE_LIMBS],
+}
+
+impl<T: Copy> MerkleLeafCols<T> {
+    #[inline]
+    pub fn block<AB: AirBuilder>(&self) -> [AB::Expr; RATE_LIMBS]
+    where
+        T: Into<AB::Expr>,
+    {
+        limbs_from_bits(&self.block_bits)
+    }
+
+    /// Returns the state before absorbing.
+    #[inline]
+    pub fn state<AB: AirBuilder>(&self) -> [AB::Expr; STATE_LIMBS]
+    where
+        T: Into<AB::Expr>,
+    {
+        let rate = limbs_from_bits::<AB::Expr, RATE_LIMBS>(&self.rate_bits);
+        from_fn(|i| {
+            if i < RATE_LIMBS {
+                rate[i].clone()
+            } else {
+                self.capacity[i - RATE_LIMBS].into()
+            }
+        })
+    }
+
+    /// Returns the input state of the permutation, which is the state with the
+    /// block xor-ed into the rate part.
+    #[inline]
+    pub fn input<AB: AirBuilder>(&self) -> [AB::Expr; STATE_LIMBS]
+    where
+        T: Into<AB::Expr>,
+    {
+        from_fn(|i| {
+            if i < RATE_LIMBS {
+                from_bits((LIMB_BITS * i..LIMB_BITS * (i + 1)).map(|t| {
+                    let a: AB::Expr = self.rate_bits[t].into();
+                    let b: AB::Expr = self.block_bits[t].into();
+                    a.clone() + b.clone() - (a * b).double()
+                }))
+            } else {
+                self.capacity[i - RATE_LIMBS].into()
+            }
+        })
+    }
+}
+
+impl<T> AlignBorrow<T> for MerkleLeafCols<T> {
+    const SIZE: usize = NUM_MERKLE_LEAF_COLS;
+}
+
+impl<T> Borrow<MerkleLeafCols<T>> for [T] {
+    #[inline]
+    fn borrow(&self) -> &MerkleLeafCols<T> {
+        MerkleLeafCols::align_borrow(self)
+    }
+}
+
+impl<T> BorrowMut<MerkleLeafCols<T>> for [T] {
+    #[inline]
+    fn borrow_mut(&mut self) -> &mut MerkleLeafCols<T> {
+        MerkleLeafCols::align_borrow_mut(self)
+    }
+}
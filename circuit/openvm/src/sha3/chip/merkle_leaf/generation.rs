@@ -0,0 +1,122 @@
+use_instance!({
+    chip::{
+        bits,
+        merkle_leaf::column::{MerkleLeafCols, NUM_MERKLE_LEAF_COLS},
+    },
+    hash_sig::{pad, VerificationTrace, MERKLE_LEAF_BLOCKS, RATE, RATE_LIMBS},
+    F,
+});
+use crate::util::{
+    field::{MaybeUninitField, MaybeUninitFieldSlice},
+    par_zip, zip,
+};
+use core::{iter, mem::MaybeUninit};
+use p3_field::FieldAlgebra;
+use p3_matrix::dense::{RowMajorMatrix, RowMajorMatrixViewMut};
+use p3_maybe_rayon::prelude::*;
+
+pub const fn trace_height(traces: &[VerificationTrace]) -> usize {
+    (traces.len() * MERKLE_LEAF_BLOCKS).next_power_of_two()
+}
+
+pub fn generate_trace_rows(
+    extra_capacity_bits: usize,
+    epoch: u32,
+    traces: &[VerificationTrace],
+) -> RowMajorMatrix<F> {
+    let height = trace_height(traces);
+    let size = height * NUM_MERKLE_LEAF_COLS;
+    let mut vec = Vec::with_capacity(size << extra_capacity_bits);
+    let trace = &mut vec.spare_capacity_mut()[..size];
+    let trace = RowMajorMatrixViewMut::new(trace, NUM_MERKLE_LEAF_COLS);
+
+    let (prefix, rows, suffix) = unsafe {
+        trace
+            .values
+            .align_to_mut::<MerkleLeafCols<MaybeUninit<_>>>()
+    };
+    assert!(prefix.is_empty(), "Alignment should match");
+    assert!(suffix.is_empty(), "Alignment should match");
+    assert_eq!(rows.len(), height);
+
+    let (rows, padding_rows) = rows.split_at_mut(traces.len() * MERKLE_LEAF_BLOCKS);
+
+    join(
+        || {
+            par_zip!(rows.par_chunks_mut(MERKLE_LEAF_BLOCKS), traces)
+                .enumerate()
+                .for_each(|(sig_idx, (rows, trace))| {
+                    generate_trace_rows_sig(rows, epoch, sig_idx, trace);
+                });
+        },
+        || {
+            padding_rows
+                .par_iter_mut()
+                .for_each(generate_trace_row_padding);
+        },
+    );
+
+    unsafe { vec.set_len(size) };
+
+    RowMajorMatrix::new(vec, NUM_MERKLE_LEAF_COLS)
+}
+
+#[inline]
+fn generate_trace_rows_sig(
+    rows: &mut [MerkleLeafCols<MaybeUninit<F>>],
+    epoch: u32,
+    sig_idx: usize,
+    trace: &VerificationTrace,
+) {
+    let padded = pad(&trace.merkle_leaf_input(epoch));
+    let states =
+        iter::once(None).chain(trace.merkle_leaf[..MERKLE_LEAF_BLOCKS - 1].iter().map(Some));
+    zip!(rows, padded.chunks(RATE), &trace.merkle_leaf, states)
+        .enumerate()
+        .for_each(|(block_idx, (row, block, perm, state))| {
+            let block_idx_f = F::from_canonical_usize(block_idx);
+            let state = state.map(|state| state.output_limbs());
+            row.is_active.write_one();
+            row.sig_idx.write_usize(sig_idx);
+            row.block_idx.write_usize(block_idx);
+            row.is_first_block.populate(block_idx_f);
+            row.is_last_block
+                .populate(block_idx_f, F::from_canonical_usize(MERKLE_LEAF_BLOCKS - 1));
+            row.block_bits.fill_from_iter(bits(block));
+            match state {
+                Some(state) => {
+                    let rate = state[..RATE_LIMBS]
+                        .iter()
+                        .flat_map(|limb| limb.to_le_bytes())
+                        .collect::<Vec<_>>();
+                    row.rate_bits.fill_from_iter(bits(&rate));
+                    row.capacity.fill_from_iter(
+                        state[RATE_LIMBS..]
+                            .iter()
+                            .copied()
+                            .map(F::from_canonical_u16),
+                    );
+                }
+                None => {
+                    row.rate_bits.fill_zero();
+                    row.capacity.fill_zero();
+                }
+            }
+            row.output
+                .fill_from_iter(perm.output_limbs().map(F::from_canonical_u16));
+        });
+}
+
+#[inline]
+fn generate_trace_row_padding(row: &mut MerkleLeafCols<MaybeUninit<F>>) {
+    row.is_active.write_zero();
+    row.sig_idx.write_zero();
+    row.block_idx.write_zero();
+    row.is_first_block.populate(F::ZERO);
+    row.is_last_block
+        .populate(F::ZERO, F::from_canonical_usize(MERKLE_LEAF_BLOCKS - 1));
+    row.block_bits.fill_zero();
+    row.rate_bits.fill_zero();
+    row.capacity.fill_zero();
+    row.output.fill_zero();
+}
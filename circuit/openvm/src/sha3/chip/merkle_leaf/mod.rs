@@ -0,0 +1,109 @@
+use_instance!({
+    chip::merkle_leaf::column::NUM_MERKLE_LEAF_COLS,
+    hash_sig::{VerificationTrace, MERKLE_LEAF_BLOCKS, MERKLE_LEAF_LEN, RATE_LIMBS},
+    F, PADDING_DOMAIN,
+});
+use core::any::type_name;
+use generation::{generate_trace_rows, trace_height};
+use openvm_stark_backend::{
+    config::{Domain, StarkGenericConfig},
+    prover::types::{AirProofInput, AirProofRawInput},
+    rap::AnyRap,
+    Chip, ChipUsageGetter,
+};
+use p3_commit::PolynomialSpace;
+use p3_field::FieldAlgebra;
+use std::sync::Arc;
+
+mod air;
+mod column;
+mod generation;
+
+pub use air::MerkleLeafAir;
+
+/// Number of limbs of the Merkle leaf preimage in the last block, the rest of
+/// the block is padding.
+const LAST_BLOCK_LIMBS: usize = {
+    assert!(MERKLE_LEAF_LEN % 2 == 0);
+    MERKLE_LEAF_LEN / 2 - (MERKLE_LEAF_BLOCKS - 1) * RATE_LIMBS
+};
+
+/// Returns limb `j` of the last block, for `j` in padding.
+const fn padding_limb(j: usize) -> u32 {
+    let mut limb = 0;
+    if j == LAST_BLOCK_LIMBS {
+        limb += PADDING_DOMAIN as u32;
+    }
+    if j == RATE_LIMBS - 1 {
+        limb += 0x80 << 8;
+    }
+    limb
+}
+
+/// Returns public values of [`MerkleLeafAir`].
+pub fn public_values(epoch: u32) -> Vec<F> {
+    epoch
+        .to_be_bytes()
+        .into_iter()
+        .map(F::from_canonical_u8)
+        .collect()
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct MerkleLeafChip<'a> {
+    extra_capacity_bits: usize,
+    epoch: u32,
+    traces: &'a [VerificationTrace],
+}
+
+impl<'a> MerkleLeafChip<'a> {
+    pub const fn new(
+        extra_capacity_bits: usize,
+        epoch: u32,
+        traces: &'a [VerificationTrace],
+    ) -> Self {
+        Self {
+            extra_capacity_bits,
+            epoch,
+            traces,
+        }
+    }
+}
+
+impl ChipUsageGetter for MerkleLeafChip<'_> {
+    fn air_name(&self) -> String {
+        type_name::<MerkleLeafAir>().to_string()
+    }
+
+    fn current_trace_height(&self) -> usize {
+        trace_height(self.traces)
+    }
+
+    fn trace_width(&self) -> usize {
+        NUM_MERKLE_LEAF_COLS
+    }
+}
+
+impl<SC: StarkGenericConfig> Chip<SC> for MerkleLeafChip<'_>
+where
+    Domain<SC>: PolynomialSpace<Val = F>,
+{
+    fn air(&self) -> Arc<dyn AnyRap<SC>> {
+        Arc::new(MerkleLeafAir)
+    }
+
+    fn generate_air_proof_input(self) -> AirProofInput<SC> {
+        AirProofInput {
+            cached_mains_pdata: Vec::new(),
+            raw: AirProofRawInput {
+                cached_mains: Vec::new(),
+                common_main: Some(generate_trace_rows(
+                    self.extra_capacity_bits,
+                    self.epoch,
+                    self.traces,
+                )),
+                public_values: public_values(self.epoch),
+            },
+        }
+    }
+}
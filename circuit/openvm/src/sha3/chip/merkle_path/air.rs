@@ -0,0 +1,141 @@
+use_instance!({
+    chip::{
+        digest, limb_from_bytes,
+        merkle_path::column::{MerklePathCols, NUM_MERKLE_PATH_COLS},
+        padded_preimage, send_keccak_f, Bus,
+    },
+    hash_sig::TWEAK_MERKLE_TREE_SEP,
+    F,
+});
+use crate::gadget::select;
+use core::{borrow::Borrow, iter};
+use itertools::Itertools;
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    rap::{BaseAirWithPublicValues, PartitionedBaseAir},
+};
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_field::FieldAlgebra;
+use p3_matrix::Matrix;
+
+#[derive(Clone, Copy, Debug)]
+pub struct MerklePathAir;
+
+impl BaseAir<F> for MerklePathAir {
+    fn width(&self) -> usize {
+        NUM_MERKLE_PATH_COLS
+    }
+}
+
+impl PartitionedBaseAir<F> for MerklePathAir {}
+
+impl BaseAirWithPublicValues<F> for MerklePathAir {}
+
+impl<AB> Air<AB> for MerklePathAir
+where
+    AB: InteractionBuilder<F = F> + AirBuilderWithPublicValues,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+
+        let local = main.row_slice(0);
+        let local: &MerklePathCols<AB::Var> = (*local).borrow();
+
+        // When every rows
+        builder.assert_bool(local.is_active);
+        local.index_bits.map(|bit| builder.assert_bool(bit));
+
+        // Interaction
+        receive_node(builder, local);
+        receive_parameter(builder, local);
+        send_compression(builder, local);
+        send_parent(builder, local);
+    }
+}
+
+#[inline]
+fn receive_node<AB>(builder: &mut AB, local: &MerklePathCols<AB::Var>)
+where
+    AB: InteractionBuilder<F = F>,
+{
+    builder.push_receive(
+        Bus::MerklePath as usize,
+        [local.sig_idx.into(), local.level.into()]
+            .into_iter()
+            .chain(local.node.map(Into::into))
+            .chain([local.index::<AB>()]),
+        local.is_active,
+    );
+}
+
+#[inline]
+fn receive_parameter<AB>(builder: &mut AB, local: &MerklePathCols<AB::Var>)
+where
+    AB: InteractionBuilder<F = F>,
+{
+    builder.push_receive(
+        Bus::Parameter as usize,
+        iter::once(local.sig_idx).chain(local.parameter),
+        local.is_active,
+    );
+}
+
+#[inline]
+fn send_compression<AB>(builder: &mut AB, local: &MerklePathCols<AB::Var>)
+where
+    AB: InteractionBuilder<F = F>,
+{
+    let is_right = local.index_bits[0];
+    let left = (0..local.node.len()).map(|i| {
+        select::<AB::Expr>(
+            is_right.into(),
+            local.node[i].into(),
+            local.sibling[i].into(),
+        )
+    });
+    let right = (0..local.node.len()).map(|i| {
+        select::<AB::Expr>(
+            is_right.into(),
+            local.sibling[i].into(),
+            local.node[i].into(),
+        )
+    });
+    let tweak = [
+        AB::Expr::from_canonical_u8(TWEAK_MERKLE_TREE_SEP),
+        local.level.into(),
+    ]
+    .into_iter()
+    .chain(local.next_index_be_bytes::<AB>())
+    .tuples()
+    .map(|(lo, hi)| limb_from_bytes(lo, hi));
+    send_keccak_f(
+        builder,
+        padded_preimage(
+            local
+                .parameter
+                .map(Into::into)
+                .into_iter()
+                .chain(tweak)
+                .chain(left)
+                .chain(right),
+            [],
+        ),
+        &local.output,
+        local.is_active,
+    );
+}
+
+#[inline]
+fn send_parent<AB>(builder: &mut AB, local: &MerklePathCols<AB::Var>)
+where
+    AB: InteractionBuilder<F = F>,
+{
+    builder.push_send(
+        Bus::MerklePath as usize,
+        [local.sig_idx.into(), local.level + AB::Expr::ONE]
+            .into_iter()
+            .chain(digest(&local.output).map(Into::into))
+            .chain([local.next_index::<AB>()]),
+        local.is_active,
+    );
+}
@@ -0,0 +1,82 @@
+use_instance!({
+    chip::from_bits,
+    hash_sig::{HASH_LIMBS, LOG_LIFETIME, PARAM_LIMBS, STATE_LIMBS},
+});
+use crate::util::AlignBorrow;
+use core::{
+    array::from_fn,
+    borrow::{Borrow, BorrowMut},
+};
+use p3_air::AirBuilder;
+
+pub const NUM_MERKLE_PATH_COLS: usize = size_of::<MerklePathCols<u8>>();
+
+#[repr(C)]
+pub struct MerklePathCols<T> {
+    /// Whether this row is a level or padding.
+    pub is_active: T,
+    /// Signature index.
+    pub sig_idx: T,
+    /// Level of the parent node, in range `1..=LOG_LIFETIME`.
+    pub level: T,
+    /// Index of `node` in its level in little-endian bits.
+    pub index_bits: [T; LOG_LIFETIME],
+    pub parameter: [T; PARAM_LIMBS],
+    pub node: [T; HASH_LIMBS],
+    pub sibling: [T; HASH_LIMBS],
+    /// Output state of the permutation.
+    pub output: [T; STATE_LIMBS],
+}
+
+impl<T: Copy> MerklePathCols<T> {
+    #[inline]
+    pub fn index<AB: AirBuilder>(&self) -> AB::Expr
+    where
+        T: Into<AB::Expr>,
+    {
+        from_bits(self.index_bits)
+    }
+
+    /// Returns index of the parent node.
+    #[inline]
+    pub fn next_index<AB: AirBuilder>(&self) -> AB::Expr
+    where
+        T: Into<AB::Expr>,
+    {
+        from_bits(self.index_bits[1..].iter().copied())
+    }
+
+    /// Returns big-endian bytes of index of the parent node.
+    #[inline]
+    pub fn next_index_be_bytes<AB: AirBuilder>(&self) -> [AB::Expr; 4]
+    where
+        T: Into<AB::Expr>,
+    {
+        from_fn(|k| {
+            let start = 1 + 8 * (3 - k);
+            from_bits(
+                self.index_bits[start.min(LOG_LIFETIME)..(start + 8).min(LOG_LIFETIME)]
+                    .iter()
+                    .copied(),
+            )
+        })
+    }
+}
+
+impl<T> AlignBorrow<T> for MerklePathCols<T> {
+    const SIZE: usize = NUM_MERKLE_PATH_COLS;
+}
+
+impl<T> Borrow<MerklePathCols<T>> for [T] {
+    #[inline]
+    fn borrow(&self) -> &MerklePathCols<T> {
+        MerklePathCols::align_borrow(self)
+    }
+}
+
+impl<T> BorrowMut<MerklePathCols<T>> for [T] {
+    #[inline]
+    fn borrow_mut(&mut self) -> &mut MerklePathCols<T> {
+        MerklePathCols::align_borrow_mut(self)
+    }
+}
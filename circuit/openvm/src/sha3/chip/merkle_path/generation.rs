@@ -0,0 +1,101 @@
+use_instance!({
+    chip::{
+        limbs,
+        merkle_path::column::{MerklePathCols, NUM_MERKLE_PATH_COLS},
+    },
+    hash_sig::{VerificationTrace, HASH_LEN, LOG_LIFETIME, MERKLE_LEAF_BLOCKS},
+    F,
+});
+use crate::util::{
+    field::{MaybeUninitField, MaybeUninitFieldSlice},
+    par_zip, zip,
+};
+use core::{iter, mem::MaybeUninit};
+use p3_field::FieldAlgebra;
+use p3_matrix::dense::{RowMajorMatrix, RowMajorMatrixViewMut};
+use p3_maybe_rayon::prelude::*;
+
+pub const fn trace_height(traces: &[VerificationTrace]) -> usize {
+    (traces.len() * LOG_LIFETIME).next_power_of_two()
+}
+
+pub fn generate_trace_rows(
+    extra_capacity_bits: usize,
+    epoch: u32,
+    traces: &[VerificationTrace],
+) -> RowMajorMatrix<F> {
+    let height = trace_height(traces);
+    let size = height * NUM_MERKLE_PATH_COLS;
+    let mut vec = Vec::with_capacity(size << extra_capacity_bits);
+    let trace = &mut vec.spare_capacity_mut()[..size];
+    let trace = RowMajorMatrixViewMut::new(trace, NUM_MERKLE_PATH_COLS);
+
+    let (prefix, rows, suffix) = unsafe {
+        trace
+            .values
+            .align_to_mut::<MerklePathCols<MaybeUninit<_>>>()
+    };
+    assert!(prefix.is_empty(), "Alignment should match");
+    assert!(suffix.is_empty(), "Alignment should match");
+    assert_eq!(rows.len(), height);
+
+    let (rows, padding_rows) = rows.split_at_mut(traces.len() * LOG_LIFETIME);
+
+    join(
+        || {
+            par_zip!(rows.par_chunks_mut(LOG_LIFETIME), traces)
+                .enumerate()
+                .for_each(|(sig_idx, (rows, trace))| {
+                    generate_trace_rows_sig(rows, epoch, sig_idx, trace);
+                });
+        },
+        || {
+            padding_rows
+                .par_iter_mut()
+                .for_each(generate_trace_row_padding);
+        },
+    );
+
+    unsafe { vec.set_len(size) };
+
+    RowMajorMatrix::new(vec, NUM_MERKLE_PATH_COLS)
+}
+
+#[inline]
+fn generate_trace_rows_sig(
+    rows: &mut [MerklePathCols<MaybeUninit<F>>],
+    epoch: u32,
+    sig_idx: usize,
+    trace: &VerificationTrace,
+) {
+    let nodes = iter::once(&trace.merkle_leaf[MERKLE_LEAF_BLOCKS - 1])
+        .chain(&trace.merkle_path[..LOG_LIFETIME - 1])
+        .map(|perm| perm.digest::<HASH_LEN>());
+    zip!(rows, nodes, &trace.sig.merkle_siblings, &trace.merkle_path)
+        .enumerate()
+        .for_each(|(idx, (row, node, sibling, perm))| {
+            let index = epoch >> idx;
+            row.is_active.write_one();
+            row.sig_idx.write_usize(sig_idx);
+            row.level.write_usize(idx + 1);
+            row.index_bits
+                .fill_from_iter((0..LOG_LIFETIME).map(|t| F::from_bool((index >> t) & 1 == 1)));
+            row.parameter.fill_from_iter(limbs(&trace.pk.parameter));
+            row.node.fill_from_iter(limbs(&node));
+            row.sibling.fill_from_iter(limbs(sibling));
+            row.output
+                .fill_from_iter(perm.output_limbs().map(F::from_canonical_u16));
+        });
+}
+
+#[inline]
+fn generate_trace_row_padding(row: &mut MerklePathCols<MaybeUninit<F>>) {
+    row.is_active.write_zero();
+    row.sig_idx.write_zero();
+    row.level.write_zero();
+    row.index_bits.fill_zero();
+    row.parameter.fill_zero();
+    row.node.fill_zero();
+    row.sibling.fill_zero();
+    row.output.fill_zero();
+}
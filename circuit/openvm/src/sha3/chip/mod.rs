@@ -0,0 +1,266 @@
+use super::{
+    chip::main::MainChip,
+    hash_sig::{
+        VerificationInput, VerificationTrace, HASH_LIMBS, LIMB_BITS, MSG_LEN, PARAM_LEN, RATE,
+        STATE_LIMBS, TWEAK_MERKLE_TREE_LEN,
+    },
+    F, PADDING_DOMAIN,
+};
+use crate::util::validation::{validate, ValidationReport};
+use chain::{ChainAir, ChainChip};
+use core::{array::from_fn, iter::zip};
+use itertools::Itertools;
+use keccak::{KeccakFAir, KeccakFChip};
+use main::MainAir;
+use merkle_leaf::{MerkleLeafAir, MerkleLeafChip};
+use merkle_path::{MerklePathAir, MerklePathChip};
+use openvm_stark_backend::{
+    config::{Domain, StarkGenericConfig},
+    interaction::InteractionBuilder,
+    prover::types::AirProofInput,
+    AirRef, Chip,
+};
+use p3_commit::PolynomialSpace;
+use p3_field::FieldAlgebra;
+use p3_maybe_rayon::prelude::*;
+use std::sync::Arc;
+use tracing::instrument;
+
+pub mod chain;
+pub mod keccak;
+pub mod main;
+pub mod merkle_leaf;
+pub mod merkle_path;
+
+/// Limb offset of one-time public keys in the Merkle leaf preimage.
+const ONE_TIME_PK_OFFSET: usize = {
+    assert!((PARAM_LEN + TWEAK_MERKLE_TREE_LEN) % 2 == 0);
+    (PARAM_LEN + TWEAK_MERKLE_TREE_LEN) / 2
+};
+
+#[repr(u8)]
+pub enum Bus {
+    KeccakF,
+    Chain,
+    OneTimePk,
+    Parameter,
+    MerkleLeafState,
+    MerklePath,
+}
+
+/// Returns AIRs in the same order as [`generate_air_proof_inputs`].
+///
+/// AIRs don't depend on witness, so this can be used for keygen without
+/// generating traces.
+pub fn airs<SC: StarkGenericConfig>() -> Vec<AirRef<SC>>
+where
+    Domain<SC>: PolynomialSpace<Val = F>,
+{
+    vec![
+        Arc::new(MainAir),
+        Arc::new(ChainAir),
+        Arc::new(MerkleLeafAir),
+        Arc::new(MerklePathAir),
+        Arc::new(KeccakFAir),
+    ]
+}
+
+/// Returns public values of AIRs in the same order as [`airs`], for aggregation
+/// of signatures on `msg` at `epoch`.
+pub fn public_values(epoch: u32, msg: [u8; MSG_LEN]) -> Vec<Vec<F>> {
+    vec![
+        main::public_values(epoch, msg),
+        chain::public_values(epoch),
+        merkle_leaf::public_values(epoch),
+        Vec::new(),
+        Vec::new(),
+    ]
+}
+
+/// Same as [`generate_air_proof_inputs`], but returns report of invalid
+/// signatures instead of an unprovable witness.
+pub fn try_generate_air_proof_inputs<SC: StarkGenericConfig>(
+    extra_capacity_bits: usize,
+    vi: VerificationInput,
+) -> Result<(Vec<AirRef<SC>>, Vec<AirProofInput<SC>>), ValidationReport>
+where
+    Domain<SC>: PolynomialSpace<Val = F>,
+{
+    validate(&vi, &vec![true; vi.pairs.len()])?;
+    Ok(generate_air_proof_inputs(extra_capacity_bits, vi))
+}
+
+/// Generates AIRs and their proof inputs, proving validity of all signatures.
+///
+/// Unlike the Poseidon2 instantiation, every signature is participating and
+/// public keys aren't bound to a committed signer set, so participation
+/// bitmap, threshold and signer set commitment are out of scope of this
+/// instantiation.
+#[instrument(name = "generate hash-sig aggregation traces", skip_all)]
+pub fn generate_air_proof_inputs<SC: StarkGenericConfig>(
+    extra_capacity_bits: usize,
+    vi: VerificationInput,
+) -> (Vec<AirRef<SC>>, Vec<AirProofInput<SC>>)
+where
+    Domain<SC>: PolynomialSpace<Val = F>,
+{
+    let traces = vi
+        .pairs
+        .into_par_iter()
+        .map(|(pk, sig)| VerificationTrace::generate(vi.epoch, vi.msg, pk, sig))
+        .collect::<Vec<_>>();
+    let main = MainChip::new(extra_capacity_bits, vi.epoch, vi.msg, &traces);
+    let chain = ChainChip::new(extra_capacity_bits, vi.epoch, &traces);
+    let merkle_leaf = MerkleLeafChip::new(extra_capacity_bits, vi.epoch, &traces);
+    let merkle_path = MerklePathChip::new(extra_capacity_bits, vi.epoch, &traces);
+    let keccak = KeccakFChip::new(extra_capacity_bits, &traces);
+    let ((main_api, chain_api), ((merkle_leaf_api, merkle_path_api), keccak_api)) = join(
+        || {
+            join(
+                || main.generate_air_proof_input(),
+                || chain.generate_air_proof_input(),
+            )
+        },
+        || {
+            join(
+                || {
+                    join(
+                        || merkle_leaf.generate_air_proof_input(),
+                        || merkle_path.generate_air_proof_input(),
+                    )
+                },
+                || keccak.generate_air_proof_input(),
+            )
+        },
+    );
+    (
+        airs(),
+        vec![
+            main_api,
+            chain_api,
+            merkle_leaf_api,
+            merkle_path_api,
+            keccak_api,
+        ],
+    )
+}
+
+/// Returns little-endian composition of `bits`.
+#[inline]
+fn from_bits<E: FieldAlgebra>(bits: impl IntoIterator<Item: Into<E>>) -> E {
+    zip(bits, 0..)
+        .map(|(bit, t)| bit.into() * E::from_canonical_u32(1 << t))
+        .sum()
+}
+
+/// Returns bytes composed from little-endian `bits`.
+#[inline]
+fn bytes_from_bits<E: FieldAlgebra, const N: usize>(bits: &[impl Copy + Into<E>]) -> [E; N] {
+    debug_assert_eq!(bits.len(), 8 * N);
+    from_fn(|i| from_bits(bits[8 * i..][..8].iter().copied()))
+}
+
+/// Returns limbs composed from little-endian `bits`.
+#[inline]
+fn limbs_from_bits<E: FieldAlgebra, const N: usize>(bits: &[impl Copy + Into<E>]) -> [E; N] {
+    debug_assert_eq!(bits.len(), LIMB_BITS * N);
+    from_fn(|i| from_bits(bits[LIMB_BITS * i..][..LIMB_BITS].iter().copied()))
+}
+
+/// Returns limb packed from 2 bytes in little-endian.
+#[inline]
+fn limb_from_bytes<E: FieldAlgebra>(lo: E, hi: E) -> E {
+    lo + hi * E::from_canonical_u32(1 << 8)
+}
+
+/// Returns input state of absorbing single block preimage, which consists of
+/// `limbs` followed by `bytes`.
+#[inline]
+fn padded_preimage<E: FieldAlgebra>(
+    limbs: impl IntoIterator<Item = E>,
+    bytes: impl IntoIterator<Item = E>,
+) -> [E; STATE_LIMBS] {
+    let mut limbs = limbs.into_iter().collect_vec();
+    let mut bytes = bytes.into_iter().collect_vec();
+    assert!(2 * limbs.len() + bytes.len() < RATE);
+    bytes.push(E::from_canonical_u8(PADDING_DOMAIN));
+    bytes.resize(RATE - 2 * limbs.len() - 1, E::ZERO);
+    bytes.push(E::from_canonical_u8(0x80));
+    limbs.extend(
+        bytes
+            .into_iter()
+            .tuples()
+            .map(|(lo, hi)| limb_from_bytes(lo, hi)),
+    );
+    limbs.resize(STATE_LIMBS, E::ZERO);
+    limbs.try_into().unwrap()
+}
+
+/// Sends a Keccak-f permutation from `input` to `output` to the
+/// [`KeccakFAir`].
+#[inline]
+fn send_keccak_f<AB>(
+    builder: &mut AB,
+    input: [impl Into<AB::Expr>; STATE_LIMBS],
+    output: &[AB::Var; STATE_LIMBS],
+    count: impl Into<AB::Expr>,
+) where
+    AB: InteractionBuilder<F = F>,
+{
+    builder.push_send(
+        Bus::KeccakF as usize,
+        input
+            .into_iter()
+            .map(Into::into)
+            .chain(output.iter().copied().map_into()),
+        count,
+    );
+}
+
+/// Returns digest limbs of Keccak-f `output`.
+#[inline]
+fn digest<T: Copy>(output: &[T; STATE_LIMBS]) -> [T; HASH_LIMBS] {
+    from_fn(|i| output[i])
+}
+
+/// Returns little-endian bits of `bytes`.
+#[inline]
+fn bits(bytes: &[u8]) -> impl Iterator<Item = F> + '_ {
+    bytes
+        .iter()
+        .flat_map(|byte| (0..8).map(move |t| F::from_bool((byte >> t) & 1 == 1)))
+}
+
+/// Returns little-endian limbs of `bytes`.
+#[inline]
+fn limbs(bytes: &[u8]) -> impl Iterator<Item = F> + '_ {
+    bytes
+        .chunks(2)
+        .map(|bytes| F::from_canonical_u16(u16::from_le_bytes([bytes[0], bytes[1]])))
+}
+
+#[cfg(test)]
+mod test {
+    use_instance!({
+        chip::{generate_air_proof_inputs, public_values},
+        hash_sig::test::mock_vi,
+        E, F,
+    });
+    use crate::util::engine::Engine;
+    use core::iter::zip;
+    use openvm_stark_sdk::engine::StarkEngine;
+
+    #[test]
+    fn chip() {
+        let engine = Engine::<F, E>::fastest();
+        for num_sigs in [1, 3] {
+            let vi = mock_vi(num_sigs);
+            let public_values = public_values(vi.epoch, vi.msg);
+            let (airs, air_proof_inputs) = generate_air_proof_inputs(engine.log_blowup(), vi);
+            zip(&air_proof_inputs, public_values).for_each(|(input, public_values)| {
+                assert_eq!(input.raw.public_values, public_values);
+            });
+            engine.run_test_impl(airs, air_proof_inputs).unwrap();
+        }
+    }
+}
@@ -0,0 +1,263 @@
+use super::{Digest, PADDING_DOMAIN};
+use core::array::from_fn;
+use hash_sig_verifier::{concat_array, instantiation};
+use p3_keccak::KeccakF;
+use p3_maybe_rayon::prelude::*;
+use p3_symmetric::Permutation;
+
+pub use hash_sig_verifier::{
+    instantiation::sha3::{
+        encode_tweak_chain, encode_tweak_merkle_tree, encode_tweak_msg, msg_hash_to_chunks,
+        CHUNK_SIZE, HASH_LEN, MSG_HASH_LEN, NUM_CHUNKS, PARAM_LEN, RHO_LEN, TARGET_SUM,
+        TWEAK_CHAIN_LEN, TWEAK_MERKLE_TREE_LEN, TWEAK_MSG_LEN,
+    },
+    LOG_LIFETIME, MSG_LEN,
+};
+
+pub type Sha3TargetSum = instantiation::sha3::Sha3TargetSum<Digest>;
+
+pub type Signature = hash_sig_verifier::Signature<Sha3TargetSum, NUM_CHUNKS>;
+
+pub type PublicKey = hash_sig_verifier::PublicKey<Sha3TargetSum, NUM_CHUNKS>;
+
+pub type VerificationInput = hash_sig_verifier::VerificationInput<Sha3TargetSum, NUM_CHUNKS>;
+
+/// Rate of Keccak sponge in bytes.
+pub const RATE: usize = 136;
+pub const LIMB_BITS: usize = 16;
+pub const STATE_LIMBS: usize = 100;
+pub const RATE_LIMBS: usize = RATE / 2;
+pub const CAPACITY_LIMBS: usize = STATE_LIMBS - RATE_LIMBS;
+pub const PARAM_LIMBS: usize = PARAM_LEN / 2;
+pub const HASH_LIMBS: usize = HASH_LEN / 2;
+pub const MSG_HASH_LIMBS: usize = MSG_HASH_LEN / 2;
+/// Number of hashes from one-time signature to one-time public key.
+pub const CHAIN_LEN: usize = (1 << CHUNK_SIZE) - 1;
+
+pub const MSG_HASH_INPUT_LEN: usize = RHO_LEN + PARAM_LEN + TWEAK_MSG_LEN + MSG_LEN;
+pub const CHAIN_INPUT_LEN: usize = PARAM_LEN + TWEAK_CHAIN_LEN + HASH_LEN;
+pub const MERKLE_LEAF_LEN: usize = PARAM_LEN + TWEAK_MERKLE_TREE_LEN + NUM_CHUNKS * HASH_LEN;
+pub const MERKLE_PATH_INPUT_LEN: usize = PARAM_LEN + TWEAK_MERKLE_TREE_LEN + 2 * HASH_LEN;
+/// Separators of tweaks, see [`encode_tweak_chain`], [`encode_tweak_merkle_tree`]
+/// and [`encode_tweak_msg`].
+pub const TWEAK_CHAIN_SEP: u8 = 0x00;
+pub const TWEAK_MERKLE_TREE_SEP: u8 = 0x01;
+pub const TWEAK_MSG_SEP: u8 = 0x02;
+/// Number of absorbed blocks of Merkle leaf, padding takes at least one byte.
+pub const MERKLE_LEAF_BLOCKS: usize = MERKLE_LEAF_LEN / RATE + 1;
+
+/// Input and output of a Keccak-f permutation.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct KeccakPerm {
+    pub input: [u64; 25],
+    pub output: [u64; 25],
+}
+
+impl KeccakPerm {
+    pub fn new(input: [u64; 25]) -> Self {
+        Self {
+            input,
+            output: KeccakF.permute(input),
+        }
+    }
+
+    pub fn input_limbs(&self) -> [u16; STATE_LIMBS] {
+        state_limbs(&self.input)
+    }
+
+    pub fn output_limbs(&self) -> [u16; STATE_LIMBS] {
+        state_limbs(&self.output)
+    }
+
+    pub fn digest<const N: usize>(&self) -> [u8; N] {
+        from_fn(|i| (self.output[i / 8] >> (8 * (i % 8))) as u8)
+    }
+}
+
+/// Returns little-endian limbs of `state`, lane by lane.
+pub fn state_limbs(state: &[u64; 25]) -> [u16; STATE_LIMBS] {
+    from_fn(|i| (state[i / 4] >> (LIMB_BITS * (i % 4))) as u16)
+}
+
+/// Returns `bytes` padded to multiple of [`RATE`].
+pub fn pad(bytes: &[u8]) -> Vec<u8> {
+    let mut padded = bytes.to_vec();
+    padded.push(PADDING_DOMAIN);
+    padded.resize(padded.len().next_multiple_of(RATE), 0);
+    *padded.last_mut().unwrap() |= 0x80;
+    padded
+}
+
+/// Returns permutations of absorbing `bytes` into Keccak sponge.
+pub fn sponge(bytes: &[u8]) -> Vec<KeccakPerm> {
+    pad(bytes)
+        .chunks(RATE)
+        .scan([0; 25], |state: &mut [u64; 25], block| {
+            block.chunks(8).enumerate().for_each(|(i, lane)| {
+                state[i] ^= u64::from_le_bytes(lane.try_into().unwrap());
+            });
+            let perm = KeccakPerm::new(*state);
+            *state = perm.output;
+            Some(perm)
+        })
+        .collect()
+}
+
+/// Returns permutation of absorbing `bytes` shorter than [`RATE`].
+pub fn sponge_single_block<const N: usize>(bytes: [u8; N]) -> KeccakPerm {
+    const { assert!(N < RATE) }
+    sponge(&bytes)[0]
+}
+
+#[derive(Clone, Debug)]
+pub struct VerificationTrace {
+    pub pk: PublicKey,
+    pub sig: Signature,
+    pub msg_hash: KeccakPerm,
+    pub x: [u16; NUM_CHUNKS],
+    /// Input of each step of each chain, followed by the one-time public key.
+    pub chain_values: [[[u8; HASH_LEN]; CHAIN_LEN + 1]; NUM_CHUNKS],
+    /// Permutation of each step of each chain, or `None` if the step is
+    /// skipped because it's not greater than `x_i`.
+    pub chain_perms: [[Option<KeccakPerm>; CHAIN_LEN]; NUM_CHUNKS],
+    pub merkle_leaf: [KeccakPerm; MERKLE_LEAF_BLOCKS],
+    pub merkle_path: [KeccakPerm; LOG_LIFETIME],
+}
+
+impl VerificationTrace {
+    pub fn generate(epoch: u32, msg: [u8; MSG_LEN], pk: PublicKey, sig: Signature) -> Self {
+        let msg_hash = sponge_single_block::<MSG_HASH_INPUT_LEN>(concat_array![
+            sig.rho,
+            pk.parameter,
+            encode_tweak_msg(epoch),
+            msg,
+        ]);
+        let x = msg_hash_to_chunks(msg_hash.digest());
+        let (chain_values, chain_perms) = (0..NUM_CHUNKS)
+            .into_par_iter()
+            .map(|i| chain(epoch, pk.parameter, i as _, x[i], sig.one_time_sig[i]))
+            .unzip::<_, _, Vec<_>, Vec<_>>();
+        let chain_values: [_; NUM_CHUNKS] = chain_values.try_into().unwrap();
+        let merkle_leaf = sponge(&merkle_leaf_input(epoch, pk.parameter, &chain_values))
+            .try_into()
+            .unwrap();
+        let merkle_path = merkle_path(epoch, pk.parameter, &merkle_leaf, sig.merkle_siblings);
+        debug_assert_eq!(
+            merkle_path[LOG_LIFETIME - 1].digest::<HASH_LEN>(),
+            pk.merkle_root
+        );
+        Self {
+            pk,
+            sig,
+            msg_hash,
+            x,
+            chain_values,
+            chain_perms: chain_perms.try_into().unwrap(),
+            merkle_leaf,
+            merkle_path,
+        }
+    }
+
+    pub fn one_time_pk(&self, i: usize) -> [u8; HASH_LEN] {
+        self.chain_values[i][CHAIN_LEN]
+    }
+
+    pub fn merkle_leaf_input(&self, epoch: u32) -> [u8; MERKLE_LEAF_LEN] {
+        merkle_leaf_input(epoch, self.pk.parameter, &self.chain_values)
+    }
+
+    /// Returns all permutations of this signature.
+    pub fn keccak_perms(&self) -> impl Iterator<Item = &KeccakPerm> {
+        [&self.msg_hash]
+            .into_iter()
+            .chain(self.chain_perms.iter().flatten().flatten())
+            .chain(&self.merkle_leaf)
+            .chain(&self.merkle_path)
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn chain(
+    epoch: u32,
+    parameter: [u8; PARAM_LEN],
+    i: u16,
+    x_i: u16,
+    one_time_sig_i: [u8; HASH_LEN],
+) -> (
+    [[u8; HASH_LEN]; CHAIN_LEN + 1],
+    [Option<KeccakPerm>; CHAIN_LEN],
+) {
+    let mut values = [one_time_sig_i; CHAIN_LEN + 1];
+    let mut perms = [None; CHAIN_LEN];
+    (1..=CHAIN_LEN as u16).for_each(|k| {
+        let value = values[k as usize - 1];
+        values[k as usize] = if k > x_i {
+            let perm = sponge_single_block::<CHAIN_INPUT_LEN>(concat_array![
+                parameter,
+                encode_tweak_chain(epoch, i, k),
+                value,
+            ]);
+            perms[k as usize - 1] = Some(perm);
+            perm.digest()
+        } else {
+            value
+        };
+    });
+    (values, perms)
+}
+
+fn merkle_leaf_input(
+    epoch: u32,
+    parameter: [u8; PARAM_LEN],
+    chain_values: &[[[u8; HASH_LEN]; CHAIN_LEN + 1]; NUM_CHUNKS],
+) -> [u8; MERKLE_LEAF_LEN] {
+    concat_array![
+        parameter,
+        encode_tweak_merkle_tree(0, epoch),
+        chain_values.iter().flat_map(|values| values[CHAIN_LEN]),
+    ]
+}
+
+fn merkle_path(
+    epoch: u32,
+    parameter: [u8; PARAM_LEN],
+    merkle_leaf: &[KeccakPerm; MERKLE_LEAF_BLOCKS],
+    merkle_siblings: [[u8; HASH_LEN]; LOG_LIFETIME],
+) -> [KeccakPerm; LOG_LIFETIME] {
+    let mut node = merkle_leaf[MERKLE_LEAF_BLOCKS - 1].digest();
+    from_fn(|idx| {
+        let level = idx + 1;
+        let [left, right] = if (epoch >> idx) & 1 == 0 {
+            [node, merkle_siblings[idx]]
+        } else {
+            [merkle_siblings[idx], node]
+        };
+        let perm = sponge_single_block::<MERKLE_PATH_INPUT_LEN>(concat_array![
+            parameter,
+            encode_tweak_merkle_tree(level as u8, epoch >> level),
+            left,
+            right,
+        ]);
+        node = perm.digest();
+        perm
+    })
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::{
+        encode_tweak_chain, encode_tweak_merkle_tree, encode_tweak_msg, VerificationInput,
+        TWEAK_CHAIN_SEP, TWEAK_MERKLE_TREE_SEP, TWEAK_MSG_SEP,
+    };
+
+    pub fn mock_vi(size: usize) -> VerificationInput {
+        hash_sig_testdata::mock_vi(size)
+    }
+
+    #[test]
+    fn tweak_sep() {
+        assert_eq!(encode_tweak_chain(0, 0, 0)[0], TWEAK_CHAIN_SEP);
+        assert_eq!(encode_tweak_merkle_tree(0, 0)[0], TWEAK_MERKLE_TREE_SEP);
+        assert_eq!(encode_tweak_msg(0)[0], TWEAK_MSG_SEP);
+    }
+}
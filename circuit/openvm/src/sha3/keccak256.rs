@@ -0,0 +1,20 @@
+/// Imports items by their path from the root of this instantiation, so the
+/// shared source included below doesn't depend on its own depth.
+macro_rules! use_instance {
+    ($($path:tt)*) => {
+        use $crate::sha3::keccak256::$($path)*;
+    };
+}
+
+#[path = "chip/mod.rs"]
+pub mod chip;
+#[path = "hash_sig.rs"]
+pub mod hash_sig;
+
+pub use hash_sig_verifier::instantiation::sha3::Keccak256 as Digest;
+pub use p3_koala_bear::KoalaBear as F;
+/// Challenge field, whose degree is high enough to reach `SECURITY_BITS`, see
+/// `util::security`.
+pub type E = p3_field::extension::BinomialExtensionField<F, 8>;
+/// First byte of the padding, the last byte of it is always `0x80`.
+pub const PADDING_DOMAIN: u8 = 0x01;
@@ -0,0 +1,11 @@
+//! Circuits of `Sha3TargetSum` over KoalaBear, built for both Keccak256 and
+//! SHA3-256 side by side, sharing the same source of `chip` and `hash_sig` but
+//! instantiated with digest specific padding in [`keccak256`] and
+//! [`sha3_256`] respectively.
+//!
+//! Keccak-f state is represented as 100 little-endian limbs of 16 bits, which
+//! fits any 31-bit field, so bytes of a preimage at position `2 * j` and
+//! `2 * j + 1` are packed into limb `j`.
+
+pub mod keccak256;
+pub mod sha3_256;
@@ -1,29 +1,138 @@
-use openvm_stark_backend::{config::StarkConfig, interaction::fri_log_up::FriLogUpPhase};
+use crate::util::security::{num_queries, InsufficientSecurity, SecurityReport};
+use openvm_stark_backend::{
+    config::{StarkConfig, StarkGenericConfig},
+    interaction::fri_log_up::FriLogUpPhase,
+    keygen::types::MultiStarkVerifyingKey,
+    proof::Proof,
+    verifier::{MultiTraceStarkVerifier, VerificationError},
+};
 use openvm_stark_sdk::engine::StarkEngine;
-use p3_challenger::{HashChallenger, SerializingChallenger32};
+use p3_baby_bear::BabyBear;
+use p3_challenger::{DuplexChallenger, HashChallenger, SerializingChallenger32};
 use p3_commit::ExtensionMmcs;
 use p3_dft::Radix2DitParallel;
-use p3_field::{ExtensionField, PrimeField32, TwoAdicField};
+use p3_field::{ExtensionField, Field, PrimeField32, TwoAdicField};
 use p3_fri::{FriConfig, TwoAdicFriPcs};
 use p3_keccak::{Keccak256Hash, KeccakF, VECTOR_LEN};
+use p3_koala_bear::KoalaBear;
 use p3_merkle_tree::MerkleTreeMmcs;
-use p3_symmetric::{CompressionFunctionFromHasher, PaddingFreeSponge, SerializingHasher32To64};
+use p3_poseidon2_util::instantiation::horizon::{
+    baby_bear::{poseidon2_baby_bear_horizon_t16, Poseidon2BabyBearHorizon},
+    koala_bear::{poseidon2_koala_bear_horizon_t16, Poseidon2KoalaBearHorizon},
+};
+use p3_symmetric::{
+    CompressionFunctionFromHasher, PaddingFreeSponge, SerializingHasher32To64, TruncatedPermutation,
+};
 
 type U64Hash = PaddingFreeSponge<KeccakF, 25, 17, 4>;
 type FieldHash = SerializingHasher32To64<U64Hash>;
 type Compress = CompressionFunctionFromHasher<U64Hash, 2, 4>;
-type ValMmcs<F> = MerkleTreeMmcs<[F; VECTOR_LEN], [u64; VECTOR_LEN], FieldHash, Compress, 4>;
-type ChallengeMmcs<F, E> = ExtensionMmcs<F, E, ValMmcs<F>>;
+type KeccakValMmcs<F> = MerkleTreeMmcs<[F; VECTOR_LEN], [u64; VECTOR_LEN], FieldHash, Compress, 4>;
 type ByteHash = Keccak256Hash;
-type Challenger<F> = SerializingChallenger32<F, HashChallenger<u8, ByteHash, 32>>;
+type KeccakChallenger<F> = SerializingChallenger32<F, HashChallenger<u8, ByteHash, 32>>;
+
+type Poseidon2Perm<F> = <F as Poseidon2Field>::Poseidon2T16;
+type Poseidon2Sponge<F> = PaddingFreeSponge<Poseidon2Perm<F>, 16, 8, 8>;
+type Poseidon2Compress<F> = TruncatedPermutation<Poseidon2Perm<F>, 2, 8, 16>;
+type Poseidon2ValMmcs<F> = MerkleTreeMmcs<
+    <F as Field>::Packing,
+    <F as Field>::Packing,
+    Poseidon2Sponge<F>,
+    Poseidon2Compress<F>,
+    8,
+>;
+type Poseidon2Challenger<F> = DuplexChallenger<F, Poseidon2Perm<F>, 16, 8>;
+
+type ValMmcs<F, H> = <H as Hash<F>>::ValMmcs;
+type Challenger<F, H> = <H as Hash<F>>::Challenger;
+type ChallengeMmcs<F, E, H> = ExtensionMmcs<F, E, ValMmcs<F, H>>;
 type Dft<F> = Radix2DitParallel<F>;
-type Pcs<F, E> = TwoAdicFriPcs<F, Dft<F>, ValMmcs<F>, ChallengeMmcs<F, E>>;
-type RapPhase<F, E> = FriLogUpPhase<F, E, Challenger<F>>;
-pub type Config<F, E> = StarkConfig<Pcs<F, E>, RapPhase<F, E>, E, Challenger<F>>;
+type Pcs<F, E, H> = TwoAdicFriPcs<F, Dft<F>, ValMmcs<F, H>, ChallengeMmcs<F, E, H>>;
+type RapPhase<F, E, H> = FriLogUpPhase<F, E, Challenger<F, H>>;
+pub type Config<F, E, H = Keccak> =
+    StarkConfig<Pcs<F, E, H>, RapPhase<F, E, H>, E, Challenger<F, H>>;
+
+/// Hash used for Merkle commitments and Fiat-Shamir.
+pub trait Hash<F> {
+    type ValMmcs: Clone;
+    type Challenger;
+
+    fn val_mmcs() -> Self::ValMmcs;
+
+    fn challenger() -> Self::Challenger;
+}
+
+/// Keccak256 for both Merkle commitments and challenger, which is the cheapest
+/// to verify natively.
+#[derive(Clone, Copy, Debug)]
+pub struct Keccak;
+
+impl<F: PrimeField32> Hash<F> for Keccak {
+    type ValMmcs = KeccakValMmcs<F>;
+    type Challenger = KeccakChallenger<F>;
+
+    fn val_mmcs() -> Self::ValMmcs {
+        let u64_hash = U64Hash::new(KeccakF {});
+        let field_hash = FieldHash::new(u64_hash);
+        let compress = Compress::new(u64_hash);
+        KeccakValMmcs::new(field_hash, compress)
+    }
+
+    fn challenger() -> Self::Challenger {
+        KeccakChallenger::from_hasher(vec![], ByteHash {})
+    }
+}
+
+/// Poseidon2 Horizon with width 16 for Merkle commitments and duplex
+/// challenger, which is the cheapest to verify inside another STARK.
+#[derive(Clone, Copy, Debug)]
+pub struct Poseidon2;
+
+/// Field with Poseidon2 Horizon instantiation.
+pub trait Poseidon2Field: PrimeField32 {
+    type Poseidon2T16: Clone;
+
+    fn poseidon2_t16() -> Self::Poseidon2T16;
+}
+
+impl Poseidon2Field for BabyBear {
+    type Poseidon2T16 = Poseidon2BabyBearHorizon<16>;
+
+    fn poseidon2_t16() -> Self::Poseidon2T16 {
+        poseidon2_baby_bear_horizon_t16().clone()
+    }
+}
+
+impl Poseidon2Field for KoalaBear {
+    type Poseidon2T16 = Poseidon2KoalaBearHorizon<16>;
+
+    fn poseidon2_t16() -> Self::Poseidon2T16 {
+        poseidon2_koala_bear_horizon_t16().clone()
+    }
+}
+
+impl<F: Poseidon2Field> Hash<F> for Poseidon2 {
+    type ValMmcs = Poseidon2ValMmcs<F>;
+    type Challenger = Poseidon2Challenger<F>;
+
+    fn val_mmcs() -> Self::ValMmcs {
+        let perm = F::poseidon2_t16();
+        Poseidon2ValMmcs::new(
+            Poseidon2Sponge::new(perm.clone()),
+            Poseidon2Compress::new(perm),
+        )
+    }
+
+    fn challenger() -> Self::Challenger {
+        Poseidon2Challenger::new(F::poseidon2_t16())
+    }
+}
 
-pub struct Engine<F, E> {
-    config: Config<F, E>,
+pub struct Engine<F, E, H: Hash<F> = Keccak> {
+    config: Config<F, E, H>,
     log_blowup: usize,
+    num_queries: usize,
+    proof_of_work_bits: usize,
 }
 
 impl<F, E> Engine<F, E>
@@ -32,47 +141,146 @@ where
     E: ExtensionField<F> + TwoAdicField,
 {
     pub fn new(log_blowup: usize, proof_of_work_bits: usize) -> Self {
-        let u64_hash = U64Hash::new(KeccakF {});
-        let field_hash = FieldHash::new(u64_hash);
-        let compress = Compress::new(u64_hash);
-        let val_mmcs = ValMmcs::new(field_hash, compress);
-        let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
-        let dft = Dft::default();
-        let num_queries = usize::div_ceil(2 * (128 - proof_of_work_bits), log_blowup);
-        let fri_config = FriConfig {
-            log_blowup,
-            log_final_poly_len: 3,
-            num_queries,
-            proof_of_work_bits,
-            mmcs: challenge_mmcs,
-        };
-        let pcs = Pcs::new(dft, val_mmcs, fri_config);
-        let rap_phase = RapPhase::new();
-        Self {
-            config: Config::new(pcs, rap_phase),
-            log_blowup,
-        }
+        Self::with_hash(log_blowup, proof_of_work_bits)
     }
 
     pub fn fastest() -> Self {
         Self::new(1, 0)
     }
+}
+
+impl<F, E, H> Engine<F, E, H>
+where
+    F: PrimeField32 + TwoAdicField,
+    E: ExtensionField<F> + TwoAdicField,
+    H: Hash<F>,
+{
+    pub fn with_hash(log_blowup: usize, proof_of_work_bits: usize) -> Self {
+        let num_queries = num_queries(log_blowup, proof_of_work_bits);
+        Self {
+            config: config(log_blowup, num_queries, proof_of_work_bits),
+            log_blowup,
+            num_queries,
+            proof_of_work_bits,
+        }
+    }
 
     pub const fn log_blowup(&self) -> usize {
         self.log_blowup
     }
+
+    pub const fn num_queries(&self) -> usize {
+        self.num_queries
+    }
+
+    pub const fn proof_of_work_bits(&self) -> usize {
+        self.proof_of_work_bits
+    }
+
+    /// Returns estimated security of proofs with traces of height at most
+    /// `1 << log_max_height`, or an error if it's below `SECURITY_BITS`, e.g.
+    /// when `E` is of too low degree.
+    pub fn security_report(
+        &self,
+        log_max_height: usize,
+    ) -> Result<SecurityReport, InsufficientSecurity> {
+        SecurityReport::new::<F, E>(
+            self.log_blowup,
+            self.num_queries,
+            self.proof_of_work_bits,
+            log_max_height,
+        )
+        .check()
+    }
 }
 
-impl<F, E> StarkEngine<Config<F, E>> for Engine<F, E>
+impl<F, E, H> StarkEngine<Config<F, E, H>> for Engine<F, E, H>
 where
     F: PrimeField32 + TwoAdicField,
     E: ExtensionField<F> + TwoAdicField,
+    H: Hash<F>,
+    Config<F, E, H>: StarkGenericConfig,
 {
-    fn config(&self) -> &Config<F, E> {
+    fn config(&self) -> &Config<F, E, H> {
         &self.config
     }
 
-    fn new_challenger(&self) -> Challenger<F> {
-        Challenger::from_hasher(vec![], ByteHash {})
+    fn new_challenger(&self) -> Challenger<F, H> {
+        H::challenger()
     }
 }
+
+/// Configuration to verify proofs only, which is built from the FRI
+/// parameters a proof is produced with instead of an [`Engine`].
+pub struct VerifierConfig<F, E, H: Hash<F> = Keccak> {
+    config: Config<F, E, H>,
+    log_blowup: usize,
+    num_queries: usize,
+    proof_of_work_bits: usize,
+}
+
+impl<F, E, H> VerifierConfig<F, E, H>
+where
+    F: PrimeField32 + TwoAdicField,
+    E: ExtensionField<F> + TwoAdicField,
+    H: Hash<F>,
+    Config<F, E, H>: StarkGenericConfig<Challenger = Challenger<F, H>>,
+{
+    pub fn new(log_blowup: usize, num_queries: usize, proof_of_work_bits: usize) -> Self {
+        Self {
+            config: config(log_blowup, num_queries, proof_of_work_bits),
+            log_blowup,
+            num_queries,
+            proof_of_work_bits,
+        }
+    }
+
+    /// Returns estimated security of proofs with traces of height at most
+    /// `1 << log_max_height`, or an error if it's below `SECURITY_BITS`.
+    pub fn security_report(
+        &self,
+        log_max_height: usize,
+    ) -> Result<SecurityReport, InsufficientSecurity> {
+        SecurityReport::new::<F, E>(
+            self.log_blowup,
+            self.num_queries,
+            self.proof_of_work_bits,
+            log_max_height,
+        )
+        .check()
+    }
+
+    pub fn verify(
+        &self,
+        vk: &MultiStarkVerifyingKey<Config<F, E, H>>,
+        proof: &Proof<Config<F, E, H>>,
+    ) -> Result<(), VerificationError> {
+        let mut challenger = H::challenger();
+        MultiTraceStarkVerifier::new(&self.config).verify(&mut challenger, vk, proof)
+    }
+}
+
+fn config<F, E, H>(
+    log_blowup: usize,
+    num_queries: usize,
+    proof_of_work_bits: usize,
+) -> Config<F, E, H>
+where
+    F: PrimeField32 + TwoAdicField,
+    E: ExtensionField<F> + TwoAdicField,
+    H: Hash<F>,
+{
+    let val_mmcs = H::val_mmcs();
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let dft = Dft::default();
+    let fri_config = FriConfig {
+        log_blowup,
+        log_final_poly_len: 3,
+        num_queries,
+        proof_of_work_bits,
+        mmcs: challenge_mmcs,
+    };
+    let pcs = Pcs::new(dft, val_mmcs, fri_config);
+    let rap_phase = RapPhase::new();
+    Config::new(pcs, rap_phase)
+}
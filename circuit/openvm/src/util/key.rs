@@ -0,0 +1,156 @@
+use crate::util::engine::{Engine, Hash};
+use core::{any::type_name, fmt};
+use openvm_stark_backend::{
+    config::StarkGenericConfig,
+    keygen::types::{MultiStarkProvingKey, MultiStarkVerifyingKey},
+    AirRef,
+};
+use openvm_stark_sdk::engine::StarkEngine;
+use p3_field::{ExtensionField, FieldExtensionAlgebra, PrimeField32, TwoAdicField};
+use p3_keccak::Keccak256Hash;
+use p3_symmetric::CryptographicHasher;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::Path,
+};
+
+/// Keccak256 of serialized verifying key, which identifies the AIR set together
+/// with the engine configuration used to generate the key.
+pub type Fingerprint = [u8; 32];
+
+/// Generates proving key of `airs`.
+pub fn keygen<SC: StarkGenericConfig>(
+    engine: &impl StarkEngine<SC>,
+    airs: &[AirRef<SC>],
+) -> MultiStarkProvingKey<SC> {
+    let mut keygen_builder = engine.keygen_builder();
+    engine.set_up_keygen_builder(&mut keygen_builder, airs);
+    keygen_builder.generate_pk()
+}
+
+pub fn fingerprint<SC: StarkGenericConfig>(vk: &MultiStarkVerifyingKey<SC>) -> Fingerprint
+where
+    MultiStarkVerifyingKey<SC>: Serialize,
+{
+    Keccak256Hash.hash_iter(bincode::serialize(vk).unwrap())
+}
+
+pub fn save<T: Serialize>(path: impl AsRef<Path>, value: &T) -> bincode::Result<()> {
+    bincode::serialize_into(BufWriter::new(File::create(path)?), value)
+}
+
+pub fn load<T: DeserializeOwned>(path: impl AsRef<Path>) -> bincode::Result<T> {
+    bincode::deserialize_from(BufReader::new(File::open(path)?))
+}
+
+/// Parameters a proving key is generated with, which are saved together with
+/// the key by [`save_pk`] and compared by [`load_pk`], so a stale key isn't
+/// used to produce unverifiable proofs.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyParameters {
+    pub modulus: u32,
+    pub extension_degree: usize,
+    pub hash: String,
+    pub log_blowup: usize,
+    pub num_queries: usize,
+    pub proof_of_work_bits: usize,
+    /// Description of the AIR set, e.g. instantiation, number of signatures
+    /// and layout, which should change whenever the AIRs do.
+    pub airs: String,
+}
+
+impl KeyParameters {
+    pub fn new<F, E, H>(engine: &Engine<F, E, H>, airs: impl Into<String>) -> Self
+    where
+        F: PrimeField32 + TwoAdicField,
+        E: ExtensionField<F> + TwoAdicField,
+        H: Hash<F>,
+    {
+        Self {
+            modulus: F::ORDER_U32,
+            extension_degree: <E as FieldExtensionAlgebra<F>>::D,
+            hash: type_name::<H>().to_string(),
+            log_blowup: engine.log_blowup(),
+            num_queries: engine.num_queries(),
+            proof_of_work_bits: engine.proof_of_work_bits(),
+            airs: airs.into(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum LoadError {
+    Bincode(bincode::Error),
+    /// Key is generated with other parameters.
+    ParametersMismatch {
+        expected: Box<KeyParameters>,
+        found: Box<KeyParameters>,
+    },
+    /// Fingerprint of the loaded verifying key differs from the saved one.
+    FingerprintMismatch,
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bincode(err) => write!(f, "malformed proving key: {err}"),
+            Self::ParametersMismatch { expected, found } => write!(
+                f,
+                "proving key is generated with {found:?}, expected {expected:?}"
+            ),
+            Self::FingerprintMismatch => write!(f, "verifying key fingerprint mismatch"),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+impl From<bincode::Error> for LoadError {
+    fn from(err: bincode::Error) -> Self {
+        Self::Bincode(err)
+    }
+}
+
+/// Saves `pk` prefixed by `params` and fingerprint of its verifying key.
+pub fn save_pk<SC: StarkGenericConfig>(
+    path: impl AsRef<Path>,
+    params: &KeyParameters,
+    pk: &MultiStarkProvingKey<SC>,
+) -> bincode::Result<()>
+where
+    MultiStarkProvingKey<SC>: Serialize,
+    MultiStarkVerifyingKey<SC>: Serialize,
+{
+    let mut writer = BufWriter::new(File::create(path)?);
+    bincode::serialize_into(&mut writer, params)?;
+    bincode::serialize_into(&mut writer, &fingerprint(&pk.get_vk()))?;
+    bincode::serialize_into(&mut writer, pk)
+}
+
+/// Loads proving key saved by [`save_pk`], the parameters are compared before
+/// the key is deserialized.
+pub fn load_pk<SC: StarkGenericConfig>(
+    path: impl AsRef<Path>,
+    params: &KeyParameters,
+) -> Result<MultiStarkProvingKey<SC>, LoadError>
+where
+    MultiStarkProvingKey<SC>: DeserializeOwned,
+    MultiStarkVerifyingKey<SC>: Serialize,
+{
+    let mut reader = BufReader::new(File::open(path).map_err(bincode::Error::from)?);
+    let found: KeyParameters = bincode::deserialize_from(&mut reader)?;
+    if found != *params {
+        return Err(LoadError::ParametersMismatch {
+            expected: Box::new(params.clone()),
+            found: Box::new(found),
+        });
+    }
+    let vk_fingerprint: Fingerprint = bincode::deserialize_from(&mut reader)?;
+    let pk: MultiStarkProvingKey<SC> = bincode::deserialize_from(&mut reader)?;
+    if fingerprint(&pk.get_vk()) != vk_fingerprint {
+        return Err(LoadError::FingerprintMismatch);
+    }
+    Ok(pk)
+}
@@ -1,5 +1,10 @@
 pub mod engine;
 pub mod field;
+pub mod key;
+pub mod report;
+pub mod security;
+pub mod trace;
+pub mod validation;
 
 /// A generic trait for types that can be borrowed from a `[T]` slice.
 pub trait AlignBorrow<T>: Sized {
@@ -50,6 +55,7 @@ macro_rules! zip {
     }};
 }
 
+#[cfg(feature = "prover")]
 macro_rules! par_zip {
     ($first:expr $(, $rest:expr)* $(,)*) => {{
         use p3_maybe_rayon::prelude::*;
@@ -57,4 +63,6 @@ macro_rules! par_zip {
     }};
 }
 
-pub(crate) use {hash_sig_verifier::concat_array, par_zip, zip};
+#[cfg(feature = "prover")]
+pub(crate) use par_zip;
+pub(crate) use {hash_sig_verifier::concat_array, zip};
@@ -0,0 +1,43 @@
+use crate::util::trace::TraceHeight;
+use core::iter::zip;
+use openvm_stark_backend::{config::StarkGenericConfig, keygen::types::MultiStarkProvingKey};
+use serde::Serialize;
+
+/// Cost and constraint statistics of an AIR.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct AirReport {
+    pub name: String,
+    pub width: usize,
+    /// Padded height of the trace.
+    pub height: usize,
+    /// Rows not used by witness but for padding to power of two.
+    pub padding_rows: usize,
+    pub max_constraint_degree: usize,
+    pub num_constraints: usize,
+    pub num_interactions: usize,
+    pub num_public_values: usize,
+}
+
+/// Returns report of each AIR in `pk` with trace of `heights` in the same
+/// order.
+pub fn air_reports<SC: StarkGenericConfig>(
+    pk: &MultiStarkProvingKey<SC>,
+    heights: &[TraceHeight],
+) -> Vec<AirReport> {
+    assert_eq!(pk.per_air.len(), heights.len());
+    zip(&pk.per_air, heights)
+        .map(|(pk, height)| {
+            let constraints = &pk.vk.symbolic_constraints;
+            AirReport {
+                name: pk.air_name.clone(),
+                width: pk.vk.params.width.main_width(),
+                height: height.height,
+                padding_rows: height.padding_rows(),
+                max_constraint_degree: constraints.max_constraint_degree(),
+                num_constraints: constraints.constraints.len(),
+                num_interactions: constraints.interactions.len(),
+                num_public_values: pk.vk.params.num_public_values,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,135 @@
+use core::{cmp::min, fmt};
+use p3_field::{ExtensionField, PrimeField32};
+
+/// Targeted security bits of proofs.
+pub const SECURITY_BITS: usize = 128;
+
+/// Estimated security bits of a proof, which is the minimum of
+/// [`SecurityReport::fri_bits`] and [`SecurityReport::challenge_bits`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SecurityReport {
+    /// Bits from FRI queries and proof of work, where each query contributes
+    /// `log_blowup / 2` bits as in the unique decoding regime.
+    pub fri_bits: usize,
+    /// Bits from sampling challenges in the extension field, which is bits of
+    /// the extension field minus `log_max_height + log_blowup` lost to degree
+    /// of polynomials checked by DEEP and LogUp.
+    pub challenge_bits: usize,
+}
+
+impl SecurityReport {
+    pub fn new<F, E>(
+        log_blowup: usize,
+        num_queries: usize,
+        proof_of_work_bits: usize,
+        log_max_height: usize,
+    ) -> Self
+    where
+        F: PrimeField32,
+        E: ExtensionField<F>,
+    {
+        Self {
+            fri_bits: num_queries * log_blowup / 2 + proof_of_work_bits,
+            challenge_bits: extension_field_bits::<F, E>()
+                .saturating_sub(log_max_height + log_blowup),
+        }
+    }
+
+    pub const fn bits(&self) -> usize {
+        min(self.fri_bits, self.challenge_bits)
+    }
+
+    pub const fn is_secure(&self) -> bool {
+        self.bits() >= SECURITY_BITS
+    }
+
+    /// Returns `self` if it reaches [`SECURITY_BITS`], or an error otherwise.
+    pub const fn check(self) -> Result<Self, InsufficientSecurity> {
+        if self.is_secure() {
+            Ok(self)
+        } else {
+            Err(InsufficientSecurity(self))
+        }
+    }
+}
+
+/// Configuration whose estimated security is below [`SECURITY_BITS`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InsufficientSecurity(pub SecurityReport);
+
+impl fmt::Display for InsufficientSecurity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "estimated security is {} bits (fri: {}, challenge: {}), expected at least {SECURITY_BITS}",
+            self.0.bits(),
+            self.0.fri_bits,
+            self.0.challenge_bits,
+        )
+    }
+}
+
+impl std::error::Error for InsufficientSecurity {}
+
+/// Returns number of FRI queries needed to reach [`SECURITY_BITS`].
+pub const fn num_queries(log_blowup: usize, proof_of_work_bits: usize) -> usize {
+    (2 * SECURITY_BITS.saturating_sub(proof_of_work_bits)).div_ceil(log_blowup)
+}
+
+/// Returns lower bound of bits of extension field `E`.
+pub fn extension_field_bits<F, E>() -> usize
+where
+    F: PrimeField32,
+    E: ExtensionField<F>,
+{
+    E::D * F::ORDER_U32.ilog2() as usize
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        poseidon2::{baby_bear, koala_bear},
+        util::security::{
+            extension_field_bits, num_queries, InsufficientSecurity, SecurityReport, SECURITY_BITS,
+        },
+    };
+
+    #[test]
+    fn fri_bits() {
+        for log_blowup in 1..4 {
+            for proof_of_work_bits in [0, 16, 20] {
+                let num_queries = num_queries(log_blowup, proof_of_work_bits);
+                let report = SecurityReport::new::<koala_bear::F, koala_bear::E>(
+                    log_blowup,
+                    num_queries,
+                    proof_of_work_bits,
+                    0,
+                );
+                assert!(report.fri_bits >= SECURITY_BITS);
+            }
+        }
+    }
+
+    #[test]
+    fn challenge_bits() {
+        type KoalaBearE4 = p3_field::extension::BinomialExtensionField<koala_bear::F, 4>;
+        assert_eq!(extension_field_bits::<baby_bear::F, baby_bear::E>(), 240);
+        assert_eq!(extension_field_bits::<koala_bear::F, koala_bear::E>(), 240);
+        let report = SecurityReport::new::<baby_bear::F, baby_bear::E>(1, 256, 0, 20);
+        assert_eq!(report.challenge_bits, 219);
+        assert_eq!(report.check(), Ok(report));
+        let report = SecurityReport::new::<koala_bear::F, koala_bear::E>(1, 256, 0, 20);
+        assert_eq!(report.challenge_bits, 219);
+        assert_eq!(report.check(), Ok(report));
+        let report = SecurityReport::new::<koala_bear::F, KoalaBearE4>(1, 256, 0, 20);
+        assert_eq!(report.challenge_bits, 99);
+        assert_eq!(report.check(), Err(InsufficientSecurity(report)));
+    }
+
+    #[test]
+    fn insufficient_fri_bits() {
+        let report = SecurityReport::new::<koala_bear::F, koala_bear::E>(1, 128, 0, 20);
+        assert_eq!(report.fri_bits, 64);
+        assert_eq!(report.check(), Err(InsufficientSecurity(report)));
+    }
+}
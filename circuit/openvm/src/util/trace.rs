@@ -0,0 +1,92 @@
+use p3_matrix::dense::RowMajorMatrix;
+use serde::Serialize;
+
+/// Trace allocated with `extra_capacity_bits` headroom but not yet
+/// initialized, so rows can be generated in chunks before it's turned into a
+/// [`RowMajorMatrix`].
+#[derive(Debug)]
+pub struct UninitTrace<F> {
+    vec: Vec<F>,
+    width: usize,
+    height: usize,
+}
+
+impl<F> UninitTrace<F> {
+    pub fn new(width: usize, height: usize, extra_capacity_bits: usize) -> Self {
+        Self {
+            vec: Vec::with_capacity((width * height) << extra_capacity_bits),
+            width,
+            height,
+        }
+    }
+
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    pub const fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns all rows of the trace as `R`.
+    ///
+    /// # Safety
+    ///
+    /// `R` should be a `#[repr(C)]` struct consisting of exactly `width`
+    /// values of `MaybeUninit<F>`.
+    pub unsafe fn rows_mut<R>(&mut self) -> &mut [R] {
+        self.row_parts_mut(1)
+    }
+
+    /// Returns all rows of the trace as `R`, each row consisting of `parts`
+    /// consecutive `R`s.
+    ///
+    /// # Safety
+    ///
+    /// `R` should be a `#[repr(C)]` struct consisting of exactly
+    /// `width / parts` values of `MaybeUninit<F>`.
+    pub unsafe fn row_parts_mut<R>(&mut self, parts: usize) -> &mut [R] {
+        let size = self.width * self.height;
+        let (prefix, rows, suffix) = self.vec.spare_capacity_mut()[..size].align_to_mut::<R>();
+        assert!(prefix.is_empty(), "Alignment should match");
+        assert!(suffix.is_empty(), "Alignment should match");
+        assert_eq!(rows.len(), self.height * parts);
+        rows
+    }
+}
+
+impl<F: Clone + Send + Sync> UninitTrace<F> {
+    /// Returns the trace as [`RowMajorMatrix`].
+    ///
+    /// # Safety
+    ///
+    /// All rows should have been initialized via [`UninitTrace::rows_mut`].
+    pub unsafe fn assume_init(mut self) -> RowMajorMatrix<F> {
+        self.vec.set_len(self.width * self.height);
+        RowMajorMatrix::new(self.vec, self.width)
+    }
+}
+
+/// Number of rows used by witness and the padded height of a trace.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct TraceHeight {
+    pub num_rows: usize,
+    pub height: usize,
+}
+
+impl TraceHeight {
+    pub const fn new(num_rows: usize, height: usize) -> Self {
+        debug_assert!(num_rows <= height);
+        Self { num_rows, height }
+    }
+
+    pub const fn padding_rows(&self) -> usize {
+        self.height - self.num_rows
+    }
+}
+
+/// Returns bytes of the trace of `width` and `height` allocated with
+/// `extra_capacity_bits` headroom.
+pub const fn trace_size<F>(width: usize, height: usize, extra_capacity_bits: usize) -> usize {
+    ((width * height) << extra_capacity_bits) * size_of::<F>()
+}
@@ -0,0 +1,130 @@
+use core::fmt;
+use hash_sig_verifier::{
+    instantiation::Instantiation, PublicKey, Signature, VerificationInput, MSG_LEN,
+};
+use p3_maybe_rayon::prelude::*;
+
+/// Reason of a signature failing to verify.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InvalidReason {
+    /// Chunks of the message hash don't sum to the target sum.
+    TargetSum,
+    /// Merkle root computed from the signature doesn't match the public key's.
+    MerkleRoot,
+    /// Signer is the same as of an earlier participating signature.
+    DuplicateSigner,
+}
+
+impl fmt::Display for InvalidReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TargetSum => write!(f, "unmatched target sum"),
+            Self::MerkleRoot => write!(f, "unmatched merkle root"),
+            Self::DuplicateSigner => write!(f, "duplicate signer"),
+        }
+    }
+}
+
+/// Indices and reasons of invalid signatures, in ascending order of index.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidationReport {
+    pub invalid: Vec<(usize, InvalidReason)>,
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} invalid signatures:", self.invalid.len())?;
+        self.invalid
+            .iter()
+            .try_for_each(|(idx, reason)| write!(f, " #{idx} ({reason})"))
+    }
+}
+
+impl std::error::Error for ValidationReport {}
+
+/// Verifies signatures in `vi` flagged in `participation` natively, so invalid
+/// ones are reported before generating an unprovable witness.
+pub fn validate<I: Instantiation<NUM_CHUNKS>, const NUM_CHUNKS: usize>(
+    vi: &VerificationInput<I, NUM_CHUNKS>,
+    participation: &[bool],
+) -> Result<(), ValidationReport> {
+    debug_assert_eq!(vi.pairs.len(), participation.len());
+    let invalid = vi
+        .pairs
+        .par_iter()
+        .zip(participation)
+        .enumerate()
+        .filter(|(_, (_, is_participating))| **is_participating)
+        .filter_map(|(idx, ((pk, sig), _))| {
+            validate_pair(vi.epoch, vi.msg, pk, sig)
+                .err()
+                .map(|reason| (idx, reason))
+        })
+        .collect::<Vec<_>>();
+    if invalid.is_empty() {
+        Ok(())
+    } else {
+        Err(ValidationReport { invalid })
+    }
+}
+
+/// Verifies by [`Instantiation::verify`], and tells the reason on failure.
+fn validate_pair<I: Instantiation<NUM_CHUNKS>, const NUM_CHUNKS: usize>(
+    epoch: u32,
+    msg: [u8; MSG_LEN],
+    pk: &PublicKey<I, NUM_CHUNKS>,
+    sig: &Signature<I, NUM_CHUNKS>,
+) -> Result<(), InvalidReason> {
+    I::verify(epoch, msg, *pk, *sig).map_err(|_| {
+        if I::encode(epoch, msg, pk.parameter, sig.rho).is_err() {
+            InvalidReason::TargetSum
+        } else {
+            InvalidReason::MerkleRoot
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        poseidon2::{
+            hash_sig::{test::mock_vi, Poseidon2TargetSum, NUM_CHUNKS},
+            F,
+        },
+        util::validation::{validate, InvalidReason, ValidationReport},
+    };
+    use hash_sig_verifier::instantiation::Instantiation;
+    use p3_field::FieldAlgebra;
+
+    #[test]
+    fn validation() {
+        let mut vi = mock_vi(8);
+        let participation = (0..8).map(|idx| idx != 5).collect::<Vec<_>>();
+        assert_eq!(validate(&vi, &participation), Ok(()));
+
+        vi.pairs[5].1.merkle_siblings[0][0] += F::ONE;
+        assert_eq!(validate(&vi, &participation), Ok(()));
+
+        vi.pairs[2].1.merkle_siblings[0][0] += F::ONE;
+        let (pk, sig) = &mut vi.pairs[6];
+        while <Poseidon2TargetSum as Instantiation<NUM_CHUNKS>>::encode(
+            vi.epoch,
+            vi.msg,
+            pk.parameter,
+            sig.rho,
+        )
+        .is_ok()
+        {
+            sig.rho[0] += F::ONE;
+        }
+        assert_eq!(
+            validate(&vi, &participation),
+            Err(ValidationReport {
+                invalid: vec![
+                    (2, InvalidReason::MerkleRoot),
+                    (6, InvalidReason::TargetSum)
+                ]
+            })
+        );
+    }
+}
@@ -133,22 +133,22 @@ impl<P: Sha3Digest> Instantiation<NUM_CHUNKS> for Sha3TargetSum<P> {
     }
 }
 
-fn encode_tweak_chain(epoch: u32, i: u16, k: u16) -> [u8; 9] {
+pub fn encode_tweak_chain(epoch: u32, i: u16, k: u16) -> [u8; 9] {
     const SEP: u8 = 0x00;
     concat_array![[SEP], epoch.to_be_bytes(), i.to_be_bytes(), k.to_be_bytes()]
 }
 
-fn encode_tweak_merkle_tree(l: u8, i: u32) -> [u8; 6] {
+pub fn encode_tweak_merkle_tree(l: u8, i: u32) -> [u8; 6] {
     const SEP: u8 = 0x01;
     concat_array![[SEP, l], i.to_be_bytes()]
 }
 
-fn encode_tweak_msg(epoch: u32) -> [u8; 5] {
+pub fn encode_tweak_msg(epoch: u32) -> [u8; 5] {
     const SEP: u8 = 0x02;
     concat_array![[SEP], epoch.to_le_bytes()]
 }
 
-fn msg_hash_to_chunks(bytes: [u8; MSG_HASH_LEN]) -> [u16; NUM_CHUNKS] {
+pub fn msg_hash_to_chunks(bytes: [u8; MSG_HASH_LEN]) -> [u16; NUM_CHUNKS] {
     const MASK: u8 = ((1 << CHUNK_SIZE) - 1) as u8;
     from_fn(|i| ((bytes[(i * CHUNK_SIZE) / 8] >> ((i * CHUNK_SIZE) % 8)) & MASK).into())
 }